@@ -0,0 +1,50 @@
+use bids::{Bid, BidSet};
+use cards::{Card, Suit};
+
+
+/// Anything a player can do during a game
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Action {
+    /// A bid, with its suit for bids other than pass/counter/double counter
+    Bid(Bid, Option<Suit>),
+    /// Playing a card in the current trick
+    Play(Card),
+    /// Taking a suit as trump during the taking phase of classic belote, `None` to refuse
+    Take(Option<Suit>),
+}
+
+/// Expands the available bids into every action possible, one per allowed suit for the bids
+/// that need one. The order is stable: bids in increasing order, suits in `Suit::iterator` order
+pub(crate) fn bid_actions(bids: BidSet, suits: &[Suit]) -> Vec<Action> {
+    let mut actions = vec![];
+    for bid in bids.iter() {
+        if bid.requires_suit() {
+            for suit in suits {
+                actions.push(Action::Bid(bid, Some(*suit)));
+            }
+        } else {
+            actions.push(Action::Bid(bid, None));
+        }
+    }
+    actions
+}
+
+/// Every choice of the taking phase: refusing first, then taking each suit in the given order
+pub(crate) fn take_actions(suits: &[Suit]) -> Vec<Action> {
+    let mut actions = vec![Action::Take(None)];
+    actions.extend(suits.iter().map(|s| Action::Take(Some(*s))));
+    actions
+}
+
+/// Every action that can ever be taken, in a fixed order: all the bids in the order of
+/// `bid_actions`, then the 32 cards in the order of `Card::ALL` and the choices of the
+/// taking phase of classic belote in the order of `take_actions`.
+/// Useful when a fixed number of possible actions is needed, eg for machine learning
+pub fn action_space() -> Vec<Action> {
+    let bids: BidSet = Bid::iterator().cloned().collect();
+    let suits: Vec<Suit> = Suit::iterator().cloned().collect();
+    let mut actions = bid_actions(bids, &suits);
+    actions.extend(Card::ALL.iter().cloned().map(Action::Play));
+    actions.extend(take_actions(&suits));
+    actions
+}
@@ -0,0 +1,283 @@
+//! Bidding advice following the usual contrée conventions on top of the hand evaluation.
+//! Every club plays its own system so each convention can be tuned or turned off.
+
+use bids::Bid;
+use cards::{Card, Rank, Suit};
+use evaluation::{evaluate_hand, TrumpMode};
+use view::PlayerView;
+
+
+/// The bidding system of a table
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Conventions {
+    /// Announce a suit of at least that many cards including its Jack or Nine at 80,
+    /// or 90 with one more card, even if the hand alone isn't worth it. `None` to never do it
+    pub long_suit: Option<usize>,
+    /// How much to raise the bid of the partner for each Jack or Nine of their suit
+    /// and each ace outside of it. 0 to never support the partner
+    pub support_raise: usize,
+    /// How much higher to bid when holding the King and Queen of trump
+    pub belote_jump: usize,
+    /// Counter the opponents when holding at least that many sure tricks with their trump.
+    /// `None` to never counter
+    pub counter_sure_tricks: Option<usize>,
+}
+
+impl Default for Conventions {
+    fn default() -> Conventions {
+        Conventions {
+            long_suit: Some(5),
+            support_raise: 10,
+            belote_jump: 20,
+            counter_sure_tricks: Some(5),
+        }
+    }
+}
+
+/// Which convention the advice comes from
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Convention {
+    /// The hand is worth that bid on its own
+    Natural,
+    LongSuit,
+    SupportPartner,
+    BeloteJump,
+    Counter,
+    Pass,
+}
+
+/// A suggested bid and the convention behind it
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Advice {
+    pub bid: Bid,
+    pub suit: Option<Suit>,
+    pub convention: Convention,
+}
+
+/// Suggests bids according to a set of conventions
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct BiddingAdvisor {
+    pub conventions: Conventions,
+}
+
+fn has_master(hand: &[Card], suit: Suit) -> bool {
+    hand.contains(&Card::new(suit, Rank::Jack)) || hand.contains(&Card::new(suit, Rank::Nine))
+}
+
+impl BiddingAdvisor {
+    pub fn new(conventions: Conventions) -> BiddingAdvisor {
+        BiddingAdvisor { conventions }
+    }
+
+    /// The value the hand is worth with that trump and the convention that gives it
+    fn own_value(&self, hand: &[Card], suit: Suit, partner_bid: Option<(Bid, Suit)>) -> Option<(usize, Convention)> {
+        let mut candidates = vec![];
+
+        if let Some(bid) = evaluate_hand(hand, TrumpMode::Suit(suit)).max_bid {
+            let belote = hand.contains(&Card::new(suit, Rank::King)) && hand.contains(&Card::new(suit, Rank::Queen));
+            if belote && self.conventions.belote_jump > 0 {
+                candidates.push((bid.value() + self.conventions.belote_jump, Convention::BeloteJump));
+            } else {
+                candidates.push((bid.value(), Convention::Natural));
+            }
+        }
+
+        if let Some(length) = self.conventions.long_suit {
+            let count = hand.iter().filter(|c| c.suit == suit).count();
+            if count >= length && has_master(hand, suit) {
+                let value = if count > length { 90 } else { 80 };
+                candidates.push((value, Convention::LongSuit));
+            }
+        }
+
+        if let Some((bid, partner_suit)) = partner_bid {
+            if partner_suit == suit && self.conventions.support_raise > 0 {
+                let masters = [Rank::Jack, Rank::Nine]
+                    .iter()
+                    .filter(|r| hand.contains(&Card::new(suit, **r)))
+                    .count();
+                let aces = hand.iter().filter(|c| c.suit != suit && c.rank == Rank::Ace).count();
+                if masters + aces > 0 {
+                    let value = bid.value() + self.conventions.support_raise * (masters + aces);
+                    candidates.push((value, Convention::SupportPartner));
+                }
+            }
+        }
+
+        candidates.into_iter().max_by_key(|&(value, _)| value)
+    }
+
+    /// The bid to make for the player of the view, who must be the one to bid
+    pub fn advise(&self, view: &PlayerView) -> Advice {
+        let pass = Advice { bid: Bid::Pass, suit: None, convention: Convention::Pass };
+        let team = view.player.team();
+        let last_suit_bid = |ours: bool| {
+            view.bids
+                .iter()
+                .rev()
+                .find(|&&(p, b, _)| (p.team() == team) == ours && p != view.player && b.requires_suit())
+                .map(|&(_, b, s)| (b, s.unwrap()))
+        };
+        let partner_bid = last_suit_bid(true);
+        let opponent_bid = last_suit_bid(false);
+
+        if let (Some(sure_tricks), Some((_, suit))) = (self.conventions.counter_sure_tricks, opponent_bid) {
+            let opponents_lead = view.bids
+                .iter()
+                .rev()
+                .find(|&&(_, b, _)| b.requires_suit())
+                .map_or(false, |&(p, _, _)| p.team() != team);
+            if opponents_lead
+                && view.available_bids.contains(Bid::Counter)
+                && evaluate_hand(&view.hand, TrumpMode::Suit(suit)).sure_tricks >= sure_tricks
+            {
+                return Advice { bid: Bid::Counter, suit: None, convention: Convention::Counter };
+            }
+        }
+
+        let mut best: Option<Advice> = None;
+        for suit in &view.biddable_suits() {
+            let (value, convention) = match self.own_value(&view.hand, *suit, partner_bid) {
+                Some(v) => v,
+                None => continue,
+            };
+            let bid = view.available_bids
+                .iter()
+                .filter(|b| *b != Bid::Capot && b.requires_suit() && b.value() <= value)
+                .max();
+            if let Some(bid) = bid {
+                if best.map_or(true, |b| bid > b.bid) {
+                    best = Some(Advice { bid, suit: Some(*suit), convention });
+                }
+            }
+        }
+        best.unwrap_or(pass)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::Game;
+    use players::Player;
+    use rules::RuleSet;
+
+    fn card(suit: Suit, rank: Rank) -> Card {
+        Card::new(suit, rank)
+    }
+
+    fn view(hand: Vec<Card>, bids: &[(Player, Bid, Option<Suit>)]) -> PlayerView {
+        let mut game = Game::default();
+        game.new_round();
+        for &(player, bid, suit) in bids {
+            game.bid(player, bid, suit).unwrap();
+        }
+        let player = game.current_player().unwrap();
+        let mut view = game.player_view(player);
+        view.hand = hand.into();
+        view
+    }
+
+    fn weak_hand() -> Vec<Card> {
+        vec![
+            card(Suit::Hearts, Rank::Seven),
+            card(Suit::Hearts, Rank::Eight),
+            card(Suit::Spades, Rank::Seven),
+            card(Suit::Spades, Rank::Ace),
+            card(Suit::Clubs, Rank::Eight),
+            card(Suit::Clubs, Rank::Nine),
+            card(Suit::Diamonds, Rank::Eight),
+            card(Suit::Diamonds, Rank::Ace),
+        ]
+    }
+
+    #[test]
+    fn can_announce_long_suit() {
+        let hand = vec![
+            card(Suit::Hearts, Rank::Nine),
+            card(Suit::Hearts, Rank::Seven),
+            card(Suit::Hearts, Rank::Eight),
+            card(Suit::Hearts, Rank::Queen),
+            card(Suit::Hearts, Rank::Ten),
+            card(Suit::Spades, Rank::Seven),
+            card(Suit::Clubs, Rank::Eight),
+            card(Suit::Diamonds, Rank::Eight),
+        ];
+        let advice = BiddingAdvisor::default().advise(&view(hand.clone(), &[]));
+        assert_eq!(advice, Advice { bid: Bid::Eighty, suit: Some(Suit::Hearts), convention: Convention::LongSuit });
+
+        let advisor = BiddingAdvisor::new(Conventions { long_suit: None, ..Conventions::default() });
+        assert_eq!(advisor.advise(&view(hand, &[])).bid, Bid::Pass);
+    }
+
+    #[test]
+    fn can_support_partner() {
+        // North opened at 80 hearts, South holds two aces
+        let bids = [
+            (Player::South, Bid::Pass, None),
+            (Player::West, Bid::Pass, None),
+            (Player::North, Bid::Eighty, Some(Suit::Hearts)),
+            (Player::East, Bid::Pass, None),
+        ];
+        let advice = BiddingAdvisor::default().advise(&view(weak_hand(), &bids));
+        assert_eq!(advice, Advice { bid: Bid::Hundred, suit: Some(Suit::Hearts), convention: Convention::SupportPartner });
+
+        let advisor = BiddingAdvisor::new(Conventions { support_raise: 0, ..Conventions::default() });
+        assert_eq!(advisor.advise(&view(weak_hand(), &bids)).bid, Bid::Pass);
+    }
+
+    #[test]
+    fn can_jump_with_belote() {
+        let hand = vec![
+            card(Suit::Hearts, Rank::Jack),
+            card(Suit::Hearts, Rank::King),
+            card(Suit::Hearts, Rank::Queen),
+            card(Suit::Spades, Rank::Seven),
+            card(Suit::Spades, Rank::Eight),
+            card(Suit::Clubs, Rank::Eight),
+            card(Suit::Clubs, Rank::Seven),
+            card(Suit::Diamonds, Rank::Eight),
+        ];
+        // 20 + 4 + 3 + 30 = 57: not worth a bid, and the belote only raises a bid
+        assert_eq!(BiddingAdvisor::default().advise(&view(hand.clone(), &[])).bid, Bid::Pass);
+
+        let mut stronger = hand;
+        stronger[3] = card(Suit::Spades, Rank::Ace);
+        stronger[4] = card(Suit::Hearts, Rank::Nine);
+        // 20 + 14 + 4 + 3 + 40 + 10 = 91, 110 with the belote
+        let advice = BiddingAdvisor::default().advise(&view(stronger, &[]));
+        assert_eq!(advice, Advice { bid: Bid::HundredTen, suit: Some(Suit::Hearts), convention: Convention::BeloteJump });
+    }
+
+    #[test]
+    fn can_counter_opponents() {
+        let hand = vec![
+            card(Suit::Hearts, Rank::Jack),
+            card(Suit::Hearts, Rank::Nine),
+            card(Suit::Hearts, Rank::Ace),
+            card(Suit::Spades, Rank::Ace),
+            card(Suit::Spades, Rank::Ten),
+            card(Suit::Clubs, Rank::Ace),
+            card(Suit::Clubs, Rank::Ten),
+            card(Suit::Diamonds, Rank::Eight),
+        ];
+        let bids = [(Player::South, Bid::Eighty, Some(Suit::Hearts))];
+        let advice = BiddingAdvisor::default().advise(&view(hand, &bids));
+        assert_eq!(advice, Advice { bid: Bid::Counter, suit: None, convention: Convention::Counter });
+    }
+
+    #[test]
+    fn only_advises_allowed_trumps() {
+        let rules = RuleSet { allowed_trumps: vec![TrumpMode::Suit(Suit::Clubs)], ..RuleSet::default() };
+        let mut game = Game::from_seed(rules, 5);
+        game.new_round();
+        for _ in 0..4 {
+            let player = game.current_player().unwrap();
+            let view = game.player_view(player);
+            let advice = BiddingAdvisor::default().advise(&view);
+            assert!(advice.suit.map_or(true, |s| s == Suit::Clubs));
+            game.bid(player, advice.bid, advice.suit).unwrap();
+        }
+    }
+}
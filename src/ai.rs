@@ -0,0 +1,258 @@
+//! Information-Set Monte Carlo Tree Search (ISMCTS) for the card play.
+//!
+//! A player doesn't know the hands of the others so we can't search the game tree directly.
+//! Instead, every iteration samples hands for the other players that are consistent with
+//! everything that has been played so far and walks a single tree shared by all those samples,
+//! only considering the cards that are playable in the current sample.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rand;
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+use bids::{Bid, Contract};
+use bots::HeuristicBot;
+use cards::{Card, Suit};
+use players::{Player, Team};
+use round::Round;
+use rules::RuleSet;
+use sampling::{played_cards, sample_round};
+use strategy::Strategy;
+use view::PlayerView;
+
+
+/// Exploration constant of the UCB formula, rewards being between 0 and 1
+const EXPLORATION: f64 = 0.7;
+
+/// How long the bot thinks before playing a card
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Budget {
+    /// A fixed number of search iterations, for reproducible results
+    Iterations(usize),
+    /// As many iterations as possible in the given time
+    Time(Duration),
+}
+
+/// A node of the search tree: the card played to get there and its statistics
+#[derive(Debug, Clone)]
+struct Node {
+    /// The card played and who played it, `None` for the root
+    action: Option<(Player, Card)>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    visits: usize,
+    /// How many times the node was playable when its parent was selected
+    availability: usize,
+    /// Sum of the rewards for the team of the player of the card
+    reward: f64,
+}
+
+impl Node {
+    fn new(action: Option<(Player, Card)>, parent: Option<usize>) -> Node {
+        Node { action, parent, children: vec![], visits: 0, availability: 0, reward: 0.0 }
+    }
+
+    fn ucb(&self) -> f64 {
+        let visits = self.visits as f64;
+        self.reward / visits + EXPLORATION * ((self.availability as f64).ln() / visits).sqrt()
+    }
+}
+
+/// A bot searching the card play with ISMCTS.
+/// Bidding is left to the `HeuristicBot` as the search only covers the play of the cards.
+#[derive(Debug, Clone)]
+pub struct IsmctsBot {
+    budget: Budget,
+    rules: RuleSet,
+    rng: Isaac64Rng,
+}
+
+impl IsmctsBot {
+    /// `rules` need to be the ones of the game played so the bot knows which cards can be played
+    pub fn new(budget: Budget, rules: RuleSet) -> IsmctsBot {
+        IsmctsBot::from_seed(budget, rules, rand::random())
+    }
+
+    /// A bot making the same choices every time for the same seed and the same views,
+    /// as long as the budget is a number of iterations
+    pub fn from_seed(budget: Budget, rules: RuleSet, seed: u64) -> IsmctsBot {
+        IsmctsBot { budget, rules, rng: Isaac64Rng::from_seed(&[seed]) }
+    }
+
+    /// Runs the search and returns how many times each card was visited from the root
+    fn search(&mut self, view: &PlayerView, contract: &Contract) -> HashMap<Card, usize> {
+        let mut nodes = vec![Node::new(None, None)];
+        // We are the one to play: if nobody played yet we lead the first trick
+        let leader = played_cards(view).first().map_or(view.player, |&(player, _)| player);
+        let start = Instant::now();
+        let mut iterations = 0;
+
+        loop {
+            match self.budget {
+                Budget::Iterations(n) if iterations >= n => break,
+                Budget::Time(duration) if iterations > 0 && start.elapsed() >= duration => break,
+                _ => (),
+            }
+            iterations += 1;
+
+            let mut round = match sample_round(view, contract, leader, &self.rules, &mut self.rng) {
+                Some(round) => round,
+                None => continue,
+            };
+
+            // Selection and expansion
+            let mut current = 0;
+            while !round.is_over() {
+                let player = round.next_player();
+                let playable = round.playable_cards(player, &self.rules);
+                let children: Vec<usize> = nodes[current]
+                    .children
+                    .iter()
+                    .cloned()
+                    .filter(|&c| playable.contains(&nodes[c].action.unwrap().1))
+                    .collect();
+                for &child in &children {
+                    nodes[child].availability += 1;
+                }
+
+                let untried: Vec<Card> = playable
+                    .iter()
+                    .cloned()
+                    .filter(|card| !children.iter().any(|&c| nodes[c].action.unwrap().1 == *card))
+                    .collect();
+                if let Some(&card) = self.rng.choose(&untried) {
+                    let mut node = Node::new(Some((player, card)), Some(current));
+                    node.availability = 1;
+                    nodes.push(node);
+                    let index = nodes.len() - 1;
+                    nodes[current].children.push(index);
+                    round.play(player, card, &self.rules).unwrap();
+                    current = index;
+                    break;
+                }
+
+                let best = children
+                    .iter()
+                    .cloned()
+                    .max_by(|&a, &b| nodes[a].ucb().partial_cmp(&nodes[b].ucb()).unwrap())
+                    .unwrap();
+                round.play(player, nodes[best].action.unwrap().1, &self.rules).unwrap();
+                current = best;
+            }
+
+            // Random playout until the end of the round
+            while !round.is_over() {
+                let player = round.next_player();
+                let card = *self.rng.choose(&round.playable_cards(player, &self.rules)).unwrap();
+                round.play(player, card, &self.rules).unwrap();
+            }
+            round.calculate_points(&self.rules);
+            let rewards = rewards(&round);
+
+            // Backpropagation
+            let mut node = Some(current);
+            while let Some(index) = node {
+                nodes[index].visits += 1;
+                if let Some((player, _)) = nodes[index].action {
+                    nodes[index].reward += rewards[&player.team()];
+                }
+                node = nodes[index].parent;
+            }
+        }
+
+        nodes[0]
+            .children
+            .iter()
+            .map(|&c| (nodes[c].action.unwrap().1, nodes[c].visits))
+            .collect()
+    }
+}
+
+impl Strategy for IsmctsBot {
+    fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>) {
+        HeuristicBot::new().choose_bid(view)
+    }
+
+    fn choose_card(&mut self, view: &PlayerView) -> Card {
+        let contract = match view.contract {
+            Some(ref contract) => contract.clone(),
+            None => return view.playable_cards[0],
+        };
+        if view.playable_cards.len() == 1 {
+            return view.playable_cards[0];
+        }
+
+        let visits = self.search(view, &contract);
+        let best = view.playable_cards
+            .iter()
+            .cloned()
+            .max_by_key(|card| visits.get(card).cloned().unwrap_or(0));
+        match best {
+            Some(card) if !visits.is_empty() => card,
+            // We couldn't find any hands consistent with the cards played
+            _ => HeuristicBot::new().choose_card(view),
+        }
+    }
+}
+
+/// Share of the round score won by each team, between 0 and 1
+fn rewards(round: &Round) -> HashMap<Team, f64> {
+    let sn = round.scores[Team::SouthNorth] as f64;
+    let ew = round.scores[Team::EastWest] as f64;
+    let total = if sn + ew > 0.0 { sn + ew } else { 1.0 };
+    let mut rewards = HashMap::new();
+    rewards.insert(Team::SouthNorth, sn / total);
+    rewards.insert(Team::EastWest, ew / total);
+    rewards
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::RandomBot;
+    use game::Game;
+    use strategy::run_game;
+
+    #[test]
+    fn ismcts_bot_is_reproducible() {
+        let mut game = Game::from_seed(RuleSet::default(), 1);
+        game.new_round();
+        while game.is_bidding() {
+            let player = game.current_player().unwrap();
+            let bid = if game.player_view(player).bids.is_empty() {
+                (Bid::Eighty, Some(Suit::Hearts))
+            } else {
+                (Bid::Pass, None)
+            };
+            game.bid(player, bid.0, bid.1).unwrap();
+        }
+        let view = game.player_view(game.current_player().unwrap());
+        let mut a = IsmctsBot::from_seed(Budget::Iterations(100), RuleSet::default(), 42);
+        let mut b = IsmctsBot::from_seed(Budget::Iterations(100), RuleSet::default(), 42);
+        let card = a.choose_card(&view);
+        assert!(view.playable_cards.contains(&card));
+        assert_eq!(card, b.choose_card(&view));
+    }
+
+    #[test]
+    fn ismcts_bot_beats_random_bots() {
+        let mut wins = 0;
+        for seed in 0..6 {
+            let mut game = Game::from_seed(RuleSet::default(), seed);
+            let mut strategies: [Box<dyn Strategy>; 4] = [
+                Box::new(IsmctsBot::from_seed(Budget::Iterations(50), RuleSet::default(), seed)),
+                Box::new(RandomBot::from_seed(seed * 2)),
+                Box::new(IsmctsBot::from_seed(Budget::Iterations(50), RuleSet::default(), seed + 100)),
+                Box::new(RandomBot::from_seed(seed * 2 + 1)),
+            ];
+            if run_game(&mut game, &mut strategies).unwrap() == Team::SouthNorth {
+                wins += 1;
+            }
+        }
+        assert!(wins >= 5);
+    }
+}
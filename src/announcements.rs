@@ -0,0 +1,162 @@
+use std::error;
+use std::fmt;
+
+use cards::{Card, Rank, Suit};
+
+
+/// An announcement made by a player about their hand
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Announcement {
+    /// King and Queen of trump
+    Belote,
+    /// At least 3 consecutive cards of the same suit, ending with `highest`
+    Sequence { suit: Suit, highest: Rank, length: usize },
+    /// The four cards of a rank
+    Square(Rank),
+}
+
+/// Why an announcement is not possible
+#[derive(Debug, Eq, PartialEq)]
+pub enum AnnouncementError {
+    /// The announcement requires a card that is not in the hand
+    MissingCard(Card),
+    /// A sequence must have between 3 and 8 cards and stay within the suit
+    InvalidSequence,
+    /// Squares of 7s and 8s do not exist
+    InvalidSquare(Rank),
+    /// The player already made that announcement in the round
+    AlreadyAnnounced,
+    /// The sequence uses a card of an earlier sequence of the player
+    OverlapsAnnouncement(Announcement),
+    /// Announcements can only be made while the round is being played
+    RoundOver,
+}
+
+impl fmt::Display for AnnouncementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AnnouncementError::MissingCard(card) => {
+                write!(f, "The announcement requires the {} which is not in the hand", card)
+            },
+            AnnouncementError::InvalidSequence => {
+                write!(f, "A sequence must have between 3 and 8 cards and stay within the suit")
+            },
+            AnnouncementError::InvalidSquare(rank) => write!(f, "There is no square of {:?}", rank),
+            AnnouncementError::AlreadyAnnounced => write!(f, "The announcement was already made"),
+            AnnouncementError::OverlapsAnnouncement(other) => {
+                write!(f, "The sequence uses cards of an earlier announcement: {:?}", other)
+            },
+            AnnouncementError::RoundOver => write!(f, "The round is over"),
+        }
+    }
+}
+
+impl error::Error for AnnouncementError {}
+
+impl Announcement {
+    /// How many points the announcement is worth
+    pub fn points(&self) -> usize {
+        match *self {
+            Announcement::Belote => 20,
+            Announcement::Sequence { length, .. } => match length {
+                3 => 20,
+                4 => 50,
+                _ => 100,
+            },
+            Announcement::Square(Rank::Jack) => 200,
+            Announcement::Square(Rank::Nine) => 150,
+            Announcement::Square(_) => 100,
+        }
+    }
+
+    /// All the cards a player needs to hold to make that announcement
+    pub fn cards(&self, trump: Suit) -> Result<Vec<Card>, AnnouncementError> {
+        match *self {
+            Announcement::Belote => Ok(vec![
+                Card::new(trump, Rank::King),
+                Card::new(trump, Rank::Queen),
+            ]),
+            Announcement::Sequence { suit, highest, length } => {
+                let ranks: Vec<Rank> = Rank::iterator().cloned().collect();
+                let end = ranks.iter().position(|r| *r == highest).unwrap() + 1;
+                if length < 3 || length > end {
+                    return Err(AnnouncementError::InvalidSequence);
+                }
+                Ok(ranks[end - length..end].iter().map(|r| Card::new(suit, *r)).collect())
+            },
+            Announcement::Square(rank) => {
+                if rank == Rank::Seven || rank == Rank::Eight {
+                    return Err(AnnouncementError::InvalidSquare(rank));
+                }
+                Ok(Suit::iterator().map(|s| Card::new(*s, rank)).collect())
+            },
+        }
+    }
+
+    /// Checks that the announcement is consistent with the hand of the player
+    pub fn validate(&self, hand: &[Card], trump: Suit) -> Result<(), AnnouncementError> {
+        for card in self.cards(trump)? {
+            if !hand.contains(&card) {
+                return Err(AnnouncementError::MissingCard(card));
+            }
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand() -> Vec<Card> {
+        vec![
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Hearts, Rank::Queen),
+            Card::new(Suit::Hearts, Rank::Jack),
+            Card::new(Suit::Spades, Rank::Jack),
+            Card::new(Suit::Clubs, Rank::Jack),
+            Card::new(Suit::Diamonds, Rank::Jack),
+            Card::new(Suit::Diamonds, Rank::Seven),
+            Card::new(Suit::Spades, Rank::Ace),
+        ]
+    }
+
+    #[test]
+    fn can_validate_belote() {
+        assert!(Announcement::Belote.validate(&hand(), Suit::Hearts).is_ok());
+        assert_eq!(
+            Announcement::Belote.validate(&hand(), Suit::Spades),
+            Err(AnnouncementError::MissingCard(Card::new(Suit::Spades, Rank::King)))
+        );
+    }
+
+    #[test]
+    fn can_validate_sequences() {
+        let tierce = Announcement::Sequence { suit: Suit::Hearts, highest: Rank::King, length: 3 };
+        assert!(tierce.validate(&hand(), Suit::Spades).is_ok());
+        assert_eq!(tierce.points(), 20);
+
+        let quarte = Announcement::Sequence { suit: Suit::Hearts, highest: Rank::King, length: 4 };
+        assert_eq!(
+            quarte.validate(&hand(), Suit::Spades),
+            Err(AnnouncementError::MissingCard(Card::new(Suit::Hearts, Rank::Ten)))
+        );
+
+        let too_short = Announcement::Sequence { suit: Suit::Hearts, highest: Rank::King, length: 2 };
+        assert_eq!(too_short.validate(&hand(), Suit::Spades), Err(AnnouncementError::InvalidSequence));
+        let too_long = Announcement::Sequence { suit: Suit::Hearts, highest: Rank::Eight, length: 3 };
+        assert_eq!(too_long.validate(&hand(), Suit::Spades), Err(AnnouncementError::InvalidSequence));
+    }
+
+    #[test]
+    fn can_validate_squares() {
+        assert!(Announcement::Square(Rank::Jack).validate(&hand(), Suit::Spades).is_ok());
+        assert_eq!(Announcement::Square(Rank::Jack).points(), 200);
+        assert!(Announcement::Square(Rank::Ace).validate(&hand(), Suit::Spades).is_err());
+        assert_eq!(
+            Announcement::Square(Rank::Seven).validate(&hand(), Suit::Spades),
+            Err(AnnouncementError::InvalidSquare(Rank::Seven))
+        );
+    }
+}
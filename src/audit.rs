@@ -0,0 +1,152 @@
+//! A tamper-evident log of everything done in a game. Each record has the hash of the record
+//! before it, the action and the `vectors::state_hash` of the game after the action, and is
+//! hashed itself. Changing, removing or reordering any record breaks the chain, and replaying
+//! the actions from the initial game must give the same states.
+
+use failure::Error;
+
+use actions::Action;
+use game::Game;
+use players::Player;
+use sha256;
+use vectors::{action_code, state_hash};
+
+
+/// One action of the log
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AuditRecord {
+    /// Starts at 1
+    pub seq: usize,
+    /// The hash of the previous record, or the state hash of the initial game for the first one
+    pub previous_hash: String,
+    pub player: Player,
+    pub action: Action,
+    /// The `vectors::state_hash` of the game after the action
+    pub state_hash: String,
+    /// The hash of all the fields above
+    pub hash: String,
+}
+
+fn record_hash(seq: usize, previous_hash: &str, player: Player, action: Action, state_hash: &str) -> String {
+    let data = format!("{}\n{}\n{:?}\n{}\n{}", seq, previous_hash, player, action_code(action), state_hash);
+    sha256::digest(data.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The hash chain of a game, starting from the state of the game when the log was created
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AuditLog {
+    /// The state hash of the game before any action of the log
+    pub initial_hash: String,
+    pub records: Vec<AuditRecord>,
+}
+
+impl AuditLog {
+    /// Starts a log for the game in its current state
+    pub fn new(game: &Game) -> AuditLog {
+        AuditLog { initial_hash: state_hash(game), records: Vec::new() }
+    }
+
+    /// The hash the next record will have to chain to
+    pub fn last_hash(&self) -> &str {
+        self.records.last().map_or(&self.initial_hash, |r| &r.hash)
+    }
+
+    /// Makes the action in the game and adds it to the log.
+    /// Errors if the action is not allowed, in which case nothing is logged
+    pub fn apply(&mut self, game: &mut Game, player: Player, action: Action) -> Result<&AuditRecord, Error> {
+        game.apply(player, action)?;
+        let seq = self.records.len() + 1;
+        let state_hash = state_hash(game);
+        let hash = record_hash(seq, self.last_hash(), player, action, &state_hash);
+        let previous_hash = self.last_hash().to_string();
+        self.records.push(AuditRecord { seq, previous_hash, player, action, state_hash, hash });
+        Ok(self.records.last().unwrap())
+    }
+
+    /// Checks the chain of hashes and replays every action from `initial`, which must be the
+    /// game as it was when the log was created, comparing the states with the ones logged.
+    /// Errors on the first record that doesn't match
+    pub fn verify(&self, initial: &Game) -> Result<(), Error> {
+        let mut game = initial.clone();
+        if state_hash(&game) != self.initial_hash {
+            bail!("The initial game doesn't match the log");
+        }
+
+        let mut previous_hash = self.initial_hash.as_str();
+        for (i, record) in self.records.iter().enumerate() {
+            if record.seq != i + 1 {
+                bail!("Record {} has the sequence number {}", i + 1, record.seq);
+            }
+            if record.previous_hash != previous_hash {
+                bail!("Record {} is not chained to the previous one", record.seq);
+            }
+            let hash = record_hash(record.seq, &record.previous_hash, record.player, record.action, &record.state_hash);
+            if record.hash != hash {
+                bail!("Record {} has been modified", record.seq);
+            }
+            if let Err(e) = game.apply(record.player, record.action) {
+                bail!("Record {} cannot be replayed: {}", record.seq, e);
+            }
+            if state_hash(&game) != record.state_hash {
+                bail!("Record {} doesn't match the state of the game after replaying it", record.seq);
+            }
+            previous_hash = &record.hash;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Bid;
+    use rules::RuleSet;
+
+    fn logged_game() -> (Game, AuditLog) {
+        let mut game = Game::from_seed(RuleSet::default(), 8);
+        game.new_round();
+        let initial = game.clone();
+        let mut log = AuditLog::new(&game);
+        for _ in 0..12 {
+            let player = game.current_player().unwrap();
+            let action = game.hint(player).unwrap().action;
+            log.apply(&mut game, player, action).unwrap();
+        }
+        (initial, log)
+    }
+
+    #[test]
+    fn can_verify_a_log() {
+        let (initial, mut log) = logged_game();
+        assert_eq!(log.records.len(), 12);
+        assert_eq!(log.records[1].previous_hash, log.records[0].hash);
+        assert!(log.verify(&initial).is_ok());
+
+        let mut game = initial.clone();
+        assert!(log.apply(&mut game, Player::East, Action::Bid(Bid::Pass, None)).is_err());
+        assert_eq!(log.records.len(), 12);
+        assert!(log.verify(&Game::from_seed(RuleSet::default(), 8)).is_err());
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let (initial, log) = logged_game();
+
+        let mut removed = log.clone();
+        removed.records.remove(3);
+        assert!(removed.verify(&initial).is_err());
+
+        let mut modified = log.clone();
+        modified.records[5].state_hash = modified.records[4].state_hash.clone();
+        assert!(modified.verify(&initial).is_err());
+
+        // Even with the hashes recomputed, the state after the action doesn't match
+        let mut rehashed = log.clone();
+        let record = rehashed.records[0].clone();
+        let other = *initial.legal_actions().iter().find(|a| **a != record.action).unwrap();
+        rehashed.records[0].action = other;
+        rehashed.records[0].hash = record_hash(1, &record.previous_hash, record.player, other, &record.state_hash);
+        assert!(rehashed.verify(&initial).is_err());
+    }
+}
@@ -1,9 +1,15 @@
+use std::error;
+use std::fmt;
+use std::iter::FromIterator;
 use std::slice::Iter;
+use std::str::FromStr;
 
 use failure::Error;
 
 use cards::Suit;
+use errors::{ContreeError, Phase};
 use players::Player;
+use rules::{RuleSet, ValidationMode, Violation};
 
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash, PartialOrd, Ord)]
@@ -26,7 +32,7 @@ pub enum Bid {
 
 impl Bid {
     // We cannot iterate on enum values in Rust so we duplicate a bit the code
-    // here to be able to iterate on the values in the deck
+    // here to be able to iterate on the values in the deck.
     pub fn iterator() -> Iter<'static, Bid> {
         [
             Bid::Pass,
@@ -42,17 +48,135 @@ impl Bid {
             Bid::Capot,
             Bid::Counter,
             Bid::DoubleCounter,
-        ].into_iter()
+        ].iter()
+    }
+
+    /// How many points the bid is worth when the contract is made.
+    /// Pass and counters have no value on their own
+    pub fn value(&self) -> usize {
+        match *self {
+            Bid::Eighty => 80,
+            Bid::Ninety => 90,
+            Bid::Hundred => 100,
+            Bid::HundredTen => 110,
+            Bid::HundredTwenty => 120,
+            Bid::HundredThirty => 130,
+            Bid::HundredForty => 140,
+            Bid::HundredFifty => 150,
+            Bid::HundredSixty => 160,
+            Bid::Capot => 250,
+            Bid::Pass | Bid::Counter | Bid::DoubleCounter => 0,
+        }
     }
 
     pub fn requires_suit(&self) -> bool {
+        !matches!(*self, Bid::Pass | Bid::Counter | Bid::DoubleCounter)
+    }
+}
+
+/// A set of bids stored as one bit per bid, iterated in the order of `Bid::iterator`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct BidSet {
+    bits: u16,
+}
+
+impl BidSet {
+    pub fn new() -> BidSet {
+        BidSet::default()
+    }
+
+    pub fn from_bids(bids: &[Bid]) -> BidSet {
+        bids.iter().cloned().collect()
+    }
+
+    pub fn insert(&mut self, bid: Bid) {
+        self.bits |= 1 << bid as u16;
+    }
+
+    pub fn contains(&self, bid: Bid) -> bool {
+        self.bits & (1 << bid as u16) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Bid> {
+        let set = *self;
+        Bid::iterator().cloned().filter(move |b| set.contains(*b))
+    }
+
+    pub fn to_vec(&self) -> Vec<Bid> {
+        self.iter().collect()
+    }
+}
+
+impl FromIterator<Bid> for BidSet {
+    fn from_iter<I: IntoIterator<Item = Bid>>(bids: I) -> BidSet {
+        let mut set = BidSet::new();
+        for bid in bids {
+            set.insert(bid);
+        }
+        set
+    }
+}
+
+/// `pass`, the value of the bid (`80` to `160`), `capot`, `coinche` or `surcoinche`
+impl fmt::Display for Bid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            Bid::Pass | Bid::Counter | Bid::DoubleCounter => false,
-            _ => true
+            Bid::Pass => write!(f, "pass"),
+            Bid::Capot => write!(f, "capot"),
+            Bid::Counter => write!(f, "coinche"),
+            Bid::DoubleCounter => write!(f, "surcoinche"),
+            bid => write!(f, "{}", bid.value()),
         }
     }
 }
 
+/// Why a bid couldn't be parsed
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseBidError(pub String);
+
+impl fmt::Display for ParseBidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Unknown bid `{}`, expected pass, a multiple of 10 from 80 to 160, capot, coinche or surcoinche",
+            self.0,
+        )
+    }
+}
+
+impl error::Error for ParseBidError {}
+
+/// Parses the notation of `Display`, ignoring case. `counter` and `double-counter`
+/// are accepted as well
+impl FromStr for Bid {
+    type Err = ParseBidError;
+
+    fn from_str(s: &str) -> Result<Bid, ParseBidError> {
+        let bid = match s.trim().to_lowercase().as_str() {
+            "pass" => Bid::Pass,
+            "capot" => Bid::Capot,
+            "coinche" | "counter" => Bid::Counter,
+            "surcoinche" | "double-counter" => Bid::DoubleCounter,
+            value => {
+                let found = Bid::iterator().find(|b| b.value() > 0 && b.value().to_string() == value);
+                match found {
+                    Some(bid) if *bid != Bid::Capot => *bid,
+                    _ => return Err(ParseBidError(s.to_string())),
+                }
+            },
+        };
+        Ok(bid)
+    }
+}
+
 /// Which state of the bidding phase are we at
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash, PartialOrd, Ord)]
 pub enum BiddingState {
@@ -68,7 +192,8 @@ pub enum BiddingState {
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Contract {
     player: Player,
-    bid: Bid,
+    /// `None` when the contract was taken in classic belote rather than bid
+    bid: Option<Bid>,
     suit: Suit,
     countered: bool,
     double_countered: bool,
@@ -89,12 +214,51 @@ impl Contract {
                 continue;
             }
             if bid != Bid::Pass {
-                return Ok(Contract {player, bid, suit: suit.unwrap(), countered, double_countered});
+                match suit {
+                    Some(suit) => return Ok(Contract {player, bid: Some(bid), suit, countered, double_countered}),
+                    None => bail!("The winning bid doesn't have a suit"),
+                }
             }
         }
 
         bail!("Couldn't get a contract from the list of bids")
     }
+
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// The winning bid, `None` if the contract was taken in classic belote
+    pub fn bid(&self) -> Option<Bid> {
+        self.bid
+    }
+
+    pub fn is_taken(&self) -> bool {
+        self.bid.is_none()
+    }
+
+    /// How many points the contract is worth when made, 0 for a taken contract
+    pub fn value(&self) -> usize {
+        self.bid.map_or(0, |b| b.value())
+    }
+
+    /// The trump suit
+    pub fn suit(&self) -> Suit {
+        self.suit
+    }
+
+    pub fn countered(&self) -> bool {
+        self.countered
+    }
+
+    pub fn double_countered(&self) -> bool {
+        self.double_countered
+    }
+
+    /// The contract of classic belote, where the taker picked the trump suit
+    pub(crate) fn taken(player: Player, suit: Suit) -> Contract {
+        Contract { player, bid: None, suit, countered: false, double_countered: false }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -107,61 +271,96 @@ pub struct BidPhase {
     /// Whether the current bid has been countered by the opposing team
     countered: Option<Player>,
     pub state: BiddingState,
+    /// Whether invalid bids are rejected or recorded anyway
+    validation: ValidationMode,
+    /// The invalid bids that were recorded in permissive mode
+    violations: Vec<Violation>,
+    /// The suits that can be bid as trump
+    allowed_suits: Vec<Suit>,
 }
 
 
 impl BidPhase {
     pub fn new(starting_player: Player) -> BidPhase {
+        BidPhase::with_validation(starting_player, ValidationMode::Strict)
+    }
+
+    pub fn with_validation(starting_player: Player, validation: ValidationMode) -> BidPhase {
         BidPhase {
             starting_player,
             bids: vec![],
             countered: None,
             state: BiddingState::Ongoing,
+            validation,
+            violations: vec![],
+            allowed_suits: Suit::iterator().cloned().collect(),
         }
     }
 
+    /// A bid phase following the validation mode and allowed trumps of the rules
+    pub fn with_rules(starting_player: Player, rules: &RuleSet) -> BidPhase {
+        let mut bid_phase = BidPhase::with_validation(starting_player, rules.validation);
+        bid_phase.allowed_suits = rules.allowed_suits();
+        bid_phase
+    }
+
+    /// The suits that can be bid as trump
+    pub fn allowed_suits(&self) -> &[Suit] {
+        &self.allowed_suits
+    }
+
     /// Finds all available bids for the given player
     pub fn available_bids(&self, player: Player) -> Vec<Bid> {
-        // everything allowed except counter/double counter
-        if self.last_bid().is_none() {
-            return Bid::iterator()
-                .filter(|b| **b != Bid::Counter && **b != Bid::DoubleCounter)
-                .cloned()
-                .collect();
-        }
+        self.available_bid_set(player).to_vec()
+    }
 
-        // we already checked before if we had one
-        let last_bid = self.last_bid().unwrap();
-        if last_bid.1 == Bid::DoubleCounter {
-            // Game should start now
-            return vec![];
-        }
+    /// Same as `available_bids` without allocating
+    pub fn available_bid_set(&self, player: Player) -> BidSet {
+        let last_bid = match self.last_bid() {
+            Some(last_bid) => last_bid,
+            // everything allowed except counter/double counter
+            None => return Bid::iterator().filter(|b| **b <= Bid::Capot).cloned().collect(),
+        };
 
-        // Doing the counter first
-        if last_bid.1 == Bid::Counter {
-            // Same team as counter: can only pass
-            if last_bid.0.team() == player.team() {
-                return vec![Bid::Pass];
-            } else {
-                // different team as counter: can pass and double counter
-                return vec![Bid::Pass, Bid::DoubleCounter];
-            }
+        match last_bid.1 {
+            // Game should start now
+            Bid::DoubleCounter => BidSet::new(),
+            // Same team as counter: can only pass, otherwise can pass and double counter
+            Bid::Counter if last_bid.0.team() == player.team() => BidSet::from_bids(&[Bid::Pass]),
+            Bid::Counter => BidSet::from_bids(&[Bid::Pass, Bid::DoubleCounter]),
+            // Back to normal bids now: pass is always allowed
+            _ => {
+                let mut bids = BidSet::from_bids(&[Bid::Pass]);
+                for bid in Bid::iterator().filter(|b| **b > last_bid.1 && **b != Bid::DoubleCounter) {
+                    bids.insert(*bid);
+                }
+                bids
+            },
         }
+    }
 
-        // Back to normal bids now
-        // Pass is always allowed
-        let mut bids = vec![Bid::Pass];
-        let all_bids: Vec<Bid> = Bid::iterator()
-            .filter(|b| **b > last_bid.1 && **b != Bid::DoubleCounter)
-            .cloned()
-            .collect();
-
-        bids.extend(all_bids);
-        bids
+    /// Whether the player can make that bid, ignoring whose turn it is
+    pub fn is_bid_available(&self, player: Player, bid: Bid) -> bool {
+        self.available_bid_set(player).contains(bid)
     }
 
     /// Update the state of the bidding phase
     fn next_state(&self) -> BiddingState {
+        match self.auction_state() {
+            // In permissive mode the bidding can end without a valid contract, eg if only
+            // counters were made: the cards are dealt again as if everyone passed
+            BiddingState::Done if Contract::new(&self.bids).is_err() => BiddingState::DealAgain,
+            state => state,
+        }
+    }
+
+    /// Whether the bidding is over according to the bids made, valid or not
+    fn auction_state(&self) -> BiddingState {
+        // Nobody can bid after a double counter
+        if let Some(&(_, Bid::DoubleCounter, _)) = self.bids.last() {
+            return BiddingState::Done;
+        }
+
         // Bid phase can only be over if there are at least 4 bids
         if self.bids.len() <= 3 {
             return BiddingState::Ongoing;
@@ -204,36 +403,79 @@ impl BidPhase {
         None
     }
 
-    /// Add a bid if possible and returns an error if an invalid bid was submitted.
-    pub fn bid(&mut self, player: Player, bid: Bid, suit: Option<Suit>) -> Result<(), Error> {
+    /// All the bids made so far, in order, passes included
+    pub fn history(&self) -> impl Iterator<Item = (Player, Bid, Option<Suit>)> + '_ {
+        self.bids.iter().cloned()
+    }
+
+    /// Whether the player has spoken yet, passing counts
+    pub fn has_bid(&self, player: Player) -> bool {
+        self.bids.iter().any(|&(p, _, _)| p == player)
+    }
+
+    /// The latest bid of the player, if they have spoken yet
+    pub fn last_bid_of(&self, player: Player) -> Option<(Bid, Option<Suit>)> {
+        self.bids.iter().rev().find(|&&(p, _, _)| p == player).map(|&(_, bid, suit)| (bid, suit))
+    }
+
+    /// The player who has to bid next, `None` once the bidding is over.
+    /// Bids always go around the table, including after a counter
+    pub fn current_player(&self) -> Option<Player> {
         if self.state != BiddingState::Ongoing {
-            bail!("The bidding phase is over!");
+            return None;
         }
+        Some(self.next_in_order())
+    }
 
-        // Is a player trying to be sneaky and skip the order?
-        if let Some(&(last_player, _, _)) = self.bids.last() {
-            if last_player.next_player() != player {
-                bail!("Wrong player");
-            }
-        } else {
-            if player != self.starting_player {
-                bail!("Wrong player");
-            }
+    fn next_in_order(&self) -> Player {
+        match self.bids.last() {
+            Some(&(last_player, _, _)) => last_player.next_player(),
+            None => self.starting_player,
         }
+    }
 
-        // First bid: everything is okay
-        if self.bids.is_empty() {
-            self.bids.push((player, bid, suit));
-            // no need to update state, still ongoing
-            return Ok(());
+    /// Checks whether the player can make that bid right now
+    pub(crate) fn check_bid(&self, player: Player, bid: Bid, suit: Option<Suit>) -> Result<(), ContreeError> {
+        // Is a player trying to be sneaky and skip the order?
+        let expected = self.next_in_order();
+        if player != expected {
+            return Err(ContreeError::WrongPlayer { expected, got: player });
         }
 
-        if !self.available_bids(player).contains(&bid) {
-            bail!("Bid not possible");
+        if !self.is_bid_available(player, bid) {
+            return Err(ContreeError::BidNotAllowed { bid, allowed: self.available_bids(player) });
         }
 
         if suit.is_none() && bid.requires_suit() {
-            bail!("A bid other than pass/counter/doublecounter must have a suit associated");
+            return Err(ContreeError::SuitRequired(bid));
+        }
+        if let Some(suit) = suit {
+            if bid.requires_suit() && !self.allowed_suits.contains(&suit) {
+                return Err(ContreeError::SuitNotAllowed { suit, allowed: self.allowed_suits.clone() });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add a bid if possible and returns an error if an invalid bid was submitted.
+    /// In permissive mode, invalid bids are recorded anyway and added to the violations.
+    pub fn bid(&mut self, player: Player, bid: Bid, suit: Option<Suit>) -> Result<(), Error> {
+        if self.state != BiddingState::Ongoing {
+            return Err(ContreeError::PhaseMismatch { expected: Phase::Bidding }.into());
+        }
+
+        if let Err(e) = self.check_bid(player, bid, suit) {
+            match self.validation {
+                ValidationMode::Strict => return Err(e.into()),
+                ValidationMode::Permissive => {
+                    self.violations.push(Violation {
+                        action_index: self.bids.len(),
+                        player,
+                        reason: e.to_string(),
+                    });
+                },
+            }
         }
         self.bids.push((player, bid, suit));
 
@@ -241,6 +483,11 @@ impl BidPhase {
         Ok(())
     }
 
+    /// All the invalid bids that were recorded in permissive mode
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
     pub fn get_contract(&self) -> Result<Contract, Error> {
         if self.state != BiddingState::Done {
             bail!("Invalid bidding state: expected the bidding phase to be done with a bid");
@@ -252,8 +499,24 @@ impl BidPhase {
 
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use evaluation::TrumpMode;
+
+    #[test]
+    fn can_print_and_parse_bids() {
+        for bid in Bid::iterator() {
+            assert_eq!(bid.to_string().parse::<Bid>(), Ok(*bid));
+        }
+        assert_eq!(Bid::HundredTen.to_string(), "110");
+        assert_eq!(Bid::Counter.to_string(), "coinche");
+        assert_eq!("Capot".parse(), Ok(Bid::Capot));
+        assert_eq!("double-counter".parse(), Ok(Bid::DoubleCounter));
+        assert_eq!("85".parse::<Bid>(), Err(ParseBidError("85".to_string())));
+        assert!("250".parse::<Bid>().is_err());
+        assert!("".parse::<Bid>().is_err());
+    }
 
     #[test]
     fn can_find_all_initial_possible_bids() {
@@ -286,6 +549,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_query_single_bids() {
+        let mut bid_phase = BidPhase::new(Player::South);
+        assert!(bid_phase.bid(Player::South, Bid::HundredTwenty, Some(Suit::Hearts)).is_ok());
+        let set = bid_phase.available_bid_set(Player::West);
+        assert_eq!(set.len(), 7);
+        assert_eq!(set.to_vec(), bid_phase.available_bids(Player::West));
+        assert!(bid_phase.is_bid_available(Player::West, Bid::Counter));
+        assert!(!bid_phase.is_bid_available(Player::North, Bid::DoubleCounter));
+        assert!(!bid_phase.is_bid_available(Player::West, Bid::HundredTen));
+        assert!(!set.contains(Bid::DoubleCounter));
+    }
+
+    #[test]
+    fn bid_sets_iterate_over_all_their_bids() {
+        let set = BidSet::from_bids(&[Bid::DoubleCounter, Bid::Pass, Bid::Capot]);
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.to_vec(), vec![Bid::Pass, Bid::Capot, Bid::DoubleCounter]);
+        assert_eq!(set.iter().count(), set.len());
+    }
+
     #[test]
     fn can_find_available_bids_after_counter_other_team() {
         let mut bid_phase = BidPhase::new(Player::South);
@@ -336,7 +620,7 @@ mod tests {
         assert_eq!(bid_phase.state, BiddingState::Done);
         let contract = bid_phase.get_contract().unwrap();
         assert_eq!(contract.player, Player::South);
-        assert_eq!(contract.bid, Bid::Eighty);
+        assert_eq!(contract.bid, Some(Bid::Eighty));
         assert_eq!(contract.suit, Suit::Spades);
         assert_eq!(contract.countered, false);
         assert_eq!(contract.double_countered, false);
@@ -353,7 +637,7 @@ mod tests {
         assert_eq!(bid_phase.state, BiddingState::Done);
         let contract = bid_phase.get_contract().unwrap();
         assert_eq!(contract.player, Player::West);
-        assert_eq!(contract.bid, Bid::Eighty);
+        assert_eq!(contract.bid, Some(Bid::Eighty));
         assert_eq!(contract.suit, Suit::Spades);
         assert_eq!(contract.countered, false);
         assert_eq!(contract.double_countered, false);
@@ -376,7 +660,7 @@ mod tests {
 
         let contract = bid_phase.get_contract().unwrap();
         assert_eq!(contract.player, Player::West);
-        assert_eq!(contract.bid, Bid::HundredTwenty);
+        assert_eq!(contract.bid, Some(Bid::HundredTwenty));
         assert_eq!(contract.suit, Suit::Hearts);
         assert_eq!(contract.countered, false);
         assert_eq!(contract.double_countered, false);
@@ -400,9 +684,106 @@ mod tests {
         assert!(bid_phase.bid(Player::North, Bid::Pass, None).is_ok());
         let contract = bid_phase.get_contract().unwrap();
         assert_eq!(contract.player, Player::South);
-        assert_eq!(contract.bid, Bid::HundredTwenty);
+        assert_eq!(contract.bid, Some(Bid::HundredTwenty));
         assert_eq!(contract.suit, Suit::Spades);
         assert_eq!(contract.countered, true);
         assert_eq!(contract.double_countered, false);
     }
+
+    #[test]
+    fn knows_whose_turn_it_is() {
+        let mut bid_phase = BidPhase::new(Player::West);
+        assert_eq!(bid_phase.current_player(), Some(Player::West));
+        bid_phase.bid(Player::West, Bid::Eighty, Some(Suit::Hearts)).unwrap();
+        assert_eq!(bid_phase.current_player(), Some(Player::North));
+        bid_phase.bid(Player::North, Bid::Counter, None).unwrap();
+        // The taker's partner is next, even if the taker is the one who can double counter
+        assert_eq!(bid_phase.current_player(), Some(Player::East));
+        bid_phase.bid(Player::East, Bid::Pass, None).unwrap();
+        assert_eq!(bid_phase.current_player(), Some(Player::South));
+        bid_phase.bid(Player::South, Bid::Pass, None).unwrap();
+        bid_phase.bid(Player::West, Bid::DoubleCounter, None).unwrap();
+        assert_eq!(bid_phase.current_player(), None);
+    }
+
+    #[test]
+    fn can_read_the_history() {
+        let mut bid_phase = BidPhase::new(Player::South);
+        bid_phase.bid(Player::South, Bid::Eighty, Some(Suit::Hearts)).unwrap();
+        bid_phase.bid(Player::West, Bid::Pass, None).unwrap();
+        bid_phase.bid(Player::North, Bid::Hundred, Some(Suit::Hearts)).unwrap();
+        assert_eq!(bid_phase.history().collect::<Vec<_>>(), vec![
+            (Player::South, Bid::Eighty, Some(Suit::Hearts)),
+            (Player::West, Bid::Pass, None),
+            (Player::North, Bid::Hundred, Some(Suit::Hearts)),
+        ]);
+        assert!(bid_phase.has_bid(Player::West));
+        assert!(!bid_phase.has_bid(Player::East));
+        assert_eq!(bid_phase.last_bid_of(Player::West), Some((Bid::Pass, None)));
+        assert_eq!(bid_phase.last_bid_of(Player::East), None);
+
+        bid_phase.bid(Player::East, Bid::Pass, None).unwrap();
+        bid_phase.bid(Player::South, Bid::HundredTen, Some(Suit::Hearts)).unwrap();
+        assert_eq!(bid_phase.last_bid_of(Player::South), Some((Bid::HundredTen, Some(Suit::Hearts))));
+    }
+
+    #[test]
+    fn double_counter_ends_bid_phase() {
+        let mut bid_phase = BidPhase::new(Player::South);
+        assert!(bid_phase.bid(Player::South, Bid::HundredTwenty, Some(Suit::Spades)).is_ok());
+        assert!(bid_phase.bid(Player::West, Bid::Counter, None).is_ok());
+        assert!(bid_phase.bid(Player::North, Bid::DoubleCounter, None).is_ok());
+        assert_eq!(bid_phase.state, BiddingState::Done);
+        let contract = bid_phase.get_contract().unwrap();
+        assert!(contract.countered);
+        assert!(contract.double_countered);
+    }
+
+    #[test]
+    fn errors_on_invalid_first_bid() {
+        let mut bid_phase = BidPhase::new(Player::South);
+        assert!(bid_phase.bid(Player::South, Bid::Counter, None).is_err());
+        assert!(bid_phase.bid(Player::South, Bid::Capot, None).is_err());
+    }
+
+    #[test]
+    fn permissive_mode_records_invalid_bids() {
+        let mut bid_phase = BidPhase::with_validation(Player::South, ValidationMode::Permissive);
+        assert!(bid_phase.bid(Player::South, Bid::Ninety, Some(Suit::Spades)).is_ok());
+        assert!(bid_phase.bid(Player::West, Bid::Eighty, Some(Suit::Hearts)).is_ok());
+        assert!(bid_phase.bid(Player::East, Bid::Pass, None).is_ok());
+        assert_eq!(bid_phase.violations().len(), 2);
+        assert_eq!(bid_phase.violations()[0].action_index, 1);
+        assert_eq!(bid_phase.violations()[0].player, Player::West);
+        assert_eq!(bid_phase.violations()[1].reason, "Wrong player");
+    }
+
+    #[test]
+    fn only_allowed_trumps_can_be_bid() {
+        let rules = RuleSet { allowed_trumps: vec![TrumpMode::Suit(Suit::Hearts)], ..RuleSet::default() };
+        let mut bid_phase = BidPhase::with_rules(Player::South, &rules);
+        assert_eq!(bid_phase.allowed_suits(), &[Suit::Hearts]);
+        let error = bid_phase.bid(Player::South, Bid::Eighty, Some(Suit::Spades)).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ContreeError>(),
+            Some(&ContreeError::SuitNotAllowed { suit: Suit::Spades, allowed: vec![Suit::Hearts] })
+        );
+        assert!(bid_phase.bid(Player::South, Bid::Eighty, Some(Suit::Hearts)).is_ok());
+        assert!(bid_phase.bid(Player::West, Bid::Counter, None).is_ok());
+    }
+
+    #[test]
+    fn permissive_mode_deals_again_without_contract() {
+        let mut bid_phase = BidPhase::with_validation(Player::South, ValidationMode::Permissive);
+        assert!(bid_phase.bid(Player::South, Bid::Counter, None).is_ok());
+        for player in &[Player::West, Player::North, Player::East] {
+            assert!(bid_phase.bid(*player, Bid::Pass, None).is_ok());
+        }
+        assert_eq!(bid_phase.state, BiddingState::DealAgain);
+        assert!(bid_phase.get_contract().is_err());
+
+        let mut bid_phase = BidPhase::with_validation(Player::South, ValidationMode::Permissive);
+        assert!(bid_phase.bid(Player::South, Bid::DoubleCounter, None).is_ok());
+        assert_eq!(bid_phase.state, BiddingState::DealAgain);
+    }
 }
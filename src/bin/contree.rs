@@ -0,0 +1,149 @@
+//! Play a game of contree against three bots in the terminal.
+//! You are South, your partner North and both opponents use the heuristic bot.
+//!
+//! Run with `cargo run --features cli`, or `cargo run --features cli -- <seed>` to replay a deal.
+
+extern crate contree;
+#[macro_use]
+extern crate failure;
+extern crate rand;
+
+use std::env;
+use std::io::{self, BufRead, Write};
+
+use failure::Error;
+
+use contree::actions::Action;
+use contree::bots::HeuristicBot;
+use contree::game::{Game, MatchResult};
+use contree::players::{Player, Team};
+use contree::rules::RuleSet;
+use contree::strategy::choose_action;
+use contree::view::PlayerView;
+
+
+const HUMAN: Player = Player::South;
+
+fn describe(action: &Action) -> String {
+    match *action {
+        Action::Bid(bid, Some(suit)) => format!("{} {:?}", bid, suit),
+        Action::Bid(bid, None) => bid.to_string(),
+        Action::Play(card) => card.to_string(),
+        Action::Take(Some(suit)) => format!("take {:?}", suit),
+        Action::Take(None) => "refuse".to_string(),
+    }
+}
+
+fn show(view: &PlayerView) {
+    let mut hand = view.hand.to_vec();
+    hand.sort_by_key(|c| (c.suit().display_position(), c.rank() as usize));
+    let hand: Vec<String> = hand.iter().map(|c| c.to_string()).collect();
+    println!();
+    println!("Your hand: {}", hand.join(", "));
+    match view.contract {
+        Some(ref contract) => println!(
+            "Contract: {:?} {} {:?}{}",
+            contract.player(),
+            contract.bid().map_or("take".to_string(), |b| b.to_string()),
+            contract.suit(),
+            if contract.double_countered() { " x4" } else if contract.countered() { " x2" } else { "" },
+        ),
+        None => {
+            for &(player, bid, suit) in &view.bids {
+                println!("  {:?} bid {}", player, describe(&Action::Bid(bid, suit)));
+            }
+            if let Some(card) = view.turned_up {
+                println!("Turned up: {}", card);
+            }
+        }
+    }
+    for &(player, card) in &view.current_trick {
+        println!("  {:?} played {}", player, card);
+    }
+}
+
+/// Asks for the number of an action until a legal one is entered.
+/// Returns `None` when the input is closed or the player quits
+fn prompt(game: &Game, input: &mut dyn BufRead) -> Result<Option<Action>, Error> {
+    let actions = game.legal_actions_indexed();
+    for &(index, ref action) in &actions {
+        println!("  [{}] {}", index, describe(action));
+    }
+
+    loop {
+        print!("Your choice (h for a hint, q to quit): ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match line.trim() {
+            "q" => return Ok(None),
+            "h" => {
+                let hint = game.hint(HUMAN)?;
+                println!("Hint: {} ({:?})", describe(&hint.action), hint.reason);
+            }
+            choice => match choice.parse::<usize>().ok().and_then(|i| actions.get(i)) {
+                Some(&(_, action)) => return Ok(Some(action)),
+                None => println!("Enter a number between 0 and {}", actions.len() - 1),
+            },
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let seed = match env::args().nth(1) {
+        Some(s) => s.parse()?,
+        None => rand::random(),
+    };
+    println!("Playing with the seed {}", seed);
+
+    let mut game = Game::from_seed(RuleSet::default(), seed);
+    let mut bots: Vec<(Player, HeuristicBot)> = Player::iterator()
+        .filter(|p| **p != HUMAN)
+        .map(|p| (*p, HeuristicBot::new()))
+        .collect();
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut rounds = 0;
+    game.new_round();
+
+    loop {
+        let lines = game.score_sheet().lines.len();
+        if lines != rounds {
+            rounds = lines;
+            println!();
+            println!("{}", game.score_sheet());
+        }
+        if let MatchResult::Won(team) = game.standing().result {
+            println!("{}", if team == HUMAN.team() { "You won!" } else { "You lost." });
+            return Ok(());
+        }
+
+        let player = match game.current_player() {
+            Some(p) => p,
+            None => bail!("The game is stuck: nobody can play"),
+        };
+        let view = game.player_view(player);
+        let action = if player == HUMAN {
+            show(&view);
+            match prompt(&game, &mut input)? {
+                Some(action) => action,
+                None => return Ok(()),
+            }
+        } else {
+            let bot = &mut bots.iter_mut().find(|&&mut (p, _)| p == player).unwrap().1;
+            let action = choose_action(bot, &view, game.is_bidding());
+            println!("{:?}{}: {}", player, if player.team() == Team::SouthNorth { " (partner)" } else { "" }, describe(&action));
+            action
+        };
+        game.apply(player, action)?;
+    }
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
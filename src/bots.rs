@@ -0,0 +1,294 @@
+use rand;
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+use actions::Action;
+use bids::Bid;
+use cards::{Card, Rank, Suit};
+use evaluation::{evaluate_hand, TrumpMode};
+use hint::HintReason;
+use round::TOTAL_TRICK_POINTS;
+use trick::trick_winner;
+use strategy::Strategy;
+use view::PlayerView;
+
+
+/// A bot picking uniformly among all the legal actions.
+/// It's a terrible player but it goes through every rule of the game,
+/// and it is the baseline any other bot should beat.
+#[derive(Debug, Clone)]
+pub struct RandomBot {
+    rng: Isaac64Rng,
+}
+
+impl Default for RandomBot {
+    fn default() -> RandomBot {
+        RandomBot::new()
+    }
+}
+
+impl RandomBot {
+    pub fn new() -> RandomBot {
+        RandomBot::from_seed(rand::random())
+    }
+
+    /// A bot making the same choices every time for the same seed and the same views
+    pub fn from_seed(seed: u64) -> RandomBot {
+        RandomBot { rng: Isaac64Rng::from_seed(&[seed]) }
+    }
+}
+
+impl Strategy for RandomBot {
+    fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>) {
+        let bid = *self.rng.choose(&view.available_bids.to_vec()).unwrap_or(&Bid::Pass);
+        if !bid.requires_suit() {
+            return (bid, None);
+        }
+        match self.rng.choose(&view.biddable_suits()) {
+            Some(suit) => (bid, Some(*suit)),
+            None => (Bid::Pass, None),
+        }
+    }
+
+    fn choose_card(&mut self, view: &PlayerView) -> Card {
+        // Only called when it's our turn so there is always a card to play
+        *self.rng.choose(&view.playable_cards).unwrap()
+    }
+
+    fn choose_take(&mut self, view: &PlayerView) -> Option<Suit> {
+        match self.rng.choose(&view.legal_actions) {
+            Some(&Action::Take(suit)) => suit,
+            _ => None,
+        }
+    }
+}
+
+/// A bot following simple rules: it bids according to the points and trumps in its hand
+/// and when playing, wins tricks as cheaply as possible, gives points to its partner
+/// when they are winning and otherwise gets rid of its lowest cards.
+#[derive(Debug, Clone, Default)]
+pub struct HeuristicBot;
+
+impl HeuristicBot {
+    pub fn new() -> HeuristicBot {
+        HeuristicBot
+    }
+
+    /// Least valuable card first: fewer points, then weaker
+    fn cheapest(cards: &[Card], trump: Suit) -> Option<Card> {
+        cards.iter().cloned().min_by_key(|c| (c.suit == trump, c.points(trump), c.strength(trump)))
+    }
+}
+
+impl HeuristicBot {
+    /// The bid the bot would make and why
+    pub(crate) fn advise_bid(&self, view: &PlayerView) -> ((Bid, Option<Suit>), HintReason) {
+        let mut best: Option<(Bid, Suit, usize)> = None;
+        for suit in &view.biddable_suits() {
+            let evaluation = evaluate_hand(&view.hand, TrumpMode::Suit(*suit));
+            let max_bid = match evaluation.max_bid {
+                Some(bid) => bid,
+                None => continue,
+            };
+            let bid = view.available_bids
+                .iter()
+                .filter(|b| b.requires_suit() && *b <= max_bid)
+                .max();
+            if let Some(bid) = bid {
+                if best.map_or(true, |(b, _, _)| bid > b) {
+                    best = Some((bid, *suit, evaluation.expected_points));
+                }
+            }
+        }
+
+        match best {
+            Some((bid, suit, estimate)) => ((bid, Some(suit)), HintReason::HandEstimate { suit, estimate }),
+            None => ((Bid::Pass, None), HintReason::NoSuitWorthBidding),
+        }
+    }
+
+    /// Whether the bot would take in classic belote, the suit it would take and why.
+    /// It takes the suit where its hand and the turned-up card should make more than half
+    /// of the trick points
+    pub(crate) fn advise_take(&self, view: &PlayerView) -> (Option<Suit>, HintReason) {
+        let mut hand = view.hand.to_vec();
+        hand.extend(view.turned_up);
+        let mut best: Option<(Suit, usize)> = None;
+        for action in &view.legal_actions {
+            if let Action::Take(Some(suit)) = *action {
+                let estimate = evaluate_hand(&hand, TrumpMode::Suit(suit)).expected_points;
+                if estimate > TOTAL_TRICK_POINTS / 2 && best.map_or(true, |(_, e)| estimate > e) {
+                    best = Some((suit, estimate));
+                }
+            }
+        }
+
+        match best {
+            Some((suit, estimate)) => (Some(suit), HintReason::HandEstimate { suit, estimate }),
+            None => (None, HintReason::NoSuitWorthBidding),
+        }
+    }
+
+    /// The card the bot would play and why
+    pub(crate) fn advise_card(&self, view: &PlayerView) -> (Card, HintReason) {
+        let cards = &view.playable_cards;
+        if cards.len() == 1 {
+            return (cards[0], HintReason::OnlyCard);
+        }
+        let trump = match view.contract {
+            Some(ref contract) => contract.suit(),
+            None => return (cards[0], HintReason::OnlyCard),
+        };
+
+        if view.current_trick.is_empty() {
+            // Lead with an ace if we have one, otherwise with our cheapest card
+            let ace = cards.iter().find(|c| c.suit != trump && c.rank == Rank::Ace);
+            return match ace {
+                Some(card) => (*card, HintReason::LeadAce),
+                None => (HeuristicBot::cheapest(cards, trump).unwrap(), HintReason::LeadCheapest),
+            };
+        }
+
+        let winner = trick_winner(&view.current_trick, trump);
+        if winner.team() == view.player.team() {
+            // Partner is winning: give them as many points as possible, keeping our trumps
+            let card = cards
+                .iter()
+                .cloned()
+                .max_by_key(|c| (c.suit != trump, c.points(trump)))
+                .unwrap();
+            return (card, HintReason::FeedPartner);
+        }
+
+        let winning_cards: Vec<Card> = cards
+            .iter()
+            .cloned()
+            .filter(|c| {
+                let mut trick = view.current_trick.clone();
+                trick.push((view.player, *c));
+                trick_winner(&trick, trump) == view.player
+            })
+            .collect();
+        if winning_cards.is_empty() {
+            (HeuristicBot::cheapest(cards, trump).unwrap(), HintReason::Discard)
+        } else {
+            (HeuristicBot::cheapest(&winning_cards, trump).unwrap(), HintReason::WinCheaply)
+        }
+    }
+}
+
+impl Strategy for HeuristicBot {
+    fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>) {
+        self.advise_bid(view).0
+    }
+
+    fn choose_card(&mut self, view: &PlayerView) -> Card {
+        self.advise_card(view).0
+    }
+}
+
+/// A `HeuristicBot` at every seat
+pub(crate) fn heuristic_table() -> [Box<dyn Strategy>; 4] {
+    [
+        Box::new(HeuristicBot::new()),
+        Box::new(HeuristicBot::new()),
+        Box::new(HeuristicBot::new()),
+        Box::new(HeuristicBot::new()),
+    ]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::Game;
+    use players::{Player, Team};
+    use rules::{DealingMode, RuleSet};
+    use strategy::run_game;
+
+    fn random_bots(seed: u64) -> [Box<dyn Strategy>; 4] {
+        [
+            Box::new(RandomBot::from_seed(seed)),
+            Box::new(RandomBot::from_seed(seed + 1)),
+            Box::new(RandomBot::from_seed(seed + 2)),
+            Box::new(RandomBot::from_seed(seed + 3)),
+        ]
+    }
+
+    #[test]
+    fn random_bots_can_play_many_games() {
+        for seed in 0..20 {
+            let mut game = Game::from_seed(RuleSet::default(), seed);
+            assert!(run_game(&mut game, &mut random_bots(seed * 4)).is_ok());
+        }
+    }
+
+    #[test]
+    fn heuristic_bot_beats_random_bots() {
+        let mut wins = 0;
+        for seed in 0..20 {
+            let mut game = Game::from_seed(RuleSet::default(), seed);
+            let mut strategies: [Box<dyn Strategy>; 4] = [
+                Box::new(HeuristicBot::new()),
+                Box::new(RandomBot::from_seed(seed * 2)),
+                Box::new(HeuristicBot::new()),
+                Box::new(RandomBot::from_seed(seed * 2 + 1)),
+            ];
+            if run_game(&mut game, &mut strategies).unwrap() == Team::SouthNorth {
+                wins += 1;
+            }
+        }
+        assert!(wins >= 15);
+    }
+
+    #[test]
+    fn heuristic_bot_bids_with_good_hand() {
+        let mut bot = HeuristicBot::new();
+        let mut game = Game::default();
+        game.new_round();
+        let mut view = game.player_view(Player::South);
+        view.hand = vec![
+            Card::new(Suit::Hearts, Rank::Jack),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::Seven),
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Spades, Rank::Seven),
+            Card::new(Suit::Clubs, Rank::Eight),
+            Card::new(Suit::Diamonds, Rank::Eight),
+        ].into();
+        assert_eq!(bot.choose_bid(&view), (Bid::Ninety, Some(Suit::Hearts)));
+
+        let mut weaker = view.hand.to_vec();
+        weaker[0] = Card::new(Suit::Clubs, Rank::Seven);
+        weaker[1] = Card::new(Suit::Clubs, Rank::Nine);
+        view.hand = weaker.into();
+        assert_eq!(bot.choose_bid(&view), (Bid::Pass, None));
+    }
+
+    #[test]
+    fn random_bots_can_play_with_other_rules() {
+        let rules = RuleSet {
+            dealing_mode: DealingMode::CutOnly,
+            must_overtrump_partner: true,
+            round_to_ten: true,
+            allowed_trumps: vec![TrumpMode::Suit(Suit::Hearts), TrumpMode::Suit(Suit::Clubs)],
+            ..RuleSet::default()
+        };
+        for seed in 0..10 {
+            let mut game = Game::from_seed(rules.clone(), seed);
+            assert!(run_game(&mut game, &mut random_bots(seed * 4)).is_ok());
+            let suits: Vec<Suit> = game.rounds().iter().map(|r| r.contract().suit()).collect();
+            assert!(suits.iter().all(|s| *s == Suit::Hearts || *s == Suit::Clubs));
+        }
+
+        let mut bot = HeuristicBot::new();
+        let mut game = Game::from_seed(rules, 1);
+        game.new_round();
+        let view = game.player_view(Player::South);
+        assert_eq!(view.biddable_suits(), vec![Suit::Clubs, Suit::Hearts]);
+        if let (_, Some(suit)) = bot.choose_bid(&view) {
+            assert!(suit == Suit::Hearts || suit == Suit::Clubs);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! What this build of the engine supports, so a frontend can adapt to the version it is
+//! linked against instead of hardcoding it.
+
+use registry::Registry;
+
+
+/// The features, variants and formats supported by this build
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Capabilities {
+    /// Version of the crate
+    pub version: &'static str,
+    /// Cargo features enabled in this build
+    pub features: Vec<&'static str>,
+    /// Variants of the game that can be played
+    pub variants: Vec<&'static str>,
+    /// Options of `RuleSet` that can be changed
+    pub rule_options: Vec<&'static str>,
+    /// Ids of the built-in bots in `Registry::default`
+    pub bots: Vec<String>,
+    /// Formats the engine can export to
+    pub formats: Vec<&'static str>,
+}
+
+impl Capabilities {
+    pub fn has_variant(&self, variant: &str) -> bool {
+        self.variants.contains(&variant)
+    }
+
+    pub fn has_format(&self, format: &str) -> bool {
+        self.formats.contains(&format)
+    }
+}
+
+/// Lists everything this build of the engine supports
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        features: if cfg!(feature = "cli") { vec!["cli"] } else { vec![] },
+        variants: vec!["contree", "classic"],
+        rule_options: vec![
+            "variant",
+            "game_length",
+            "tie_break",
+            "round_to_ten",
+            "dealing_mode",
+            "validation",
+            "count_announcements",
+            "counter_multiplies_announcements",
+            "must_overtrump_partner",
+            "revoke_penalty",
+            "allowed_trumps",
+        ],
+        bots: Registry::default().strategy_ids().into_iter().map(|id| id.to_string()).collect(),
+        formats: vec![
+            "score-sheet-text",
+            "deal-audit-text",
+            "test-vectors-json",
+            "score-sheet-csv",
+            "deal-results-csv",
+            "stats-csv",
+            "play-frequencies-csv",
+            "self-play-jsonl",
+            "tournament-markdown",
+            "leaderboard-markdown",
+        ],
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rules::RuleSet;
+
+    #[test]
+    fn can_list_capabilities() {
+        let capabilities = capabilities();
+        assert!(!capabilities.version.is_empty());
+        assert!(capabilities.has_variant("contree"));
+        assert!(!capabilities.has_variant("tarot"));
+        assert!(capabilities.has_format("score-sheet-text"));
+        assert!(capabilities.has_format("self-play-jsonl"));
+        assert_eq!(capabilities.bots, vec!["heuristic", "ismcts", "random"]);
+        assert_eq!(capabilities.features.contains(&"cli"), cfg!(feature = "cli"));
+    }
+
+    #[test]
+    fn lists_every_rule_option() {
+        // Stops compiling when `RuleSet` gets a new field: add it to `rule_options` too
+        let RuleSet {
+            variant: _,
+            game_length: _,
+            tie_break: _,
+            round_to_ten: _,
+            dealing_mode: _,
+            validation: _,
+            count_announcements: _,
+            counter_multiplies_announcements: _,
+            must_overtrump_partner: _,
+            revoke_penalty: _,
+            allowed_trumps: _,
+        } = RuleSet::default();
+        assert_eq!(capabilities().rule_options.len(), 11);
+    }
+}
@@ -0,0 +1,175 @@
+use std::iter::FromIterator;
+
+use cards::{Card, Suit};
+
+
+fn bit(card: Card) -> u32 {
+    1 << card.index()
+}
+
+/// A set of cards stored as one bit per card, ordered by suit and then rank
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct CardSet {
+    bits: u32,
+}
+
+impl CardSet {
+    pub fn new() -> CardSet {
+        CardSet::default()
+    }
+
+    /// The 32 cards of the deck
+    pub fn all() -> CardSet {
+        CardSet { bits: u32::MAX }
+    }
+
+    pub fn from_cards(cards: &[Card]) -> CardSet {
+        cards.iter().cloned().collect()
+    }
+
+    /// The cards of a suit
+    pub fn suit(suit: Suit) -> CardSet {
+        CardSet { bits: 0xff << (suit as u32 * 8) }
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.bits & bit(card) != 0
+    }
+
+    /// Adds the card, returning whether it wasn't there already
+    pub fn insert(&mut self, card: Card) -> bool {
+        let added = !self.contains(card);
+        self.bits |= bit(card);
+        added
+    }
+
+    /// Removes the card, returning whether it was there
+    pub fn remove(&mut self, card: Card) -> bool {
+        let removed = self.contains(card);
+        self.bits &= !bit(card);
+        removed
+    }
+
+    pub fn union(&self, other: CardSet) -> CardSet {
+        CardSet { bits: self.bits | other.bits }
+    }
+
+    pub fn intersection(&self, other: CardSet) -> CardSet {
+        CardSet { bits: self.bits & other.bits }
+    }
+
+    /// The cards of this set that are not in `other`
+    pub fn difference(&self, other: CardSet) -> CardSet {
+        CardSet { bits: self.bits & !other.bits }
+    }
+
+    /// All the cards not in the set
+    pub fn complement(&self) -> CardSet {
+        CardSet { bits: !self.bits }
+    }
+
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter { bits: self.bits }
+    }
+
+    pub fn to_vec(&self) -> Vec<Card> {
+        self.iter().collect()
+    }
+}
+
+/// The cards of a `CardSet`, ordered by suit and then rank
+#[derive(Debug, Clone)]
+pub struct CardSetIter {
+    bits: u32,
+}
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.bits == 0 {
+            return None;
+        }
+        let position = self.bits.trailing_zeros();
+        self.bits &= self.bits - 1;
+        Card::from_index(position as u8)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> CardSet {
+        let mut set = CardSet::new();
+        for card in cards {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Rank;
+
+    #[test]
+    fn can_convert_cards() {
+        let all = CardSet::all();
+        assert_eq!(all.len(), 32);
+        let cards = all.to_vec();
+        assert_eq!(cards[0], Card::new(Suit::Clubs, Rank::Seven));
+        assert_eq!(cards[31], Card::new(Suit::Spades, Rank::Ace));
+        assert_eq!(CardSet::from_cards(&cards), all);
+        assert!(CardSet::new().is_empty());
+        assert_eq!(CardSet::suit(Suit::Hearts).to_vec(), Rank::iterator().map(|r| Card::new(Suit::Hearts, *r)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn set_operations() {
+        let jack = Card::new(Suit::Hearts, Rank::Jack);
+        let nine = Card::new(Suit::Hearts, Rank::Nine);
+        let ace = Card::new(Suit::Spades, Rank::Ace);
+        let mut played = CardSet::new();
+        assert!(played.insert(jack));
+        assert!(!played.insert(jack));
+        played.insert(ace);
+
+        let hearts = CardSet::suit(Suit::Hearts);
+        assert_eq!(hearts.intersection(played).to_vec(), vec![jack]);
+        assert_eq!(hearts.union(played).len(), 9);
+        let remaining = hearts.difference(played);
+        assert_eq!(remaining.len(), 7);
+        assert!(remaining.contains(nine) && !remaining.contains(jack));
+        assert_eq!(played.complement().len(), 30);
+
+        assert!(played.remove(ace));
+        assert!(!played.remove(ace));
+        assert_eq!(played.iter().size_hint(), (1, Some(1)));
+    }
+}
@@ -1,5 +1,7 @@
+use std::error;
 use std::fmt;
 use std::slice::Iter;
+use std::str::FromStr;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Suit {
@@ -9,11 +11,46 @@ pub enum Suit {
     Spades,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Color {
+    Red,
+    Black,
+}
+
+/// The order suits are usually shown in, alternating colors
+pub const DISPLAY_ORDER: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds];
+
 impl Suit {
     // We cannot iterate on enum values in Rust so we duplicate a bit the code
     // here to be able to iterate on the values in the deck
     pub fn iterator() -> Iter<'static, Suit> {
-        [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades].into_iter()
+        [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades].iter()
+    }
+
+    pub fn color(&self) -> Color {
+        match *self {
+            Suit::Diamonds | Suit::Hearts => Color::Red,
+            Suit::Clubs | Suit::Spades => Color::Black,
+        }
+    }
+
+    /// The three other suits, in the order of `Suit::iterator`
+    pub fn others(&self) -> [Suit; 3] {
+        let mut others = [*self; 3];
+        for (other, suit) in others.iter_mut().zip(SUITS.iter().filter(|s| *s != self)) {
+            *other = *suit;
+        }
+        others
+    }
+
+    /// The position of the suit in `DISPLAY_ORDER`, to sort cards for display
+    pub fn display_position(&self) -> usize {
+        match *self {
+            Suit::Spades => 0,
+            Suit::Hearts => 1,
+            Suit::Clubs => 2,
+            Suit::Diamonds => 3,
+        }
     }
 }
 
@@ -36,27 +73,301 @@ impl Rank {
         [
             Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ten,
             Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
-        ].into_iter()
+        ].iter()
+    }
+}
+
+/// The ranks of the trump suit from the strongest to the weakest
+pub const TRUMP_ORDER: [Rank; 8] = [
+    Rank::Jack, Rank::Nine, Rank::Ace, Rank::Ten, Rank::King, Rank::Queen, Rank::Eight, Rank::Seven,
+];
+/// The ranks of a plain suit from the strongest to the weakest
+pub const PLAIN_ORDER: [Rank; 8] = [
+    Rank::Ace, Rank::Ten, Rank::King, Rank::Queen, Rank::Jack, Rank::Nine, Rank::Eight, Rank::Seven,
+];
+
+impl Rank {
+    /// How strong the rank is in the trump suit: J > 9 > A > 10 > K > Q > 8 > 7
+    pub fn trump_strength(&self) -> usize {
+        STRENGTHS[1][*self as usize] as usize
+    }
+
+    /// How strong the rank is in a plain suit: A > 10 > K > Q > J > 9 > 8 > 7
+    pub fn plain_strength(&self) -> usize {
+        STRENGTHS[0][*self as usize] as usize
+    }
+
+    /// `TRUMP_ORDER`, strongest first
+    pub fn trump_order() -> &'static [Rank; 8] {
+        &TRUMP_ORDER
+    }
+
+    /// `PLAIN_ORDER`, strongest first
+    pub fn plain_order() -> &'static [Rank; 8] {
+        &PLAIN_ORDER
     }
 }
 
+const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+const RANKS: [Rank; 8] = [
+    Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
+];
+
+/// Points of each rank, indexed by `Rank as usize`, in a plain suit and in the trump suit
+const POINTS: [[u8; 8]; 2] = [[0, 0, 0, 10, 2, 3, 4, 11], [0, 0, 14, 10, 20, 3, 4, 11]];
+/// Same as `POINTS` for `plain_strength` and `trump_strength`
+const STRENGTHS: [[u8; 8]; 2] = [[0, 1, 2, 6, 3, 4, 5, 7], [0, 1, 6, 4, 7, 2, 3, 5]];
+
+/// For each trump and card index, the cards it beats as bits by card index
+const BEATS: [[u32; 32]; 4] = beats_table();
+
+const fn beats_table() -> [[u32; 32]; 4] {
+    let mut table = [[0; 32]; 4];
+    let mut trump = 0;
+    while trump < 4 {
+        let mut card = 0;
+        while card < 32 {
+            let mut other = 0;
+            while other < 32 {
+                let (suit, other_suit) = (card / 8, other / 8);
+                let strength = STRENGTHS[(suit == trump) as usize][card % 8];
+                let other_strength = STRENGTHS[(other_suit == trump) as usize][other % 8];
+                let beats = if suit == other_suit { strength > other_strength } else { suit == trump };
+                if beats {
+                    table[trump][card] |= 1 << other;
+                }
+                other += 1;
+            }
+            card += 1;
+        }
+        trump += 1;
+    }
+    table
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Card {
-    suit: Suit,
-    rank: Rank,
+    pub(crate) suit: Suit,
+    pub(crate) rank: Rank,
+}
+
+const fn all_cards() -> [Card; 32] {
+    let mut cards = [Card::new(Suit::Clubs, Rank::Seven); 32];
+    let mut i = 0;
+    while i < 32 {
+        cards[i] = Card::new(SUITS[i / 8], RANKS[i % 8]);
+        i += 1;
+    }
+    cards
 }
 
 impl Card {
-    pub fn new(suit: Suit, rank: Rank) -> Card {
+    /// The 32 cards ordered by suit and then rank, the position of a card being its `index`
+    pub const ALL: [Card; 32] = all_cards();
+
+    pub const fn new(suit: Suit, rank: Rank) -> Card {
         Card {
             suit,
             rank,
         }
     }
+
+    pub fn suit(&self) -> Suit {
+        self.suit
+    }
+
+    pub fn rank(&self) -> Rank {
+        self.rank
+    }
+
+    /// A number between 0 and 31 identifying the card, ordered by suit and then rank
+    pub fn index(&self) -> u8 {
+        self.suit as u8 * 8 + self.rank as u8
+    }
+
+    /// The card with that `index`, if it is below 32
+    pub fn from_index(index: u8) -> Option<Card> {
+        if index >= 32 {
+            return None;
+        }
+        Some(Card::ALL[index as usize])
+    }
+
+    /// How many points the card is worth in tricks
+    pub fn points(&self, trump: Suit) -> usize {
+        POINTS[(self.suit == trump) as usize][self.rank as usize] as usize
+    }
+
+    /// How strong the card is within its suit
+    pub(crate) fn strength(&self, trump: Suit) -> usize {
+        STRENGTHS[(self.suit == trump) as usize][self.rank as usize] as usize
+    }
+
+    /// Whether the card wins over `other` in a trick where `other` is the best card so far
+    pub fn beats(&self, other: Card, trump: Suit) -> bool {
+        BEATS[trump as usize][self.index() as usize] & (1 << other.index()) != 0
+    }
 }
 
+/// Short notation: the rank (`7`, `8`, `9`, `10`, `J`, `Q`, `K` or `A`) followed by the
+/// suit (`C`, `D`, `H` or `S`), eg `10H` for the ten of hearts
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?} of {:?}", self.rank, self.suit)
+        let rank = match self.rank {
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        };
+        let suit = match self.suit {
+            Suit::Clubs => 'C',
+            Suit::Diamonds => 'D',
+            Suit::Hearts => 'H',
+            Suit::Spades => 'S',
+        };
+        write!(f, "{}{}", rank, suit)
+    }
+}
+
+/// Why a card couldn't be parsed
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseCardError {
+    Empty,
+    InvalidRank(String),
+    InvalidSuit(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseCardError::Empty => write!(f, "A card cannot be empty"),
+            ParseCardError::InvalidRank(ref rank) => {
+                write!(f, "Unknown rank `{}`, expected one of 7 8 9 10 J Q K A", rank)
+            },
+            ParseCardError::InvalidSuit(ref suit) => {
+                write!(f, "Unknown suit `{}`, expected one of C D H S or a suit symbol", suit)
+            },
+        }
+    }
+}
+
+impl error::Error for ParseCardError {}
+
+/// Parses the notation of `Display`, ignoring case. `T` is accepted for the ten and
+/// the suit can also be a symbol, eg `J♦`
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let s = s.trim();
+        let suit_start = match s.char_indices().last() {
+            Some((i, _)) => i,
+            None => return Err(ParseCardError::Empty),
+        };
+        let (rank, suit) = s.split_at(suit_start);
+        let rank = match rank.to_uppercase().as_str() {
+            "7" => Rank::Seven,
+            "8" => Rank::Eight,
+            "9" => Rank::Nine,
+            "10" | "T" => Rank::Ten,
+            "J" => Rank::Jack,
+            "Q" => Rank::Queen,
+            "K" => Rank::King,
+            "A" => Rank::Ace,
+            _ => return Err(ParseCardError::InvalidRank(rank.to_string())),
+        };
+        let suit = match suit.to_uppercase().as_str() {
+            "C" | "♣" | "♧" => Suit::Clubs,
+            "D" | "♦" | "♢" => Suit::Diamonds,
+            "H" | "♥" | "♡" => Suit::Hearts,
+            "S" | "♠" | "♤" => Suit::Spades,
+            _ => return Err(ParseCardError::InvalidSuit(suit.to_string())),
+        };
+        Ok(Card::new(suit, rank))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_cards_are_worth_162_points_with_last_trick() {
+        for trump in Suit::iterator() {
+            let total: usize = Card::ALL.iter().map(|c| c.points(*trump)).sum();
+            assert_eq!(total + 10, 162);
+        }
+    }
+
+    #[test]
+    fn can_compare_cards_strength() {
+        let jack = Card::new(Suit::Hearts, Rank::Jack);
+        let nine = Card::new(Suit::Hearts, Rank::Nine);
+        let ace = Card::new(Suit::Hearts, Rank::Ace);
+        assert!(jack.strength(Suit::Hearts) > nine.strength(Suit::Hearts));
+        assert!(nine.strength(Suit::Hearts) > ace.strength(Suit::Hearts));
+        assert!(ace.strength(Suit::Spades) > jack.strength(Suit::Spades));
+        assert!(jack.strength(Suit::Spades) > nine.strength(Suit::Spades));
+    }
+
+    #[test]
+    fn can_index_cards() {
+        for (i, card) in Card::ALL.iter().cloned().enumerate() {
+            assert_eq!(card.index() as usize, i);
+            assert_eq!(Card::from_index(i as u8), Some(card));
+        }
+        assert_eq!(Card::from_index(32), None);
+    }
+
+    #[test]
+    fn can_compare_cards_in_tricks() {
+        let nine = Card::new(Suit::Hearts, Rank::Nine);
+        let ten = Card::new(Suit::Hearts, Rank::Ten);
+        let seven = Card::new(Suit::Spades, Rank::Seven);
+        assert!(nine.beats(ten, Suit::Hearts));
+        assert!(ten.beats(nine, Suit::Spades));
+        assert!(seven.beats(ten, Suit::Spades));
+        assert!(!seven.beats(ten, Suit::Clubs));
+        assert!(!ten.beats(seven, Suit::Clubs));
+        assert!(!nine.beats(nine, Suit::Hearts));
+    }
+
+    #[test]
+    fn can_print_and_parse_cards() {
+        for suit in Suit::iterator() {
+            for rank in Rank::iterator() {
+                let card = Card::new(*suit, *rank);
+                assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+            }
+        }
+        assert_eq!(Card::new(Suit::Hearts, Rank::Ten).to_string(), "10H");
+        assert_eq!("AS".parse(), Ok(Card::new(Suit::Spades, Rank::Ace)));
+        assert_eq!("J♦".parse(), Ok(Card::new(Suit::Diamonds, Rank::Jack)));
+        assert_eq!(" th ".parse(), Ok(Card::new(Suit::Hearts, Rank::Ten)));
+
+        assert_eq!("".parse::<Card>(), Err(ParseCardError::Empty));
+        assert_eq!("1H".parse::<Card>(), Err(ParseCardError::InvalidRank("1".to_string())));
+        assert_eq!("AX".parse::<Card>(), Err(ParseCardError::InvalidSuit("X".to_string())));
+        assert_eq!("H".parse::<Card>(), Err(ParseCardError::InvalidRank(String::new())));
+    }
+
+    #[test]
+    fn orders_match_the_strengths() {
+        assert!(Rank::trump_order().windows(2).all(|w| w[0].trump_strength() > w[1].trump_strength()));
+        assert!(Rank::plain_order().windows(2).all(|w| w[0].plain_strength() > w[1].plain_strength()));
+    }
+
+    #[test]
+    fn suit_helpers() {
+        assert_eq!(Suit::Hearts.color(), Color::Red);
+        assert_eq!(Suit::Spades.color(), Color::Black);
+        assert_eq!(Suit::Hearts.others(), [Suit::Clubs, Suit::Diamonds, Suit::Spades]);
+        assert!(DISPLAY_ORDER.windows(2).all(|w| w[0].color() != w[1].color()));
+        assert!(DISPLAY_ORDER.iter().enumerate().all(|(i, s)| s.display_position() == i));
     }
 }
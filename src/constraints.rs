@@ -0,0 +1,178 @@
+//! What the cards played so far tell about the hands of the players: a player that didn't
+//! follow the suit led has none left, a player that had to trump and didn't has no trump and
+//! a player that had to overtrump and didn't has no trump higher than the ones in the trick.
+//!
+//! Discarding or playing a low trump while the partner is winning the trick is allowed unless
+//! `RuleSet::must_overtrump_partner` is set, so nothing is inferred from it in that case.
+
+use card_set::CardSet;
+use cards::{Card, Suit};
+use players::Player;
+use rules::RuleSet;
+use trick::trick_winner;
+use view::PlayerView;
+
+
+/// The cards a player has shown they can't have
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct SeatConstraints {
+    excluded: CardSet,
+}
+
+impl SeatConstraints {
+    /// All the cards the player cannot hold anymore, whether they were played or not
+    pub fn excluded(&self) -> CardSet {
+        self.excluded
+    }
+
+    pub fn can_hold(&self, card: Card) -> bool {
+        !self.excluded.contains(card)
+    }
+
+    /// The suits the player has none of
+    pub fn voids(&self) -> Vec<Suit> {
+        Suit::iterator()
+            .filter(|s| self.is_void(**s))
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_void(&self, suit: Suit) -> bool {
+        let cards = CardSet::suit(suit);
+        self.excluded.intersection(cards) == cards
+    }
+}
+
+/// The constraints of each seat, indexed by `Player::index`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Constraints {
+    seats: [SeatConstraints; 4],
+}
+
+impl Constraints {
+    /// The constraints that hold whatever the rules. Without the rules we can't know
+    /// whether a player had to overtrump their partner so nothing is inferred from it
+    pub fn from_view(view: &PlayerView) -> Constraints {
+        Constraints::infer(view, false)
+    }
+
+    /// Same as `from_view`, also using what `RuleSet::must_overtrump_partner` forces
+    pub fn with_rules(view: &PlayerView, rules: &RuleSet) -> Constraints {
+        Constraints::infer(view, rules.must_overtrump_partner)
+    }
+
+    fn infer(view: &PlayerView, must_overtrump_partner: bool) -> Constraints {
+        let mut constraints = Constraints::default();
+        let trump = match view.contract {
+            Some(ref contract) => contract.suit(),
+            None => return constraints,
+        };
+        let tricks = view.tricks
+            .iter()
+            .map(|(trick, _)| trick.as_slice())
+            .chain(Some(view.current_trick.as_slice()));
+        for trick in tricks {
+            for i in 1..trick.len() {
+                constraints.add_play(&trick[..i], trick[i], trump, must_overtrump_partner);
+            }
+        }
+        constraints
+    }
+
+    /// Records what playing `card` after the cards of `before` tells about the player
+    fn add_play(&mut self, before: &[(Player, Card)], (player, card): (Player, Card), trump: Suit, must_overtrump_partner: bool) {
+        let lead = before[0].1.suit;
+        let partner_winning = trick_winner(before, trump).team() == player.team();
+        let must_trump = lead == trump || !partner_winning || must_overtrump_partner;
+        let excluded = &mut self.seats[player.index()].excluded;
+
+        if card.suit != lead {
+            *excluded = excluded.union(CardSet::suit(lead));
+            if card.suit != trump && must_trump {
+                *excluded = excluded.union(CardSet::suit(trump));
+            }
+        }
+        if card.suit != trump || !must_trump {
+            return;
+        }
+        let highest = before.iter().map(|&(_, c)| c).filter(|c| c.suit == trump).max_by_key(|c| c.strength(trump));
+        if let Some(highest) = highest {
+            if !card.beats(highest, trump) {
+                let higher: CardSet = CardSet::suit(trump).iter().filter(|c| c.beats(highest, trump)).collect();
+                *excluded = excluded.union(higher);
+            }
+        }
+    }
+
+    pub fn for_player(&self, player: Player) -> &SeatConstraints {
+        &self.seats[player.index()]
+    }
+
+    /// Whether the player may still hold the card as far as the cards played tell
+    pub fn can_hold(&self, player: Player, card: Card) -> bool {
+        self.for_player(player).can_hold(card)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Contract;
+    use cards::Rank;
+    use game::Game;
+
+    fn card(suit: Suit, rank: Rank) -> Card {
+        Card::new(suit, rank)
+    }
+
+    fn view_with_trick(trick: Vec<(Player, Card)>) -> PlayerView {
+        let mut game = Game::default();
+        game.new_round();
+        let mut view = game.player_view(Player::South);
+        view.contract = Some(Contract::taken(Player::South, Suit::Hearts));
+        view.current_trick = trick;
+        view
+    }
+
+    #[test]
+    fn not_following_shows_a_void() {
+        let view = view_with_trick(vec![
+            (Player::South, card(Suit::Spades, Rank::Ace)),
+            (Player::West, card(Suit::Clubs, Rank::Seven)),
+        ]);
+        let constraints = Constraints::from_view(&view);
+        // Opponents are winning: West had to trump if they could
+        assert_eq!(constraints.for_player(Player::West).voids(), vec![Suit::Hearts, Suit::Spades]);
+        assert!(constraints.can_hold(Player::West, card(Suit::Clubs, Rank::Ace)));
+        assert!(constraints.for_player(Player::North).voids().is_empty());
+    }
+
+    #[test]
+    fn discarding_on_partner_tells_nothing_about_trumps() {
+        let trick = vec![
+            (Player::South, card(Suit::Spades, Rank::Ace)),
+            (Player::West, card(Suit::Spades, Rank::Seven)),
+            (Player::North, card(Suit::Clubs, Rank::Seven)),
+        ];
+        let view = view_with_trick(trick);
+        assert_eq!(Constraints::from_view(&view).for_player(Player::North).voids(), vec![Suit::Spades]);
+
+        let strict = RuleSet { must_overtrump_partner: true, ..RuleSet::default() };
+        let constraints = Constraints::with_rules(&view, &strict);
+        assert_eq!(constraints.for_player(Player::North).voids(), vec![Suit::Hearts, Suit::Spades]);
+    }
+
+    #[test]
+    fn not_overtrumping_shows_no_higher_trump() {
+        let view = view_with_trick(vec![
+            (Player::South, card(Suit::Hearts, Rank::Ace)),
+            (Player::West, card(Suit::Hearts, Rank::Seven)),
+        ]);
+        let west = *Constraints::from_view(&view).for_player(Player::West);
+        assert!(!west.can_hold(card(Suit::Hearts, Rank::Jack)));
+        assert!(!west.can_hold(card(Suit::Hearts, Rank::Nine)));
+        assert!(west.can_hold(card(Suit::Hearts, Rank::Ten)));
+        assert!(!west.is_void(Suit::Hearts));
+    }
+}
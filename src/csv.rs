@@ -0,0 +1,220 @@
+//! CSV exports of score sheets, duplicate deal results, statistics and cards played,
+//! to open in a spreadsheet.
+//! Every export starts with a header line and uses `,` as separator.
+//! Teams are `SN` for South/North and `EW` for East/West.
+
+use std::io::Write;
+
+use failure::Error;
+
+use bids::Contract;
+use duplicate::DealComparison;
+use players::Team;
+use score_sheet::ScoreSheet;
+use stats::{PlayFrequencies, Statistics};
+
+
+/// Quotes the field if it contains a separator, a quote or a new line
+fn field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_row<W: Write>(writer: &mut W, fields: &[String]) -> Result<(), Error> {
+    let fields: Vec<String> = fields.iter().map(|f| field(f)).collect();
+    writeln!(writer, "{}", fields.join(","))?;
+    Ok(())
+}
+
+fn team_code(team: Team) -> &'static str {
+    match team {
+        Team::SouthNorth => "SN",
+        Team::EastWest => "EW",
+    }
+}
+
+/// The taker, bid, trump and multiplier of a contract
+fn contract_fields(contract: &Contract) -> Vec<String> {
+    let multiplier = if contract.double_countered() { 4 } else if contract.countered() { 2 } else { 1 };
+    vec![
+        format!("{:?}", contract.player()),
+        contract.value().to_string(),
+        format!("{:?}", contract.suit()),
+        multiplier.to_string(),
+    ]
+}
+
+/// Writes one row per round of all the games, the `game` column being the position of
+/// the score sheet in `sheets`. Adjustments are not included, only the totals reflect them
+pub fn write_score_sheets<W: Write>(writer: &mut W, sheets: &[&ScoreSheet]) -> Result<(), Error> {
+    write_row(writer, &[
+        "game", "round", "taker", "bid", "trump", "multiplier", "made", "belote",
+        "trick_points_sn", "trick_points_ew", "score_sn", "score_ew", "total_sn", "total_ew",
+    ].iter().map(|h| h.to_string()).collect::<Vec<_>>())?;
+
+    for (game, sheet) in sheets.iter().enumerate() {
+        for (round, line) in sheet.lines.iter().enumerate() {
+            let mut fields = vec![(game + 1).to_string(), (round + 1).to_string()];
+            fields.extend(contract_fields(&line.contract));
+            fields.push(line.made.to_string());
+            fields.push(line.belote.map_or("", team_code).to_string());
+            for values in &[&line.trick_points, &line.scores, &line.totals] {
+                fields.push(values[Team::SouthNorth].to_string());
+                fields.push(values[Team::EastWest].to_string());
+            }
+            write_row(writer, &fields)?;
+        }
+    }
+    Ok(())
+}
+
+/// Same as `write_score_sheets` for a single game
+pub fn write_score_sheet<W: Write>(writer: &mut W, sheet: &ScoreSheet) -> Result<(), Error> {
+    write_score_sheets(writer, &[sheet])
+}
+
+/// Writes one row per deal and table. The contract columns are empty when everyone passed
+pub fn write_deal_results<W: Write>(writer: &mut W, comparisons: &[DealComparison]) -> Result<(), Error> {
+    write_row(writer, &[
+        "deal", "table", "taker", "bid", "trump", "multiplier", "score_sn", "score_ew", "difference_sn",
+    ].iter().map(|h| h.to_string()).collect::<Vec<_>>())?;
+
+    for comparison in comparisons {
+        for result in &comparison.results {
+            let mut fields = vec![comparison.deal_id.to_string(), result.table.clone()];
+            match result.contract {
+                Some(ref contract) => fields.extend(contract_fields(contract)),
+                None => fields.extend(vec![String::new(); 4]),
+            }
+            fields.push(result.scores.0.to_string());
+            fields.push(result.scores.1.to_string());
+            fields.push(comparison.difference(&result.table).unwrap_or(0).to_string());
+            write_row(writer, &fields)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes one row per contract played, most played first
+pub fn write_statistics<W: Write>(writer: &mut W, stats: &Statistics) -> Result<(), Error> {
+    write_row(writer, &["bid", "trump", "played", "made", "success_rate"].iter().map(|h| h.to_string()).collect::<Vec<_>>())?;
+
+    for ((bid, suit), _) in stats.contract_frequencies() {
+        let contract = stats.contracts[&(bid, suit)];
+        write_row(writer, &[
+            bid.value().to_string(),
+            format!("{:?}", suit),
+            contract.played.to_string(),
+            contract.made.to_string(),
+            contract.success_rate().map_or(String::new(), |r| format!("{:.3}", r)),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Writes one row per card played by a seat at a trick, tricks numbered from 1.
+/// `frequency` is the share of the rounds where it happened, to draw heatmaps
+pub fn write_play_frequencies<W: Write>(writer: &mut W, plays: &PlayFrequencies) -> Result<(), Error> {
+    write_row(writer, &["trick", "player", "card", "played", "frequency"].iter().map(|h| h.to_string()).collect::<Vec<_>>())?;
+
+    for ((trick, player, card), count) in plays.sorted_counts() {
+        write_row(writer, &[
+            (trick + 1).to_string(),
+            format!("{:?}", player),
+            card.to_string(),
+            count.to_string(),
+            plays.frequency(trick, player, card).map_or(String::new(), |f| format!("{:.3}", f)),
+        ])?;
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Bid;
+    use bots::heuristic_table;
+    use cards::{Card, Rank, Suit};
+    use duplicate::{generate_deals, play_duplicate};
+    use game::Game;
+    use players::Player;
+    use rules::RuleSet;
+    use stats::ContractStats;
+    use strategy::run_game;
+
+    #[test]
+    fn quotes_fields_when_needed() {
+        assert_eq!(field("open"), "open");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn can_export_score_sheets_and_stats() {
+        let mut game = Game::from_seed(RuleSet::default(), 0);
+        run_game(&mut game, &mut heuristic_table()).unwrap();
+        let sheet = game.score_sheet();
+
+        let mut output = vec![];
+        write_score_sheet(&mut output, &sheet).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), sheet.lines.len() + 1);
+        assert!(lines[0].starts_with("game,round,taker,bid,trump"));
+        assert!(lines[1].starts_with("1,1,"));
+        assert!(lines.iter().all(|l| l.split(',').count() == 14));
+
+        let mut stats = Statistics::new();
+        stats.add_game(&game);
+        let mut output = vec![];
+        write_statistics(&mut output, &stats).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), stats.contracts.len() + 1);
+
+        let mut stats = Statistics::new();
+        stats.contracts.insert((Bid::Eighty, Suit::Hearts), ContractStats { played: 2, made: 1 });
+        let mut output = vec![];
+        write_statistics(&mut output, &stats).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "bid,trump,played,made,success_rate\n80,Hearts,2,1,0.500\n");
+    }
+
+    #[test]
+    fn can_export_play_frequencies() {
+        let mut game = Game::from_seed(RuleSet::default(), 0);
+        run_game(&mut game, &mut heuristic_table()).unwrap();
+        let mut plays = PlayFrequencies::new();
+        plays.add_game(&game);
+        let mut output = vec![];
+        write_play_frequencies(&mut output, &plays).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), plays.counts.len() + 1);
+        assert!(output.lines().nth(1).unwrap().starts_with("1,South,"));
+
+        let mut plays = PlayFrequencies::new();
+        plays.rounds = 4;
+        plays.counts.insert((7, Player::East, Card::new(Suit::Hearts, Rank::Ten)), 1);
+        plays.counts.insert((0, Player::West, Card::new(Suit::Spades, Rank::Ace)), 3);
+        let mut output = vec![];
+        write_play_frequencies(&mut output, &plays).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "trick,player,card,played,frequency\n1,West,AS,3,0.750\n8,East,10H,1,0.250\n"
+        );
+    }
+
+    #[test]
+    fn can_export_deal_results() {
+        let mut tables = vec![("open", heuristic_table()), ("closed", heuristic_table())];
+        let comparisons = play_duplicate(&generate_deals(0, 2), &RuleSet::default(), &mut tables).unwrap();
+        let mut output = vec![];
+        write_deal_results(&mut output, &comparisons).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 5);
+        assert!(output.lines().nth(1).unwrap().starts_with("0,open,"));
+        assert!(output.lines().nth(2).unwrap().starts_with("0,closed,"));
+    }
+}
@@ -0,0 +1,152 @@
+//! Random deals satisfying some constraints, eg "South has at least 5 hearts and the trump
+//! Jack" or "East/West have at least 80 points", to build training scenarios and bidding quizzes.
+//!
+//! Cards a player must hold are given to them first, the rest of the deck is shuffled and dealt
+//! and deals not satisfying the other constraints are thrown away.
+
+use failure::Error;
+use rand;
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+use card_set::CardSet;
+use cards::{Card, Suit};
+use players::{Player, Team};
+
+
+/// How many deals we try by default before giving up
+pub const DEFAULT_MAX_ATTEMPTS: usize = 100_000;
+
+/// Something a deal has to satisfy
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DealConstraint {
+    /// The player holds that card
+    Holds(Player, Card),
+    /// The player has between `min` and `max` cards of the suit, inclusive
+    SuitLength { player: Player, suit: Suit, min: usize, max: usize },
+    /// The two players of the team hold between `min` and `max` card points
+    /// with that suit as trump, inclusive
+    TeamPoints { team: Team, trump: Suit, min: usize, max: usize },
+}
+
+impl DealConstraint {
+    /// Whether the hands, indexed by `Player::index`, satisfy the constraint
+    pub fn is_satisfied(&self, hands: &[Vec<Card>; 4]) -> bool {
+        match *self {
+            DealConstraint::Holds(player, card) => hands[player.index()].contains(&card),
+            DealConstraint::SuitLength { player, suit, min, max } => {
+                let length = hands[player.index()].iter().filter(|c| c.suit == suit).count();
+                length >= min && length <= max
+            },
+            DealConstraint::TeamPoints { team, trump, min, max } => {
+                let points: usize = Player::iterator()
+                    .filter(|p| p.team() == team)
+                    .flat_map(|p| hands[p.index()].iter())
+                    .map(|c| c.points(trump))
+                    .sum();
+                points >= min && points <= max
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DealGenerator {
+    pub constraints: Vec<DealConstraint>,
+    /// How many deals are tried before giving up
+    pub max_attempts: usize,
+}
+
+impl DealGenerator {
+    pub fn new(constraints: Vec<DealConstraint>) -> DealGenerator {
+        DealGenerator { constraints, max_attempts: DEFAULT_MAX_ATTEMPTS }
+    }
+
+    /// A deal satisfying all the constraints, the hands indexed by `Player::index`
+    /// as expected by `Game::new_round_with_hands`.
+    /// Errors if the constraints contradict each other or no deal was found in time
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<[Vec<Card>; 4], Error> {
+        let mut fixed: [Vec<Card>; 4] = Default::default();
+        let mut given = CardSet::new();
+        for constraint in &self.constraints {
+            if let DealConstraint::Holds(player, card) = *constraint {
+                if fixed[player.index()].contains(&card) {
+                    continue;
+                }
+                if !given.insert(card) {
+                    bail!("The {} can't be held by two players", card);
+                }
+                fixed[player.index()].push(card);
+            }
+        }
+        if fixed.iter().any(|hand| hand.len() > 8) {
+            bail!("A player can't hold more than 8 cards");
+        }
+
+        let mut rest = given.complement().to_vec();
+        for _ in 0..self.max_attempts {
+            rng.shuffle(&mut rest);
+            let mut hands = fixed.clone();
+            let mut cards = rest.iter();
+            for hand in hands.iter_mut() {
+                let missing = 8 - hand.len();
+                hand.extend(cards.by_ref().take(missing));
+            }
+            if self.constraints.iter().all(|c| c.is_satisfied(&hands)) {
+                return Ok(hands);
+            }
+        }
+        bail!("No deal satisfying the constraints found in {} attempts", self.max_attempts)
+    }
+
+    /// Same as `generate` but always returns the same deal for the same seed
+    pub fn generate_from_seed(&self, seed: u64) -> Result<[Vec<Card>; 4], Error> {
+        self.generate(&mut Isaac64Rng::from_seed(&[seed]))
+    }
+
+    /// Same as `generate` with a random seed
+    pub fn generate_random(&self) -> Result<[Vec<Card>; 4], Error> {
+        self.generate_from_seed(rand::random())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Rank;
+    use game::Game;
+
+    #[test]
+    fn deals_satisfy_the_constraints() {
+        let jack = Card::new(Suit::Hearts, Rank::Jack);
+        let generator = DealGenerator::new(vec![
+            DealConstraint::Holds(Player::South, jack),
+            DealConstraint::SuitLength { player: Player::South, suit: Suit::Hearts, min: 5, max: 8 },
+            DealConstraint::TeamPoints { team: Team::EastWest, trump: Suit::Hearts, min: 80, max: 152 },
+        ]);
+        for seed in 0..10 {
+            let hands = generator.generate_from_seed(seed).unwrap();
+            assert!(hands[Player::South.index()].contains(&jack));
+            assert!(generator.constraints.iter().all(|c| c.is_satisfied(&hands)));
+            assert!(Game::default().new_round_with_hands(Player::South, &hands).is_ok());
+        }
+        assert_eq!(generator.generate_from_seed(3).unwrap(), generator.generate_from_seed(3).unwrap());
+    }
+
+    #[test]
+    fn errors_on_impossible_constraints() {
+        let jack = Card::new(Suit::Hearts, Rank::Jack);
+        let twice = DealGenerator::new(vec![
+            DealConstraint::Holds(Player::South, jack),
+            DealConstraint::Holds(Player::North, jack),
+        ]);
+        assert!(twice.generate_from_seed(0).is_err());
+
+        let mut too_long = DealGenerator::new(vec![
+            DealConstraint::SuitLength { player: Player::West, suit: Suit::Clubs, min: 9, max: 9 },
+        ]);
+        too_long.max_attempts = 10;
+        assert!(too_long.generate_from_seed(0).is_err());
+    }
+}
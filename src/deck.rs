@@ -1,64 +1,391 @@
-use rand::StdRng;
+use std::fmt;
+
+use failure::Error;
+use rand;
 use rand::Rng;
+use rand::SeedableRng;
+use rand::isaac::Isaac64Rng;
 
+use card_set::CardSet;
 use cards::{Suit, Rank, Card};
+use errors::ContreeError;
+use players::Player;
+use sha256;
+
 
+/// How the current order of a deck was obtained, revealed once the round is over
+/// so anyone can recompute the deck that was dealt
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DeckOrigin {
+    /// Shuffled from the ordered deck with that seed
+    Shuffled(u64),
+    /// Put back together from the tricks of the previous round, in that order
+    Gathered([Card; 32]),
+    /// The previous order of the deck, cut at that position
+    Cut { previous: [Card; 32], position: usize },
+}
+
+impl DeckOrigin {
+    /// The order of the deck this origin gives
+    pub fn cards(&self) -> [Card; 32] {
+        match *self {
+            DeckOrigin::Shuffled(seed) => Deck::from_seed(seed).cards,
+            DeckOrigin::Gathered(cards) => cards,
+            DeckOrigin::Cut { mut previous, position } => {
+                previous.rotate_left(position);
+                previous
+            },
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Deck {
     pub cards: [Card; 32],
-    randomness: StdRng,
+    /// How the cards got in that order, to be revealed once the round is over
+    origin: DeckOrigin,
+    /// Random bytes hashed with the cards so the commitment can't be guessed, eg by trying
+    /// every cut of gathered tricks everyone has seen. Revealed along with the origin
+    nonce: u64,
+}
+
+impl Default for Deck {
+    fn default() -> Deck {
+        Deck::new()
+    }
 }
 
 impl Deck {
     pub fn new() -> Deck {
-        let mut cards = [Card::new(Suit::Spades, Rank::Ace); 32];
-
-        let mut i = 0;
-        for suit in Suit::iterator() {
-            for rank in Rank::iterator() {
-                cards[i] = Card::new(*suit, *rank);
-                i += 1;
-            }
-        }
+        Deck::from_seed(rand::random())
+    }
 
+    /// Creates a deck shuffled with the given seed.
+    /// The same seed always gives the same order, which is what allows auditing a deal
+    pub fn from_seed(seed: u64) -> Deck {
         let mut deck = Deck {
-            cards,
-            randomness: StdRng::new().unwrap(),
+            cards: [Card::new(Suit::Spades, Rank::Ace); 32],
+            origin: DeckOrigin::Shuffled(seed),
+            nonce: seed,
         };
-        // Always return a shuffled deck
-        deck.shuffle();
+        deck.shuffle_with_seed(seed);
         deck
     }
 
     /// Shuffle the cards in the deck in-place
     pub fn shuffle(&mut self) {
-        self.randomness.shuffle(&mut self.cards);
+        self.shuffle_with_seed(rand::random());
+    }
+
+    /// Puts the cards back in order and shuffle them using the given seed.
+    /// We always start from the ordered deck so the seed alone is enough to recompute it.
+    /// The seed is secret until the round is over so it is also used as the nonce
+    pub(crate) fn shuffle_with_seed(&mut self, seed: u64) {
+        self.cards = Card::ALL;
+
+        let mut rng = Isaac64Rng::from_seed(&[seed]);
+        rng.shuffle(&mut self.cards);
+        self.origin = DeckOrigin::Shuffled(seed);
+        self.nonce = seed;
+    }
+
+    /// Rebuilds the deck from the tricks of the previous round, in the order they were gathered.
+    /// This is how the deck is traditionally put back together between deals instead of
+    /// shuffling it again.
+    pub fn from_gathered_tricks(tricks: &[Vec<Card>]) -> Result<Deck, Error> {
+        let mut cards = [Card::new(Suit::Spades, Rank::Ace); 32];
+
+        let mut i = 0;
+        for card in tricks.iter().flat_map(|t| t.iter()) {
+            if i == 32 {
+                bail!("Too many cards in the gathered tricks");
+            }
+            if cards[..i].contains(card) {
+                bail!("Card {} appears twice in the gathered tricks", card);
+            }
+            cards[i] = *card;
+            i += 1;
+        }
+        if i != 32 {
+            bail!("Expected 32 cards in the gathered tricks, got {}", i);
+        }
+
+        Ok(Deck { cards, origin: DeckOrigin::Gathered(cards), nonce: rand::random() })
+    }
+
+    /// Cut the deck: the first `position` cards are moved under the rest of the deck.
+    /// A valid cut has to leave at least 3 cards in each part
+    pub fn cut(&mut self, position: usize) -> Result<(), Error> {
+        self.cut_with_nonce(position, rand::random())
+    }
+
+    /// Same as `cut` with the nonce of the new commitment given, for seeded games
+    pub(crate) fn cut_with_nonce(&mut self, position: usize, nonce: u64) -> Result<(), Error> {
+        if !(3..=29).contains(&position) {
+            bail!("A cut must leave at least 3 cards on each side");
+        }
+        self.origin = DeckOrigin::Cut { previous: self.cards, position };
+        self.nonce = nonce;
+        self.cards.rotate_left(position);
+        Ok(())
+    }
+
+    /// Cut the deck at a random valid position
+    pub fn random_cut(&mut self) {
+        let position = rand::thread_rng().gen_range(3, 30);
+        // can't fail, the position is always valid
+        self.cut(position).unwrap();
+    }
+
+    /// The seed used to shuffle the cards, if they were shuffled.
+    /// It should only be revealed after the round is over.
+    pub fn seed(&self) -> Option<u64> {
+        match self.origin {
+            DeckOrigin::Shuffled(seed) => Some(seed),
+            _ => None,
+        }
+    }
+
+    /// How the cards got in their current order.
+    /// It should only be revealed after the round is over.
+    pub fn origin(&self) -> DeckOrigin {
+        self.origin
+    }
+
+    /// A commitment to the current order of the deck that can be shown to players
+    /// before dealing without giving away anything about the cards
+    pub fn commitment(&self) -> DeckCommitment {
+        commit(&self.cards, self.nonce)
     }
 
     /// Deal the cards to all players, essentially 4 arrays of 8 cards
     /// Dealing in belote is done in a 3-2-3 way
     pub fn deal(&self) -> [Vec<Card>; 4] {
-        let mut cards1 = vec![];
-        let mut cards2 = vec![];
-        let mut cards3 = vec![];
-        let mut cards4 = vec![];
+        // 3-2-3 is always a valid pattern
+        self.deal_with_pattern(&[3, 2, 3]).unwrap()
+    }
+
+    /// Same as `deal` without allocating, for simulations dealing many times
+    pub fn deal_array(&self) -> [[Card; 8]; 4] {
+        let mut hands = [[Card::new(Suit::Spades, Rank::Ace); 8]; 4];
+        self.deal_into(&mut hands);
+        hands
+    }
+
+    /// Same as `deal_array`, writing the hands in a buffer that can be reused
+    pub fn deal_into(&self, hands: &mut [[Card; 8]; 4]) {
+        self.fill(&[3, 2, 3], hands);
+    }
+
+    /// Deal the cards to all players, giving each of them the number of cards
+    /// of each step of the pattern in turn, eg `&[3, 3, 2]`.
+    /// Every player needs to end up with 8 cards.
+    pub fn deal_with_pattern(&self, pattern: &[usize]) -> Result<[Vec<Card>; 4], Error> {
+        if pattern.contains(&0) {
+            bail!("A dealing pattern cannot have a step of 0 cards");
+        }
+        if pattern.iter().sum::<usize>() != 8 {
+            bail!("A dealing pattern must give 8 cards to each player");
+        }
+
+        let mut hands = [[Card::new(Suit::Spades, Rank::Ace); 8]; 4];
+        self.fill(pattern, &mut hands);
+        Ok([hands[0].to_vec(), hands[1].to_vec(), hands[2].to_vec(), hands[3].to_vec()])
+    }
+
+    /// Deals following a pattern already checked by `deal_with_pattern`
+    fn fill(&self, pattern: &[usize], hands: &mut [[Card; 8]; 4]) {
+        // index in the deck and in the hands
+        let (mut j, mut k) = (0, 0);
+        for num_cards in pattern {
+            for hand in hands.iter_mut() {
+                hand[k..k+num_cards].copy_from_slice(&self.cards[j..j+num_cards]);
+                j += num_cards;
+            }
+            k += num_cards;
+        }
+    }
 
-        // index in the deck
+    /// First stage of the classic belote deal: 5 cards to each player, dealt 3-2.
+    /// The next card is then turned up with `turn_up`.
+    pub fn deal_first_stage(&self) -> [Vec<Card>; 4] {
+        let mut hands = [vec![], vec![], vec![], vec![]];
         let mut j = 0;
-        for i in 0..3 {
-            let num_cards = if i == 1 { 2 } else { 3 };
-            cards1.extend(self.cards[j..j+num_cards].iter().cloned());
-            j += num_cards;
-            cards2.extend(self.cards[j..j+num_cards].iter().cloned());
-            j += num_cards;
-            cards3.extend(self.cards[j..j+num_cards].iter().cloned());
-            j += num_cards;
-            cards4.extend(self.cards[j..j+num_cards].iter().cloned());
+        for num_cards in &[3, 2] {
+            for hand in hands.iter_mut() {
+                hand.extend(self.cards[j..j+num_cards].iter().cloned());
+                j += num_cards;
+            }
+        }
+        hands
+    }
+
+    /// The card turned up after the first stage, proposed as trump
+    pub fn turn_up(&self) -> Card {
+        self.cards[20]
+    }
+
+    /// Second stage of the classic belote deal, once someone took the turned-up card.
+    /// `taker` is the index of the taker hand: they get the turned-up card and 2 more cards
+    /// while everyone else gets 3 cards.
+    /// Returns the cards to add to each hand.
+    pub fn deal_second_stage(&self, taker: usize) -> Result<[Vec<Card>; 4], Error> {
+        if taker > 3 {
+            bail!("Invalid taker: there are only 4 hands");
+        }
+
+        let mut hands = [vec![], vec![], vec![], vec![]];
+        let mut j = 21;
+        for (i, hand) in hands.iter_mut().enumerate() {
+            let num_cards = if i == taker {
+                hand.push(self.turn_up());
+                2
+            } else {
+                3
+            };
+            hand.extend(self.cards[j..j+num_cards].iter().cloned());
             j += num_cards;
         }
 
-        [cards1, cards2, cards3, cards4]
+        Ok(hands)
+    }
+}
+
+/// Checks that the hands, indexed by `Player::index`, are a valid deal:
+/// 8 cards each and every card of the deck exactly once
+pub fn validate_deal(hands: &[Vec<Card>; 4]) -> Result<(), ContreeError> {
+    for player in Player::iterator() {
+        let size = hands[player.index()].len();
+        if size != 8 {
+            return Err(ContreeError::WrongHandSize { player: *player, size });
+        }
+    }
+    let mut cards = CardSet::new();
+    for &card in hands.iter().flat_map(|h| h.iter()) {
+        if !cards.insert(card) {
+            return Err(ContreeError::DuplicateCard(card));
+        }
+    }
+    Ok(())
+}
+
+/// A stable byte representation of a card, only used for hashing
+fn card_byte(card: &Card) -> u8 {
+    (card.suit as u8) * 8 + card.rank as u8
+}
+
+/// SHA-256 hash of the nonce and the order of the deck
+fn commit(cards: &[Card; 32], nonce: u64) -> DeckCommitment {
+    let mut data = Vec::with_capacity(8 + 32);
+    for i in 0..8 {
+        data.push((nonce >> (i * 8)) as u8);
+    }
+    for card in cards.iter() {
+        data.push(card_byte(card));
+    }
+    DeckCommitment(sha256::digest(&data))
+}
+
+/// SHA-256 hash of the nonce and the order of the deck
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DeckCommitment(pub [u8; 32]);
+
+impl fmt::Display for DeckCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in self.0.iter() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of auditing a deal: the revealed origin and nonce of the deck, the commitment
+/// that was shown before the deal and the hands as recomputed from the origin.
+/// It can be stored alongside the round and checked again at any time with `verify`.
+/// For a cut deck, checking that the previous order matches the tricks of the previous
+/// round is left to the caller.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DealAudit {
+    pub origin: DeckOrigin,
+    pub nonce: u64,
+    pub commitment: DeckCommitment,
+    pub hands: [Vec<Card>; 4],
+}
+
+impl DealAudit {
+    /// Recomputes the deck from the revealed origin, checks it matches the commitment
+    /// and that each player received exactly the cards they should have.
+    /// Hands are compared regardless of the order of the cards in them.
+    pub fn new(origin: DeckOrigin, nonce: u64, commitment: DeckCommitment, received: &[Vec<Card>; 4]) -> Result<DealAudit, Error> {
+        let cards = origin.cards();
+        if commit(&cards, nonce) != commitment {
+            bail!("The revealed deck doesn't match the commitment");
+        }
+
+        let hands = Deck { cards, origin, nonce }.deal();
+        for (expected, got) in hands.iter().zip(received.iter()) {
+            if expected.len() != got.len() || expected.iter().any(|c| !got.contains(c)) {
+                bail!("The cards received do not match the committed deck");
+            }
+        }
+
+        Ok(DealAudit { origin, nonce, commitment, hands })
+    }
+
+    /// Checks the audit again, for example after loading it from an archive
+    pub fn verify(&self) -> Result<(), Error> {
+        DealAudit::new(self.origin, self.nonce, self.commitment, &self.hands).map(|_| ())
+    }
+}
+
+/// What a game records about a deal for the commit-reveal scheme: the commitment is shown
+/// to the players before they see their cards and the origin of the deck is revealed
+/// once the round is over
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CommittedDeal {
+    pub commitment: DeckCommitment,
+    /// How the deck was shuffled or cut
+    pub origin: DeckOrigin,
+    pub nonce: u64,
+    /// The hands in the order they were dealt, first player first. In classic belote the
+    /// hands depend on who takes: these are the hands of `Deck::deal` and only the order of
+    /// the deck is checked against the commitment
+    pub hands: [Vec<Card>; 4],
+}
+
+impl CommittedDeal {
+    /// Creates the record of a deal of that deck, before dealing it
+    pub fn new(deck: &Deck) -> CommittedDeal {
+        CommittedDeal { commitment: deck.commitment(), origin: deck.origin, nonce: deck.nonce, hands: deck.deal() }
+    }
+
+    /// Checks that the origin of the deck matches the commitment and the hands dealt
+    pub fn audit(&self) -> Result<DealAudit, Error> {
+        DealAudit::new(self.origin, self.nonce, self.commitment, &self.hands)
+    }
+}
+
+impl fmt::Display for DeckOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cards = |cards: &[Card; 32]| cards.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", ");
+        match *self {
+            DeckOrigin::Shuffled(seed) => write!(f, "shuffled with seed {}", seed),
+            DeckOrigin::Gathered(ref gathered) => write!(f, "gathered: {}", cards(gathered)),
+            DeckOrigin::Cut { ref previous, position } => write!(f, "cut at {} of: {}", position, cards(previous)),
+        }
+    }
+}
+
+impl fmt::Display for DealAudit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "origin: {}", self.origin)?;
+        writeln!(f, "nonce: {}", self.nonce)?;
+        writeln!(f, "commitment: {}", self.commitment)?;
+        for (i, hand) in self.hands.iter().enumerate() {
+            let cards: Vec<String> = hand.iter().map(|c| c.to_string()).collect();
+            writeln!(f, "hand {}: {}", i + 1, cards.join(", "))?;
+        }
+        Ok(())
     }
 }
 
@@ -105,4 +432,146 @@ mod tests {
             cards.insert(c, true);
         }
     }
+
+    #[test]
+    fn can_validate_deals() {
+        let deck = Deck::from_seed(1);
+        let mut hands = deck.deal();
+        assert!(validate_deal(&hands).is_ok());
+        hands[0][0] = hands[1][0];
+        assert_eq!(validate_deal(&hands), Err(ContreeError::DuplicateCard(hands[1][0])));
+        hands[0].pop();
+        assert_eq!(validate_deal(&hands), Err(ContreeError::WrongHandSize { player: Player::South, size: 7 }));
+    }
+
+    #[test]
+    fn can_deal_without_allocating() {
+        let deck = Deck::from_seed(12);
+        let hands = deck.deal_array();
+        let dealt = deck.deal();
+        for i in 0..4 {
+            assert_eq!(hands[i][..], dealt[i][..]);
+        }
+        let mut buffer = [[Card::new(Suit::Clubs, Rank::Seven); 8]; 4];
+        Deck::from_seed(13).deal_into(&mut buffer);
+        assert_eq!(buffer, Deck::from_seed(13).deal_array());
+    }
+
+    #[test]
+    fn can_deal_with_other_patterns() {
+        let deck = Deck::new();
+        let hands = deck.deal_with_pattern(&[3, 3, 2]).unwrap();
+        assert_eq!(hands[0][..3], deck.cards[..3]);
+        assert_eq!(hands[0][3..6], deck.cards[12..15]);
+        assert_eq!(hands[3][6..], deck.cards[30..]);
+        assert_eq!(deck.deal_with_pattern(&[2, 3, 3]).unwrap()[1][..2], deck.cards[2..4]);
+
+        assert!(deck.deal_with_pattern(&[3, 3, 3]).is_err());
+        assert!(deck.deal_with_pattern(&[4, 0, 4]).is_err());
+        assert!(deck.deal_with_pattern(&[]).is_err());
+    }
+
+    #[test]
+    fn can_deal_in_two_stages() {
+        let deck = Deck::new();
+        let first = deck.deal_first_stage();
+        assert!(first.iter().all(|h| h.len() == 5));
+        assert_eq!(first[1][..3], deck.cards[3..6]);
+        assert_eq!(first[1][3..], deck.cards[14..16]);
+
+        let second = deck.deal_second_stage(2).unwrap();
+        assert_eq!(second[2].len(), 3);
+        assert_eq!(second[2][0], deck.turn_up());
+        assert!(second.iter().all(|h| h.len() == 3));
+
+        // no cards duplicate
+        let mut cards = HashMap::new();
+        for (a, b) in first.iter().zip(second.iter()) {
+            for c in a.iter().chain(b.iter()) {
+                assert!(!cards.contains_key(c));
+                cards.insert(c, true);
+            }
+        }
+        assert_eq!(cards.len(), 32);
+
+        assert!(deck.deal_second_stage(4).is_err());
+    }
+
+    #[test]
+    fn same_seed_gives_same_deck() {
+        let deck = Deck::from_seed(42);
+        let deck2 = Deck::from_seed(42);
+        assert_eq!(deck.cards, deck2.cards);
+        assert_eq!(deck.commitment(), deck2.commitment());
+        assert_ne!(deck.commitment(), Deck::from_seed(43).commitment());
+    }
+
+    #[test]
+    fn can_audit_a_deal() {
+        let deck = Deck::new();
+        let commitment = deck.commitment();
+        let mut hands = deck.deal();
+        hands[2].reverse();
+
+        let audit = DealAudit::new(deck.origin(), deck.nonce, commitment, &hands).unwrap();
+        assert!(audit.verify().is_ok());
+    }
+
+    #[test]
+    fn audit_detects_tampered_deal() {
+        let deck = Deck::new();
+        let commitment = deck.commitment();
+        let mut hands = deck.deal();
+        let card = hands[0].pop().unwrap();
+        hands[1].push(card);
+
+        let seed = deck.seed().unwrap();
+        assert!(DealAudit::new(deck.origin(), seed, commitment, &hands).is_err());
+        let other = DeckOrigin::Shuffled(seed.wrapping_add(1));
+        assert!(DealAudit::new(other, seed, commitment, &deck.deal()).is_err());
+        assert!(DealAudit::new(deck.origin(), seed.wrapping_add(1), commitment, &deck.deal()).is_err());
+    }
+
+    #[test]
+    fn can_rebuild_deck_from_tricks() {
+        let deck = Deck::new();
+        let tricks: Vec<Vec<Card>> = deck.cards.chunks(4).map(|c| c.to_vec()).collect();
+        let gathered = Deck::from_gathered_tricks(&tricks).unwrap();
+        assert_eq!(gathered.cards, deck.cards);
+        assert_eq!(gathered.seed(), None);
+
+        assert!(Deck::from_gathered_tricks(&tricks[1..]).is_err());
+        let mut duplicated = tricks.clone();
+        duplicated[0][0] = duplicated[1][0];
+        assert!(Deck::from_gathered_tricks(&duplicated).is_err());
+    }
+
+    #[test]
+    fn can_cut_deck() {
+        let mut deck = Deck::new();
+        let original = deck.cards;
+        assert!(deck.cut(2).is_err());
+        assert!(deck.cut(30).is_err());
+        assert!(deck.cut(5).is_ok());
+        assert_eq!(deck.cards[0], original[5]);
+        assert_eq!(deck.cards[27], original[0]);
+        assert_eq!(deck.origin(), DeckOrigin::Cut { previous: original, position: 5 });
+    }
+
+    #[test]
+    fn can_audit_a_cut_deck() {
+        let tricks: Vec<Vec<Card>> = Deck::from_seed(3).cards.chunks(4).map(|c| c.to_vec()).collect();
+        let mut deck = Deck::from_gathered_tricks(&tricks).unwrap();
+        let gathered = CommittedDeal::new(&deck);
+        assert!(gathered.audit().is_ok());
+
+        deck.cut(7).unwrap();
+        let committed = CommittedDeal::new(&deck);
+        assert_ne!(committed.commitment, gathered.commitment);
+        let audit = committed.audit().unwrap();
+        assert_eq!(audit.hands, deck.deal());
+        // Lying about the cut is caught
+        let lie = DeckOrigin::Cut { previous: Deck::from_gathered_tricks(&tricks).unwrap().cards, position: 8 };
+        assert!(DealAudit::new(lie, committed.nonce, committed.commitment, &committed.hands).is_err());
+    }
 }
@@ -0,0 +1,164 @@
+//! Differences between two views of the same player, so a server can send only what changed
+//! after an action instead of the full view. The client applies it with `PlayerView::apply_diff`.
+
+use failure::Error;
+
+use actions::Action;
+use bids::{Bid, BidSet, Contract};
+use cards::{Card, Suit};
+use hand::Hand;
+use players::Player;
+use team_scores::TeamScores;
+use view::PlayerView;
+
+
+/// How a list changed: items were added at the end, or it was replaced, eg at a new round
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ListDiff<T> {
+    Append(Vec<T>),
+    Replace(Vec<T>),
+}
+
+impl<T: Clone + PartialEq> ListDiff<T> {
+    fn between(old: &[T], new: &[T]) -> Option<ListDiff<T>> {
+        if old == new {
+            None
+        } else if new.starts_with(old) {
+            Some(ListDiff::Append(new[old.len()..].to_vec()))
+        } else {
+            Some(ListDiff::Replace(new.to_vec()))
+        }
+    }
+
+    fn apply(&self, list: &mut Vec<T>) {
+        match *self {
+            ListDiff::Append(ref items) => list.extend_from_slice(items),
+            ListDiff::Replace(ref items) => *list = items.clone(),
+        }
+    }
+}
+
+/// A finished trick with the player who won it
+type WonTrick = (Vec<(Player, Card)>, Player);
+
+fn changed<T: Clone + PartialEq>(old: &T, new: &T) -> Option<T> {
+    if old == new { None } else { Some(new.clone()) }
+}
+
+/// What changed between two views of a player. Fields are `None` when they didn't change
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct GameStateDiff {
+    pub hand: Option<Hand>,
+    pub bids: Option<ListDiff<(Player, Bid, Option<Suit>)>>,
+    pub available_bids: Option<BidSet>,
+    pub contract: Option<Option<Contract>>,
+    pub turned_up: Option<Option<Card>>,
+    pub current_trick: Option<ListDiff<(Player, Card)>>,
+    pub tricks: Option<ListDiff<WonTrick>>,
+    pub playable_cards: Option<Vec<Card>>,
+    pub totals: Option<TeamScores>,
+    pub legal_actions: Option<Vec<Action>>,
+}
+
+impl GameStateDiff {
+    /// The changes from `old` to `new`. Errors if they are not views of the same player
+    pub fn between(old: &PlayerView, new: &PlayerView) -> Result<GameStateDiff, Error> {
+        if old.player != new.player {
+            bail!("Cannot diff the views of {:?} and {:?}", old.player, new.player);
+        }
+        Ok(GameStateDiff {
+            hand: changed(&old.hand, &new.hand),
+            bids: ListDiff::between(&old.bids, &new.bids),
+            available_bids: changed(&old.available_bids, &new.available_bids),
+            contract: changed(&old.contract, &new.contract),
+            turned_up: changed(&old.turned_up, &new.turned_up),
+            current_trick: ListDiff::between(&old.current_trick, &new.current_trick),
+            tricks: ListDiff::between(&old.tricks, &new.tricks),
+            playable_cards: changed(&old.playable_cards, &new.playable_cards),
+            totals: changed(&old.totals, &new.totals),
+            legal_actions: changed(&old.legal_actions, &new.legal_actions),
+        })
+    }
+
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        *self == GameStateDiff::default()
+    }
+}
+
+impl PlayerView {
+    /// Updates the view with the changes computed by `GameStateDiff::between`
+    /// from a view equal to this one
+    pub fn apply_diff(&mut self, diff: &GameStateDiff) {
+        if let Some(ref hand) = diff.hand {
+            self.hand = hand.clone();
+        }
+        if let Some(ref bids) = diff.bids {
+            bids.apply(&mut self.bids);
+        }
+        if let Some(ref available_bids) = diff.available_bids {
+            self.available_bids = *available_bids;
+        }
+        if let Some(ref contract) = diff.contract {
+            self.contract = contract.clone();
+        }
+        if let Some(turned_up) = diff.turned_up {
+            self.turned_up = turned_up;
+        }
+        if let Some(ref current_trick) = diff.current_trick {
+            current_trick.apply(&mut self.current_trick);
+        }
+        if let Some(ref tricks) = diff.tricks {
+            tricks.apply(&mut self.tricks);
+        }
+        if let Some(ref playable_cards) = diff.playable_cards {
+            self.playable_cards = playable_cards.clone();
+        }
+        if let Some(ref totals) = diff.totals {
+            self.totals = *totals;
+        }
+        if let Some(ref legal_actions) = diff.legal_actions {
+            self.legal_actions = legal_actions.clone();
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::Game;
+    use rules::{RuleSet, Variant};
+
+    fn check_diffs_rebuild_the_views(rules: RuleSet, seed: u64) {
+        let mut game = Game::from_seed(rules, seed);
+        game.new_round();
+        let mut client = game.player_view(Player::North);
+        assert!(GameStateDiff::between(&client, &game.player_view(Player::South)).is_err());
+        assert!(GameStateDiff::between(&client, &client).unwrap().is_empty());
+
+        // Two full rounds, so lists are both appended to and replaced
+        while game.score_sheet().lines.len() < 2 {
+            let player = game.current_player().unwrap();
+            let action = game.hint(player).unwrap().action;
+            game.apply(player, action).unwrap();
+
+            let server = game.player_view(Player::North);
+            let diff = GameStateDiff::between(&client, &server).unwrap();
+            if let Some(ListDiff::Append(ref bids)) = diff.bids {
+                assert_eq!(bids.len(), 1);
+            }
+            client.apply_diff(&diff);
+            assert_eq!(client, server);
+        }
+    }
+    #[test]
+    fn diffs_rebuild_the_views() {
+        check_diffs_rebuild_the_views(RuleSet::default(), 2);
+    }
+
+    #[test]
+    fn diffs_rebuild_the_views_of_classic_belote() {
+        check_diffs_rebuild_the_views(RuleSet { variant: Variant::Classic, ..RuleSet::default() }, 3);
+    }
+}
@@ -0,0 +1,208 @@
+//! Duplicate play: the same deals are played at several tables and the scores are compared
+//! deal by deal, which takes most of the luck of the cards out of the comparison.
+//! The usual setup is two tables where the strategies swap seats, so each team plays
+//! both sides of every deal.
+
+use failure::Error;
+
+use bids::Contract;
+use cards::Card;
+use deck::Deck;
+use game::Game;
+use players::{Player, Team};
+use rules::RuleSet;
+use strategy::{choose_action, Strategy};
+
+
+/// A deal that can be played at several tables
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Deal {
+    /// The seed of the deck it was generated from, or any number for imported deals
+    pub id: u64,
+    /// The player who bids and plays first
+    pub first_player: Player,
+    /// The hands of the players, indexed by `Player::index`
+    pub hands: [Vec<Card>; 4],
+}
+
+impl Deal {
+    /// The deal of a deck shuffled with that seed
+    pub fn from_seed(id: u64, first_player: Player) -> Deal {
+        Deal { id, first_player, hands: Deck::from_seed(id).deal() }
+    }
+}
+
+/// `n` deals generated from the seeds `seed`, `seed + 1`..., the first player
+/// moving clockwise every deal as in a normal game
+pub fn generate_deals(seed: u64, n: usize) -> Vec<Deal> {
+    let mut first_player = Player::South;
+    (0..n)
+        .map(|i| {
+            let deal = Deal::from_seed(seed.wrapping_add(i as u64), first_player);
+            first_player = first_player.next_player();
+            deal
+        })
+        .collect()
+}
+
+/// What happened to one deal at one table
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DealResult {
+    pub deal_id: u64,
+    pub table: String,
+    /// `None` if everyone passed
+    pub contract: Option<Contract>,
+    /// Scores of the South/North and East/West teams for that deal
+    pub scores: (usize, usize),
+}
+
+/// Plays a single deal with the strategies, indexed by `Player::index`.
+/// Errors if a strategy tries to do something invalid
+pub fn play_deal(
+    deal: &Deal,
+    table: &str,
+    rules: &RuleSet,
+    strategies: &mut [Box<dyn Strategy>; 4],
+) -> Result<DealResult, Error> {
+    let mut game = Game::new(rules.clone());
+    game.new_round_with_hands(deal.first_player, &deal.hands)?;
+    let mut result = DealResult { deal_id: deal.id, table: table.to_string(), contract: None, scores: (0, 0) };
+
+    loop {
+        let player = match game.current_player() {
+            Some(p) => p,
+            None => bail!("The game is stuck: nobody can play"),
+        };
+        let view = game.player_view(player);
+        let bidding = game.is_bidding();
+        game.apply(player, choose_action(&mut *strategies[player.index()], &view, bidding))?;
+        // Everyone passed and the cards were dealt again
+        if bidding && game.is_bidding() && game.player_view(player).bids.is_empty() {
+            return Ok(result);
+        }
+        if let Some(line) = game.score_sheet().lines.first() {
+            result.contract = Some(line.contract.clone());
+            result.scores = (line.scores[Team::SouthNorth], line.scores[Team::EastWest]);
+            return Ok(result);
+        }
+    }
+}
+
+/// The results of one deal at every table, in the order the tables were given
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DealComparison {
+    pub deal_id: u64,
+    pub results: Vec<DealResult>,
+}
+
+impl DealComparison {
+    /// How much more the South/North team of the table scored on that deal than the
+    /// South/North team of the first table
+    pub fn difference(&self, table: &str) -> Option<isize> {
+        let first = self.results.first()?;
+        let other = self.results.iter().find(|r| r.table == table)?;
+        Some(other.scores.0 as isize - first.scores.0 as isize)
+    }
+}
+
+/// Plays every deal at every table, tables being a name and the strategies sitting there
+pub fn play_duplicate(
+    deals: &[Deal],
+    rules: &RuleSet,
+    tables: &mut [(&str, [Box<dyn Strategy>; 4])],
+) -> Result<Vec<DealComparison>, Error> {
+    let mut comparisons = Vec::with_capacity(deals.len());
+    for deal in deals {
+        let mut results = Vec::with_capacity(tables.len());
+        for &mut (name, ref mut strategies) in tables.iter_mut() {
+            results.push(play_deal(deal, name, rules, strategies)?);
+        }
+        comparisons.push(DealComparison { deal_id: deal.id, results });
+    }
+    Ok(comparisons)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use bids::Bid;
+    use bots::{HeuristicBot, RandomBot};
+    use card_set::CardSet;
+    use cards::Suit;
+    use view::PlayerView;
+
+    /// Plays like the strategy it wraps and keeps every hand it was dealt
+    struct Recorder<S: Strategy>(S, Rc<RefCell<Vec<Vec<Card>>>>);
+
+    impl<S: Strategy> Strategy for Recorder<S> {
+        fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>) {
+            let mut hands = self.1.borrow_mut();
+            if hands.last().map(|h| &h[..]) != Some(&view.hand[..]) {
+                hands.push(view.hand.to_vec());
+            }
+            self.0.choose_bid(view)
+        }
+
+        fn choose_card(&mut self, view: &PlayerView) -> Card {
+            self.0.choose_card(view)
+        }
+    }
+
+    #[test]
+    fn can_play_the_same_deals_at_several_tables() {
+        let deals = generate_deals(10, 4);
+        assert_eq!(deals[1].first_player, Player::West);
+        assert_eq!(deals[0], Deal::from_seed(10, Player::South));
+
+        // Heuristic bots sit South/North at the first table and East/West at the second one
+        let open_hands = Rc::new(RefCell::new(vec![]));
+        let closed_hands = Rc::new(RefCell::new(vec![]));
+        let mut tables: Vec<(&str, [Box<dyn Strategy>; 4])> = vec![
+            ("open", [
+                Box::new(Recorder(HeuristicBot::new(), open_hands.clone())), Box::new(RandomBot::from_seed(0)),
+                Box::new(HeuristicBot::new()), Box::new(RandomBot::from_seed(1)),
+            ]),
+            ("closed", [
+                Box::new(Recorder(RandomBot::from_seed(2), closed_hands.clone())), Box::new(HeuristicBot::new()),
+                Box::new(RandomBot::from_seed(3)), Box::new(HeuristicBot::new()),
+            ]),
+        ];
+        let comparisons = play_duplicate(&deals, &RuleSet::default(), &mut tables).unwrap();
+        assert_eq!(comparisons.len(), 4);
+        for (comparison, deal) in comparisons.iter().zip(&deals) {
+            assert_eq!(comparison.deal_id, deal.id);
+            assert_eq!(comparison.results[0].table, "open");
+            assert_eq!(comparison.results[1].table, "closed");
+            assert!(comparison.results.iter().all(|r| r.deal_id == deal.id));
+            assert_eq!(comparison.difference("open"), Some(0));
+            let (open, closed) = (comparison.results[0].scores.0, comparison.results[1].scores.0);
+            assert_eq!(comparison.difference("closed"), Some(closed as isize - open as isize));
+            assert!(comparison.difference("nope").is_none());
+        }
+        // South got the same cards at both tables but the tables didn't play them the same way
+        let expected: Vec<Vec<Card>> = deals.iter().map(|d| d.hands[Player::South.index()].clone()).collect();
+        let as_sets = |hands: &[Vec<Card>]| -> Vec<CardSet> {
+            hands.iter().map(|h| h.iter().cloned().collect()).collect()
+        };
+        assert_eq!(as_sets(&open_hands.borrow()), as_sets(&expected));
+        assert_eq!(as_sets(&closed_hands.borrow()), as_sets(&expected));
+        assert!(comparisons.iter().any(|c| c.difference("closed") != Some(0)));
+    }
+
+    #[test]
+    fn deals_must_be_valid() {
+        let mut game = Game::default();
+        let mut deal = Deal::from_seed(0, Player::North);
+        assert!(game.new_round_with_hands(deal.first_player, &deal.hands).is_ok());
+        assert_eq!(game.current_player(), Some(Player::North));
+        assert_eq!(*game.player_view(Player::East).hand, deal.hands[Player::East.index()][..]);
+
+        deal.hands[0][0] = deal.hands[1][0];
+        assert!(Game::default().new_round_with_hands(Player::South, &deal.hands).is_err());
+        deal.hands[0].pop();
+        assert!(Game::default().new_round_with_hands(Player::South, &deal.hands).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! A gym-style environment to train an agent against the rules engine: the agent plays
+//! one seat and scripted strategies play the three others.
+
+use failure::Error;
+
+use actions::{action_space, Action};
+use bots::heuristic_table;
+use game::{Game, MatchResult};
+use players::Player;
+use rules::RuleSet;
+use strategy::{choose_action, Strategy};
+use view::PlayerView;
+
+
+/// What the agent sees before acting
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub view: PlayerView,
+    /// For each action of `action_space()`, whether it is legal right now
+    pub action_mask: Vec<bool>,
+}
+
+/// An environment where one episode is a full game.
+/// The reward is given at the end of every round: the points of the team of the agent
+/// minus the points of the opponents for that round, divided by 162.
+pub struct Env {
+    rules: RuleSet,
+    seat: Player,
+    /// Strategies of the other players, indexed by `Player::index`. The one of the agent is unused
+    strategies: [Box<dyn Strategy>; 4],
+    game: Game,
+    seed: u64,
+    actions: Vec<Action>,
+}
+
+impl Env {
+    /// The agent plays `seat` and the `HeuristicBot` plays the other seats
+    pub fn new(rules: RuleSet, seat: Player, seed: u64) -> Env {
+        Env::with_strategies(rules, seat, seed, heuristic_table())
+    }
+
+    /// The agent plays `seat` and the strategies, indexed by `Player::index`, play the others.
+    /// Every `reset` starts a new game with the next seed, starting from `seed`
+    pub fn with_strategies(rules: RuleSet, seat: Player, seed: u64, strategies: [Box<dyn Strategy>; 4]) -> Env {
+        Env {
+            game: Game::from_seed(rules.clone(), seed),
+            rules,
+            seat,
+            strategies,
+            seed,
+            actions: action_space(),
+        }
+    }
+
+    /// How many actions there are in the action space
+    pub fn action_count(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Starts a new game and returns the first observation of the agent
+    pub fn reset(&mut self) -> Result<Observation, Error> {
+        self.game = Game::from_seed(self.rules.clone(), self.seed);
+        self.seed = self.seed.wrapping_add(1);
+        self.game.new_round();
+        self.play_others()?;
+        Ok(self.observe())
+    }
+
+    /// Plays the action with that index in `action_space()` for the agent, then lets the other
+    /// players act until it's the turn of the agent again or the game is over.
+    /// Returns the next observation, the reward and whether the game is over.
+    /// Errors if the action is not legal
+    pub fn step(&mut self, action: usize) -> Result<(Observation, f64, bool), Error> {
+        if self.is_done() {
+            bail!("The game is over, call reset to start a new one");
+        }
+        let action = match self.actions.get(action) {
+            Some(a) => *a,
+            None => bail!("There is no action {}", action),
+        };
+        let rounds_before = self.game.score_sheet().lines.len();
+        self.game.apply(self.seat, action)?;
+        self.play_others()?;
+
+        let sheet = self.game.score_sheet();
+        let team = self.seat.team();
+        let reward = sheet.lines[rounds_before..]
+            .iter()
+            .map(|line| (line.scores[team] as f64 - line.scores[team.opponent()] as f64) / 162.0)
+            .sum();
+        Ok((self.observe(), reward, self.is_done()))
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(self.game.standing().result, MatchResult::Won(_))
+    }
+
+    fn play_others(&mut self) -> Result<(), Error> {
+        while !self.is_done() {
+            let player = match self.game.current_player() {
+                Some(p) if p != self.seat => p,
+                _ => break,
+            };
+            let view = self.game.player_view(player);
+            let action = choose_action(&mut *self.strategies[player.index()], &view, self.game.is_bidding());
+            self.game.apply(player, action)?;
+        }
+        Ok(())
+    }
+
+    fn observe(&self) -> Observation {
+        let view = self.game.player_view(self.seat);
+        let action_mask = self.actions.iter().map(|a| view.legal_actions.contains(a)).collect();
+        Observation { view, action_mask }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand::isaac::Isaac64Rng;
+
+    #[test]
+    fn can_play_full_episodes() {
+        let mut env = Env::new(RuleSet::default(), Player::West, 0);
+        let mut rng = Isaac64Rng::from_seed(&[0]);
+        assert_eq!(env.action_count(), 80);
+
+        for _ in 0..2 {
+            let mut observation = env.reset().unwrap();
+            let mut total_reward = 0.0;
+            loop {
+                let legal: Vec<usize> = (0..env.action_count()).filter(|&i| observation.action_mask[i]).collect();
+                assert_eq!(legal.len(), observation.view.legal_actions.len());
+                assert!(env.step(env.action_count()).is_err());
+
+                let (next, reward, done) = env.step(*rng.choose(&legal).unwrap()).unwrap();
+                total_reward += reward;
+                observation = next;
+                if done {
+                    break;
+                }
+            }
+            assert!(total_reward != 0.0);
+            assert!(env.step(0).is_err());
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! The reasons an action can be refused. Functions still return `failure::Error` but the
+//! errors below can be recovered with `error.downcast_ref::<ContreeError>()` to match on them.
+//!
+//! Clients that don't share the types, eg over the network, can use `code` and `number`,
+//! which never change once published, and `details` to render the error in their language.
+//! Details use the encoding of the `vectors` module: `S` for South, `JH` for the jack of hearts,
+//! `80H` for a bid, lists being space separated.
+
+use std::error;
+use std::fmt;
+
+use bids::Bid;
+use cards::{Card, Suit};
+use players::Player;
+use vectors::{bid_code, card_code, player_code, suit_code};
+
+
+/// The phases of a deal in which actions can be made
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Phase {
+    Bidding,
+    Taking,
+    Playing,
+}
+
+/// Why an action was refused
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ContreeError {
+    /// The action was made by `got` while `expected` had to act
+    WrongPlayer { expected: Player, got: Player },
+    /// The player asked for something only the player whose turn it is can get
+    NotYourTurn { player: Player, current: Option<Player> },
+    /// The bid is not available to the player right now
+    BidNotAllowed { bid: Bid, allowed: Vec<Bid> },
+    /// Every bid other than pass, counter and double counter needs a suit
+    SuitRequired(Bid),
+    /// The suit cannot be taken as trump
+    SuitNotAllowed { suit: Suit, allowed: Vec<Suit> },
+    CardNotInHand { card: Card, playable: Vec<Card> },
+    /// The player has a card of the suit led and must play it
+    MustFollowSuit { card: Card, playable: Vec<Card> },
+    /// The player cannot follow and must play a trump
+    MustTrump { card: Card, playable: Vec<Card> },
+    /// The player must play a higher trump than the ones in the trick
+    MustOvertrump { card: Card, playable: Vec<Card> },
+    /// The action belongs to a phase the game is not in
+    PhaseMismatch { expected: Phase },
+    /// There is no legal action with that index, `count` being the number of legal actions
+    NoSuchAction { index: usize, count: usize },
+    /// A dealt hand doesn't have 8 cards
+    WrongHandSize { player: Player, size: usize },
+    /// The same card was dealt twice
+    DuplicateCard(Card),
+}
+
+fn codes<T: Copy, C: fmt::Display>(items: &[T], code: fn(T) -> C) -> String {
+    items.iter().map(|i| code(*i).to_string()).collect::<Vec<_>>().join(" ")
+}
+
+impl ContreeError {
+    /// A stable identifier of the kind of error
+    pub fn code(&self) -> &'static str {
+        match *self {
+            ContreeError::WrongPlayer { .. } => "wrong_player",
+            ContreeError::NotYourTurn { .. } => "not_your_turn",
+            ContreeError::BidNotAllowed { .. } => "bid_not_allowed",
+            ContreeError::SuitRequired(_) => "suit_required",
+            ContreeError::SuitNotAllowed { .. } => "suit_not_allowed",
+            ContreeError::CardNotInHand { .. } => "card_not_in_hand",
+            ContreeError::MustFollowSuit { .. } => "must_follow_suit",
+            ContreeError::MustTrump { .. } => "must_trump",
+            ContreeError::MustOvertrump { .. } => "must_overtrump",
+            ContreeError::PhaseMismatch { .. } => "phase_mismatch",
+            ContreeError::NoSuchAction { .. } => "no_such_action",
+            ContreeError::WrongHandSize { .. } => "wrong_hand_size",
+            ContreeError::DuplicateCard(_) => "duplicate_card",
+        }
+    }
+
+    /// The same as `code` as a number, grouped by tens: turns, bids, cards, phases and deals
+    pub fn number(&self) -> u16 {
+        match *self {
+            ContreeError::WrongPlayer { .. } => 1,
+            ContreeError::NotYourTurn { .. } => 2,
+            ContreeError::BidNotAllowed { .. } => 10,
+            ContreeError::SuitRequired(_) => 11,
+            ContreeError::SuitNotAllowed { .. } => 12,
+            ContreeError::CardNotInHand { .. } => 20,
+            ContreeError::MustFollowSuit { .. } => 21,
+            ContreeError::MustTrump { .. } => 22,
+            ContreeError::MustOvertrump { .. } => 23,
+            ContreeError::PhaseMismatch { .. } => 30,
+            ContreeError::NoSuchAction { .. } => 31,
+            ContreeError::WrongHandSize { .. } => 40,
+            ContreeError::DuplicateCard(_) => 41,
+        }
+    }
+
+    /// The data of the error as key/value pairs, in a stable order
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        match *self {
+            ContreeError::WrongPlayer { expected, got } => vec![
+                ("expected", player_code(expected).to_string()),
+                ("got", player_code(got).to_string()),
+            ],
+            ContreeError::NotYourTurn { player, current } => vec![
+                ("player", player_code(player).to_string()),
+                ("current", current.map_or("-".to_string(), |p| player_code(p).to_string())),
+            ],
+            ContreeError::BidNotAllowed { bid, ref allowed } => vec![
+                ("bid", bid_code(bid, None)),
+                ("allowed", codes(allowed, |b| bid_code(b, None))),
+            ],
+            ContreeError::SuitRequired(bid) => vec![("bid", bid_code(bid, None))],
+            ContreeError::SuitNotAllowed { suit, ref allowed } => vec![
+                ("suit", suit_code(suit).to_string()),
+                ("allowed", codes(allowed, suit_code)),
+            ],
+            ContreeError::CardNotInHand { card, ref playable }
+            | ContreeError::MustFollowSuit { card, ref playable }
+            | ContreeError::MustTrump { card, ref playable }
+            | ContreeError::MustOvertrump { card, ref playable } => vec![
+                ("card", card_code(card)),
+                ("playable", codes(playable, card_code)),
+            ],
+            ContreeError::PhaseMismatch { expected } => {
+                vec![("expected", format!("{:?}", expected).to_lowercase())]
+            },
+            ContreeError::NoSuchAction { index, count } => vec![
+                ("index", index.to_string()),
+                ("count", count.to_string()),
+            ],
+            ContreeError::WrongHandSize { player, size } => vec![
+                ("player", player_code(player).to_string()),
+                ("size", size.to_string()),
+            ],
+            ContreeError::DuplicateCard(card) => vec![("card", card_code(card))],
+        }
+    }
+}
+
+impl fmt::Display for ContreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ContreeError::WrongPlayer { .. } => write!(f, "Wrong player"),
+            ContreeError::NotYourTurn { player, .. } => write!(f, "It's not the turn of {:?}", player),
+            ContreeError::BidNotAllowed { .. } => write!(f, "Bid not possible"),
+            ContreeError::SuitRequired(_) => {
+                write!(f, "A bid other than pass/counter/doublecounter must have a suit associated")
+            },
+            ContreeError::SuitNotAllowed { .. } => write!(f, "Suit not possible"),
+            ContreeError::CardNotInHand { .. } => write!(f, "Card not in hand"),
+            ContreeError::MustFollowSuit { .. } => write!(f, "Card not playable: the suit led must be followed"),
+            ContreeError::MustTrump { .. } => write!(f, "Card not playable: a trump must be played"),
+            ContreeError::MustOvertrump { .. } => write!(f, "Card not playable: a higher trump must be played"),
+            ContreeError::PhaseMismatch { expected } => write!(f, "Not in the {:?} phase", expected),
+            ContreeError::NoSuchAction { index, .. } => write!(f, "No legal action with the index {}", index),
+            ContreeError::WrongHandSize { player, .. } => write!(f, "{:?} must get 8 cards", player),
+            ContreeError::DuplicateCard(_) => write!(f, "The same card was dealt twice"),
+        }
+    }
+}
+
+impl error::Error for ContreeError {}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Rank;
+
+    #[test]
+    fn errors_have_codes_and_details() {
+        let error = ContreeError::MustFollowSuit {
+            card: Card::new(Suit::Clubs, Rank::Ace),
+            playable: vec![Card::new(Suit::Spades, Rank::Seven), Card::new(Suit::Spades, Rank::Jack)],
+        };
+        assert_eq!(error.code(), "must_follow_suit");
+        assert_eq!(error.number(), 21);
+        assert_eq!(error.details(), vec![("card", "AC".to_string()), ("playable", "7S JS".to_string())]);
+
+        let error = ContreeError::BidNotAllowed { bid: Bid::Counter, allowed: vec![Bid::Pass, Bid::Ninety] };
+        assert_eq!(error.details(), vec![("bid", "counter".to_string()), ("allowed", "pass 90".to_string())]);
+        let error = ContreeError::NotYourTurn { player: Player::North, current: None };
+        assert_eq!(error.details()[1], ("current", "-".to_string()));
+    }
+}
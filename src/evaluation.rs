@@ -0,0 +1,170 @@
+//! Rough evaluation of a hand before the bidding, for bots and bidding helpers.
+
+use bids::Bid;
+use cards::{Card, Rank, Suit};
+use round::LAST_TRICK_POINTS;
+
+
+/// What is trump for the evaluation
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TrumpMode {
+    Suit(Suit),
+    /// No suit is trump and every suit uses the plain order and points
+    NoTrump,
+    /// Every suit uses the trump order and points
+    AllTrump,
+}
+
+impl TrumpMode {
+    fn is_trump(&self, suit: Suit) -> bool {
+        match *self {
+            TrumpMode::Suit(trump) => suit == trump,
+            TrumpMode::NoTrump => false,
+            TrumpMode::AllTrump => true,
+        }
+    }
+
+    fn points(&self, card: Card) -> usize {
+        if self.is_trump(card.suit) {
+            card.points(card.suit)
+        } else {
+            // Any other suit works as the points only depend on the card being trump or not
+            let other = if card.suit == Suit::Clubs { Suit::Spades } else { Suit::Clubs };
+            card.points(other)
+        }
+    }
+
+    /// The ranks of the suit from the strongest to the weakest
+    fn order(&self, suit: Suit) -> &'static [Rank; 8] {
+        if self.is_trump(suit) { Rank::trump_order() } else { Rank::plain_order() }
+    }
+}
+
+/// What a hand is likely to be worth with a given trump
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct HandEvaluation {
+    /// How many trumps are in the hand
+    pub trump_length: usize,
+    /// Tricks the hand wins whatever the others hold: the masters of each suit,
+    /// at most 2 per side suit when there is a trump as the next ones are likely to be trumped
+    pub sure_tricks: usize,
+    /// Trick points the team can expect: the points of the sure tricks, with average cards
+    /// from the other players, and half of everything else
+    pub expected_points: usize,
+    /// The highest bid the hand supports on its own, if any.
+    /// Only suit contracts can be bid so this is always `None` for the other modes
+    pub max_bid: Option<Bid>,
+}
+
+/// Sure tricks of a single suit: the cards held from the top of the suit without any gap
+fn masters(hand: &[Card], suit: Suit, trump: TrumpMode) -> Vec<Card> {
+    let mut masters = vec![];
+    for &rank in trump.order(suit) {
+        let card = Card::new(suit, rank);
+        if !hand.contains(&card) {
+            break;
+        }
+        masters.push(card);
+    }
+    if let TrumpMode::Suit(_) = trump {
+        if !trump.is_trump(suit) {
+            masters.truncate(2);
+        }
+    }
+    masters
+}
+
+/// The value the hand can bid up to with that trump: trump points, 10 per trump and
+/// 10 per outside ace, as long as it has at least 3 trumps including the Jack or the Nine
+fn bid_estimate(hand: &[Card], trump: Suit) -> usize {
+    let trumps: Vec<&Card> = hand.iter().filter(|c| c.suit == trump).collect();
+    let has_master = trumps.iter().any(|c| c.rank == Rank::Jack || c.rank == Rank::Nine);
+    if trumps.len() < 3 || !has_master {
+        return 0;
+    }
+
+    let trump_points: usize = trumps.iter().map(|c| c.points(trump)).sum();
+    let outside_aces = hand.iter().filter(|c| c.suit != trump && c.rank == Rank::Ace).count();
+    trump_points + 10 * trumps.len() + 10 * outside_aces
+}
+
+/// Evaluates a hand of 8 cards (or fewer, before the end of the deal) with the given trump
+pub fn evaluate_hand(hand: &[Card], trump: TrumpMode) -> HandEvaluation {
+    let trump_length = hand.iter().filter(|c| trump.is_trump(c.suit)).count();
+    let sure: Vec<Card> = Suit::iterator().flat_map(|s| masters(hand, *s, trump)).collect();
+
+    let all_points: usize = Card::ALL.iter().map(|c| trump.points(*c)).sum();
+    let hand_points: usize = hand.iter().map(|c| trump.points(*c)).sum();
+    let unseen_cards = 32 - hand.len();
+    let average_unseen = (all_points - hand_points).checked_div(unseen_cards).unwrap_or(0);
+
+    let total = all_points + LAST_TRICK_POINTS;
+    let sure_points: usize = sure.iter().map(|c| trump.points(*c) + 3 * average_unseen).sum();
+    let sure_points = sure_points.min(total);
+    let expected_points = sure_points + (total - sure_points) / 2;
+
+    let max_bid = match trump {
+        TrumpMode::Suit(suit) => {
+            let estimate = bid_estimate(hand, suit);
+            Bid::iterator()
+                .filter(|b| **b != Bid::Capot && b.requires_suit() && b.value() <= estimate)
+                .cloned()
+                .max()
+        },
+        _ => None,
+    };
+
+    HandEvaluation { trump_length, sure_tricks: sure.len(), expected_points, max_bid }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand() -> Vec<Card> {
+        vec![
+            Card::new(Suit::Hearts, Rank::Jack),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::Seven),
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Spades, Rank::Ten),
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Diamonds, Rank::Eight),
+        ]
+    }
+
+    #[test]
+    fn can_evaluate_strong_hand() {
+        let evaluation = evaluate_hand(&hand(), TrumpMode::Suit(Suit::Hearts));
+        assert_eq!(evaluation.trump_length, 4);
+        // J 9 A of hearts and A 10 of spades, the King being capped
+        assert_eq!(evaluation.sure_tricks, 5);
+        assert!(evaluation.expected_points > 120);
+        // 20 + 14 + 11 + 40 + 10 = 95
+        assert_eq!(evaluation.max_bid, Some(Bid::Ninety));
+    }
+
+    #[test]
+    fn can_evaluate_weak_suit() {
+        let evaluation = evaluate_hand(&hand(), TrumpMode::Suit(Suit::Diamonds));
+        assert_eq!(evaluation.trump_length, 1);
+        assert_eq!(evaluation.max_bid, None);
+        assert!(evaluation.expected_points < evaluate_hand(&hand(), TrumpMode::Suit(Suit::Hearts)).expected_points);
+    }
+
+    #[test]
+    fn can_evaluate_other_modes() {
+        let no_trump = evaluate_hand(&hand(), TrumpMode::NoTrump);
+        assert_eq!(no_trump.trump_length, 0);
+        // A 10 K of spades and the Ace of hearts
+        assert_eq!(no_trump.sure_tricks, 4);
+        assert_eq!(no_trump.max_bid, None);
+
+        let all_trump = evaluate_hand(&hand(), TrumpMode::AllTrump);
+        assert_eq!(all_trump.trump_length, 8);
+        // J 9 A of hearts only
+        assert_eq!(all_trump.sure_tricks, 3);
+    }
+}
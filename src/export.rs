@@ -0,0 +1,155 @@
+//! Exports self-play games as training data: one JSON object per line for every decision,
+//! with the encoded observation, the legal actions, the action taken and the outcome.
+//!
+//! Every line looks like:
+//!
+//! ```text
+//! {"seed":3,"player":2,"observation":[0,1,...],"legal_actions":[0,1,5],"action":5,"round_outcome":-0.35,"game_outcome":1}
+//! ```
+//!
+//! `player` is the `Player::index` of the player deciding, `observation` is `PlayerView::encode`,
+//! actions are indices in `action_space()`. `round_outcome` is the score of the team of the player
+//! minus the one of the opponents for that round, divided by 162, and `game_outcome` is 1 if the
+//! team won the game and -1 otherwise.
+
+use std::io::Write;
+
+use failure::Error;
+
+use actions::{action_space, Action};
+use game::Game;
+use players::{Player, Team};
+use rules::RuleSet;
+use strategy::{choose_action, play_until_won, Strategy};
+
+
+/// Which games to export
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ExportConfig {
+    /// Total number of games across all the shards
+    pub games: usize,
+    /// Game `i` is played with the seed `seed + i`
+    pub seed: u64,
+    /// Only the games where `i % shards == shard` are exported, so several processes
+    /// can each produce a part of the data
+    pub shard: usize,
+    pub shards: usize,
+}
+
+impl ExportConfig {
+    pub fn new(games: usize, seed: u64) -> ExportConfig {
+        ExportConfig { games, seed, shard: 0, shards: 1 }
+    }
+
+    /// The seeds of the games of that shard
+    fn seeds(&self) -> Vec<u64> {
+        (0..self.games)
+            .filter(|i| i % self.shards.max(1) == self.shard)
+            .map(|i| self.seed.wrapping_add(i as u64))
+            .collect()
+    }
+}
+
+/// A decision waiting for the end of the round and of the game to know its outcome
+struct Decision {
+    player: Player,
+    observation: Vec<f32>,
+    legal_actions: Vec<usize>,
+    action: usize,
+    round: usize,
+}
+
+fn json_list<T: ToString>(values: &[T]) -> String {
+    let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Plays the games of the shard with the strategies, indexed by `Player::index`, and writes
+/// every decision to `writer`. Returns how many lines were written.
+/// Errors if a strategy does something invalid or if writing fails
+pub fn export_self_play<W: Write>(
+    writer: &mut W,
+    rules: &RuleSet,
+    strategies: &mut [Box<dyn Strategy>; 4],
+    config: &ExportConfig,
+) -> Result<usize, Error> {
+    let actions = action_space();
+    let index_of = |action: Action| actions.iter().position(|a| *a == action).unwrap();
+    let mut lines = 0;
+
+    for seed in config.seeds() {
+        let mut game = Game::from_seed(rules.clone(), seed);
+        game.new_round();
+        let mut decisions = vec![];
+
+        let winner = play_until_won(&mut game, |game, player| {
+            let view = game.player_view(player);
+            let action = choose_action(&mut *strategies[player.index()], &view, game.is_bidding());
+            decisions.push(Decision {
+                player,
+                observation: view.encode(),
+                legal_actions: view.legal_actions.iter().map(|a| index_of(*a)).collect(),
+                action: index_of(action),
+                round: game.score_sheet().lines.len(),
+            });
+            game.apply(player, action)
+        })?;
+
+        let sheet = game.score_sheet();
+        for decision in decisions {
+            let team: Team = decision.player.team();
+            // Decisions of deals where everyone passed belong to the next round played
+            let round_outcome = sheet.lines.get(decision.round).map_or(0.0, |line| {
+                (line.scores[team] as f64 - line.scores[team.opponent()] as f64) / 162.0
+            });
+            let game_outcome = if team == winner { 1 } else { -1 };
+            writeln!(
+                writer,
+                "{{\"seed\":{},\"player\":{},\"observation\":{},\"legal_actions\":{},\"action\":{},\"round_outcome\":{},\"game_outcome\":{}}}",
+                seed,
+                decision.player.index(),
+                json_list(&decision.observation),
+                json_list(&decision.legal_actions),
+                decision.action,
+                round_outcome,
+                game_outcome,
+            )?;
+            lines += 1;
+        }
+    }
+    Ok(lines)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::heuristic_table;
+
+    fn export(config: &ExportConfig) -> String {
+        let mut output = vec![];
+        let lines = export_self_play(&mut output, &RuleSet::default(), &mut heuristic_table(), config).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), lines);
+        output
+    }
+
+    #[test]
+    fn can_export_games() {
+        let output = export(&ExportConfig::new(1, 7));
+        let first = output.lines().next().unwrap();
+        assert!(first.starts_with("{\"seed\":7,\"player\":0,\"observation\":["));
+        assert!(first.ends_with("\"game_outcome\":-1}"));
+        assert_eq!(output, export(&ExportConfig::new(1, 7)));
+    }
+
+    #[test]
+    fn shards_split_the_games() {
+        let all = export(&ExportConfig::new(3, 0));
+        let shards: Vec<String> = (0..2)
+            .map(|shard| export(&ExportConfig { shard, shards: 2, ..ExportConfig::new(3, 0) }))
+            .collect();
+        assert_eq!(all.lines().count(), shards[0].lines().count() + shards[1].lines().count());
+        assert!(shards[1].lines().all(|l| l.starts_with("{\"seed\":1,")));
+    }
+}
@@ -1,12 +1,70 @@
-use deck::Deck;
-use cards::Suit;
-use bids::BidPhase;
-use players::{Player, Team};
+use std::collections::{HashMap, VecDeque};
+
+use failure::Error;
+use rand;
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+use actions::{self, Action};
+use announcements::Announcement;
+use bids::{Bid, BidPhase, BidSet, BiddingState};
+use bots::HeuristicBot;
+use cards::{Card, Suit};
+use constraints::Constraints;
+use deck::{validate_deal, CommittedDeal, Deck, DeckCommitment};
+use errors::{ContreeError, Phase};
+use hand::Hand;
+use hint::Hint;
+use players::{Player, PlayerInfo, Seat, Team};
 use round::Round;
+use rules::{DealingMode, GameLength, RuleSet, TieBreak, Variant};
+use sampling::{played_cards, sample_hands, sample_round};
+use score_sheet::{ScoreAdjustment, ScoreSheet};
+use take::TakePhase;
+use team_scores::TeamScores;
+use view::{PlayerView, SpectatorView};
 
-static SCORE_GOAL: usize = 1000;
 
+/// How many ids of actions `Game::apply_with_id` remembers
+pub const MAX_SUBMISSIONS: usize = 64;
 
+/// Whether a game is over and who won it
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum MatchResult {
+    /// Neither team has won yet
+    Ongoing,
+    Won(Team),
+    /// Both teams finished at the same time and the tie break rule
+    /// asks for another deal to be played
+    ExtraDeal,
+}
+
+/// Why cards were dealt again without playing the round
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum RedealReason {
+    /// Everyone passed, or refused to take in classic belote
+    AllPassed,
+    /// The bidding ended without a valid contract, which can only happen in
+    /// `ValidationMode::Permissive`, eg when only counters were made
+    NoContract,
+    /// A player said the cards were not dealt properly
+    Misdeal { claimed_by: Player, reason: String },
+}
+
+/// A deal that was thrown away
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Redeal {
+    /// The number of the deal, counting from 1 like `Game::revealed_deals`
+    pub deal: usize,
+    pub reason: RedealReason,
+}
+
+/// The totals of each team and the result of the game so far
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Standing {
+    pub totals: TeamScores,
+    pub result: MatchResult,
+}
 
 #[derive(Debug, Clone)]
 pub struct Game {
@@ -15,49 +73,1449 @@ pub struct Game {
     /// south -> west -> north -> east -> south
     first_player: Player,
     /// All the rounds in the current game
-    /// Resets when a team reaches SCORE_GOAL
+    /// Resets when a team reaches the score goal
     rounds: Vec<Round>,
     /// The deck the game is going to use
     deck: Deck,
+    /// The rules used by this table
+    rules: RuleSet,
+    /// Manual corrections of the scores, in the order they were made.
+    /// They can only be added, never changed or removed
+    adjustments: Vec<ScoreAdjustment>,
+    /// How many times cards have been dealt, including deals where everyone passed
+    deals: usize,
+    /// The cards dealt to each player for the current round. In classic belote, only
+    /// the first 5 cards until someone takes
+    hands: HashMap<Player, Vec<Card>>,
+    /// The bidding phase of the current round, kept until the round is over
+    bid_phase: Option<BidPhase>,
+    /// The taking phase of the current round in classic belote, kept until the round is over
+    take_phase: Option<TakePhase>,
+    /// The round being played, once the bidding phase is done
+    round: Option<Round>,
+    /// Where the seeds used to shuffle and cut the deck come from
+    rng: Isaac64Rng,
+    /// Every deal made from the deck with the number of the deal. The deck of the current
+    /// deal must not be shown before its round is over
+    committed_deals: Vec<(usize, CommittedDeal)>,
+    /// The deals thrown away, in order
+    redeals: Vec<Redeal>,
+    /// Who sits at each seat, indexed by `Player::index`
+    seats: [Option<PlayerInfo>; 4],
+    /// The last actions submitted with an id by `apply_with_id` and whether they succeeded,
+    /// oldest first
+    submissions: VecDeque<(String, Player, Action, Result<(), ContreeError>)>,
+}
+
+impl Default for Game {
+    fn default() -> Game {
+        Game::new(RuleSet::default())
+    }
 }
 
 impl Game {
-    pub fn new() -> Game {
+    pub fn new(rules: RuleSet) -> Game {
+        Game::from_seed(rules, rand::random())
+    }
+
+    /// A game where the deck is shuffled and cut the same way every time for the same seed
+    pub fn from_seed(rules: RuleSet, seed: u64) -> Game {
+        let mut rng = Isaac64Rng::from_seed(&[seed]);
         Game {
             first_player: Player::South,
             rounds: Vec::new(),
-            deck: Deck::new(),
+            deck: Deck::from_seed(rng.gen()),
+            rules,
+            adjustments: Vec::new(),
+            deals: 0,
+            hands: HashMap::new(),
+            bid_phase: None,
+            take_phase: None,
+            round: None,
+            rng,
+            committed_deals: Vec::new(),
+            submissions: VecDeque::new(),
+            redeals: Vec::new(),
+            seats: [None, None, None, None],
+        }
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Puts the deck back together from the tricks of the round that just ended.
+    /// Only used in `DealingMode::CutOnly`, the deck is shuffled anyway otherwise.
+    pub fn gather_tricks(&mut self, tricks: &[Vec<Card>]) -> Result<(), Error> {
+        self.deck = Deck::from_gathered_tricks(tricks)?;
+        Ok(())
+    }
+
+    /// The player who bids first in the current round
+    pub fn first_player(&self) -> Player {
+        self.first_player
+    }
+
+    /// The rounds played to the end so far, in order
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    /// The round being played, `None` during the bidding or between rounds
+    pub fn current_round(&self) -> Option<&Round> {
+        self.round.as_ref()
+    }
+
+    /// The bidding phase of the current round. It is kept while the round is played
+    pub fn current_bid_phase(&self) -> Option<&BidPhase> {
+        self.bid_phase.as_ref()
+    }
+
+    /// An independent copy of the game at the current position, to try other bids or cards
+    /// without touching this game. The copy shuffles the next deals with its own seed so it
+    /// can't be used to find out the next deals of this game
+    pub fn fork(&self) -> Game {
+        self.fork_from_seed(rand::random())
+    }
+
+    /// Same as `fork`, the next deals of the copy only depending on the seed.
+    /// The copy doesn't know the commitments and hands of the deals, who sits at the table
+    /// or the actions submitted with an id
+    pub fn fork_from_seed(&self, seed: u64) -> Game {
+        let mut game = self.clone();
+        game.rng = Isaac64Rng::from_seed(&[seed]);
+        game.committed_deals.clear();
+        game.submissions.clear();
+        game.seats = [None, None, None, None];
+        game
+    }
+
+    /// A fork where the cards the player can't see are dealt again at random, consistently
+    /// with everything they know. Their own hand and the cards played don't change.
+    /// Errors if no consistent deal was found
+    pub fn fork_determinized(&self, player: Player, seed: u64) -> Result<Game, Error> {
+        let mut rng = Isaac64Rng::from_seed(&[seed]);
+        let mut game = self.fork_from_seed(rng.gen());
+        let view = self.player_view(player);
+
+        if let Some(ref round) = self.round {
+            let played = played_cards(&view);
+            let leader = played.first().map_or(round.next_player(), |&(p, _)| p);
+            let mut sampled = match sample_round(&view, round.contract(), leader, &self.rules, &mut rng) {
+                Some(sampled) => sampled,
+                None => bail!("No deal consistent with the cards played was found"),
+            };
+            sampled.copy_announcements(round);
+            game.hands = Player::iterator()
+                .map(|p| {
+                    let mut hand = sampled.hand(*p).to_vec();
+                    hand.extend(played.iter().filter(|&&(q, _)| q == *p).map(|&(_, c)| c));
+                    (*p, hand)
+                })
+                .collect();
+            game.round = Some(sampled);
+        } else if self.take_phase.is_some() {
+            bail!("Cards can't be dealt again during the taking phase, the rest of the deck is unknown");
+        } else if !self.hands.is_empty() {
+            game.hands = match sample_hands(&view, &Constraints::from_view(&view), &mut rng) {
+                Some(hands) => hands,
+                None => bail!("No consistent deal was found"),
+            };
+        }
+        Ok(game)
+    }
+
+    /// Changes who bids first in the first round, eg to carry the rotation over from a previous game.
+    /// Errors if cards have already been dealt
+    pub fn set_first_player(&mut self, player: Player) -> Result<(), Error> {
+        if !self.is_initial_round() {
+            bail!("The first player can only be changed before the first deal");
+        }
+        self.first_player = player;
+        Ok(())
+    }
+
+    /// Sits the player at the seat, replacing whoever was there.
+    /// Errors if the player is already sitting at another seat
+    pub fn seat(&mut self, seat: Seat, info: PlayerInfo) -> Result<(), Error> {
+        if let Some(other) = self.seat_of(&info.id) {
+            if other != seat {
+                bail!("{} is already sitting at {:?}", info.id, other);
+            }
         }
+        self.seats[seat.player().index()] = Some(info);
+        Ok(())
+    }
+
+    /// Frees the seat, returning who was sitting there
+    pub fn leave(&mut self, seat: Seat) -> Option<PlayerInfo> {
+        self.seats[seat.player().index()].take()
+    }
+
+    pub fn player_info(&self, seat: Seat) -> Option<&PlayerInfo> {
+        self.seats[seat.player().index()].as_ref()
+    }
+
+    /// The seat of the player with that id, if they are sitting
+    pub fn seat_of(&self, id: &str) -> Option<Seat> {
+        Player::iterator().map(|p| Seat::of(*p)).find(|s| self.player_info(*s).map_or(false, |i| i.id == id))
     }
 
     fn is_initial_round(&self) -> bool {
-        self.rounds.is_empty()
+        self.deals == 0
     }
 
+    /// Deals the cards and starts the bidding phase of a new round
     pub fn new_round(&mut self) {
         // move to next player except on the first round
         if !self.is_initial_round() {
             self.first_player = self.first_player.next_player();
         }
-        self.deck.shuffle();
-        let cards = self.deck.deal();
+        self.deal();
+    }
+
+    /// Shuffles or cuts the deck, deals it and starts a bidding phase, or the taking phase
+    /// with the first 5 cards in classic belote
+    fn deal(&mut self) {
+        match self.rules.dealing_mode {
+            DealingMode::Shuffle => self.deck.shuffle_with_seed(self.rng.gen()),
+            // can't fail, the position is always valid
+            DealingMode::CutOnly => {
+                let position = self.rng.gen_range(3, 30);
+                self.deck.cut_with_nonce(position, self.rng.gen()).unwrap()
+            },
+        }
+
+        self.deals += 1;
+        self.committed_deals.push((self.deals, CommittedDeal::new(&self.deck)));
+
+        // The first player to bid is also the first one to receive cards
+        let hands = match self.rules.variant {
+            Variant::Contree => self.deck.deal(),
+            Variant::Classic => self.deck.deal_first_stage(),
+        };
+        self.hands.clear();
+        let mut player = self.first_player;
+        for hand in hands.iter() {
+            self.hands.insert(player, hand.clone());
+            player = player.next_player();
+        }
+
+        match self.rules.variant {
+            Variant::Contree => {
+                self.bid_phase = Some(BidPhase::with_rules(self.first_player, &self.rules));
+                self.take_phase = None;
+            },
+            Variant::Classic => {
+                self.bid_phase = None;
+                self.take_phase = Some(TakePhase::new(self.first_player, self.deck.turn_up()));
+            },
+        }
+        self.round = None;
+    }
+
+    /// Starts the bidding phase of a new round with the given hands, indexed by `Player::index`,
+    /// instead of dealing from the deck, eg to play the same deal at several tables.
+    /// `first_player` is the one who bids and plays first.
+    /// Errors if cards are being played, if the hands are not 8 cards each from a full deck
+    /// or in classic belote, where the hands depend on who takes
+    pub fn new_round_with_hands(&mut self, first_player: Player, hands: &[Vec<Card>; 4]) -> Result<(), Error> {
+        if self.round.is_some() {
+            bail!("Cannot deal while a round is being played");
+        }
+        if self.rules.variant == Variant::Classic {
+            bail!("Hands cannot be given in classic belote, they are dealt in two stages from the deck");
+        }
+        validate_deal(hands)?;
+
+        self.first_player = first_player;
+        self.hands = Player::iterator().map(|p| (*p, hands[p.index()].clone())).collect();
+        self.deals += 1;
+        self.bid_phase = Some(BidPhase::with_rules(first_player, &self.rules));
+        Ok(())
+    }
+
+    /// The commitment to the deck of the current deal, to show to the players before they
+    /// look at their cards. `None` if the cards were not dealt from the deck
+    pub fn deal_commitment(&self) -> Option<DeckCommitment> {
+        match self.committed_deals.last() {
+            Some(&(deal, ref committed)) if deal == self.deals => Some(committed.commitment),
+            _ => None,
+        }
+    }
+
+    /// The deals whose deck can be revealed: all the deals from the deck except the
+    /// current one, unless the game is over. Each of them can be checked with `CommittedDeal::audit`
+    pub fn revealed_deals(&self) -> Vec<&CommittedDeal> {
+        let over = self.has_winner().is_some();
+        self.committed_deals
+            .iter()
+            .filter(|&&(deal, _)| over || deal < self.deals)
+            .map(|(_, committed)| committed)
+            .collect()
+    }
+
+    /// The player who has to bid, take or play a card, if a round is in progress
+    pub fn current_player(&self) -> Option<Player> {
+        if let Some(ref round) = self.round {
+            return Some(round.next_player());
+        }
+        if let Some(ref take_phase) = self.take_phase {
+            return Some(take_phase.current_player());
+        }
+        self.bid_phase.as_ref().and_then(|b| b.current_player())
+    }
+
+    /// The `Round::zobrist_hash` of the round being played, `None` outside of the playing phase
+    pub fn zobrist_hash(&self) -> Option<u64> {
+        self.round.as_ref().map(|r| r.zobrist_hash())
+    }
+
+    /// Whether we are in the bidding phase of a round, or in the taking phase in classic belote
+    pub fn is_bidding(&self) -> bool {
+        self.round.is_none() && (self.bid_phase.is_some() || self.take_phase.is_some())
+    }
+
+    /// The card turned up in classic belote, while players can take it
+    pub fn turned_up(&self) -> Option<Card> {
+        match self.take_phase {
+            Some(ref take_phase) if self.round.is_none() => Some(take_phase.turned_up()),
+            _ => None,
+        }
+    }
+
+    /// Makes a bid in the current bidding phase.
+    /// Starts the round once the bidding is done, or deals again if everyone passed.
+    pub fn bid(&mut self, player: Player, bid: Bid, suit: Option<Suit>) -> Result<(), Error> {
+        if !self.is_bidding() || self.bid_phase.is_none() {
+            return Err(ContreeError::PhaseMismatch { expected: Phase::Bidding }.into());
+        }
+
+        let (state, contract) = {
+            let bid_phase = self.bid_phase.as_mut().unwrap();
+            bid_phase.bid(player, bid, suit)?;
+            let contract = if bid_phase.state == BiddingState::Done {
+                Some(bid_phase.get_contract()?)
+            } else {
+                None
+            };
+            (bid_phase.state, contract)
+        };
+
+        match state {
+            BiddingState::Ongoing => (),
+            BiddingState::DealAgain => {
+                let all_passed = self.bid_phase.as_ref().unwrap().history().all(|(_, bid, _)| bid == Bid::Pass);
+                self.redeal(if all_passed { RedealReason::AllPassed } else { RedealReason::NoContract })?
+            },
+            BiddingState::Done => {
+                let contract = contract.unwrap();
+                self.round = Some(Round::with_hands(contract, self.first_player, self.hands.clone()));
+            },
+        }
+        Ok(())
+    }
+
+    /// Takes the suit as trump, or refuses with `None`, in the taking phase of classic belote.
+    /// Once someone takes, the rest of the cards are dealt and the round starts.
+    /// The cards are dealt again if everyone refused twice
+    pub fn take(&mut self, player: Player, suit: Option<Suit>) -> Result<(), Error> {
+        let state = match self.take_phase {
+            Some(ref mut take_phase) if self.round.is_none() => {
+                match suit {
+                    Some(suit) => take_phase.take(player, suit)?,
+                    None => take_phase.refuse(player)?,
+                }
+                take_phase.state
+            },
+            _ => return Err(ContreeError::PhaseMismatch { expected: Phase::Taking }.into()),
+        };
+
+        match state {
+            BiddingState::Ongoing => (),
+            BiddingState::DealAgain => self.redeal(RedealReason::AllPassed)?,
+            BiddingState::Done => {
+                let contract = self.take_phase.as_ref().unwrap().get_contract()?;
+                // The rest of the deck is dealt in the same order as the first cards
+                let taker = (contract.player().index() + 4 - self.first_player.index()) % 4;
+                let rest = self.deck.deal_second_stage(taker)?;
+                let mut player = self.first_player;
+                for cards in rest.iter() {
+                    self.hands.get_mut(&player).unwrap().extend(cards.iter().cloned());
+                    player = player.next_player();
+                }
+                self.round = Some(Round::with_hands(contract, self.first_player, self.hands.clone()));
+            },
+        }
+        Ok(())
+    }
+
+    /// Throws the current deal away and deals again. The hands are put back together
+    /// in `DealingMode::CutOnly`, in classic belote the deck is cut again as it is since
+    /// it was not dealt entirely. The same player bids first again after a misdeal,
+    /// the next one otherwise
+    fn redeal(&mut self, reason: RedealReason) -> Result<(), Error> {
+        if self.rules.dealing_mode == DealingMode::CutOnly && self.take_phase.is_none() {
+            let hands: Vec<Vec<Card>> = Player::iterator().map(|p| self.hands[p].clone()).collect();
+            self.deck = Deck::from_gathered_tricks(&hands)?;
+        }
+        let misdeal = matches!(reason, RedealReason::Misdeal { .. });
+        self.redeals.push(Redeal { deal: self.deals, reason });
+        if misdeal {
+            self.deal();
+        } else {
+            self.new_round();
+        }
+        Ok(())
+    }
+
+    /// A player says the cards were not dealt properly: the deal is thrown away and the
+    /// cards are dealt again. Only possible before the first card is played
+    pub fn claim_misdeal(&mut self, player: Player, reason: &str) -> Result<(), Error> {
+        if !self.is_bidding() {
+            return Err(ContreeError::PhaseMismatch { expected: Phase::Bidding }.into());
+        }
+        self.redeal(RedealReason::Misdeal { claimed_by: player, reason: reason.to_string() })
+    }
+
+    /// The deals thrown away because everyone passed or of a misdeal, in order
+    pub fn redeals(&self) -> &[Redeal] {
+        &self.redeals
+    }
+
+    /// Plays a card in the current round.
+    /// Once the round is over, it is scored and a new round starts unless the game is over.
+    pub fn play(&mut self, player: Player, card: Card) -> Result<(), Error> {
+        let over = match self.round {
+            Some(ref mut round) => {
+                round.play(player, card, &self.rules)?;
+                round.is_over()
+            },
+            None => return Err(ContreeError::PhaseMismatch { expected: Phase::Playing }.into()),
+        };
+        if over {
+            self.finish_round()?;
+        }
+        Ok(())
+    }
+
+    /// Makes an announcement for the player in the round being played, see `Round::announce`
+    pub fn announce(&mut self, player: Player, announcement: Announcement) -> Result<(), Error> {
+        match self.round {
+            Some(ref mut round) => Ok(round.announce(player, announcement)?),
+            None => Err(ContreeError::PhaseMismatch { expected: Phase::Playing }.into()),
+        }
+    }
+
+    /// Scores the round being played and starts the next one unless the game is won
+    fn finish_round(&mut self) -> Result<(), Error> {
+        let mut round = self.round.take().unwrap();
+        round.calculate_points(&self.rules);
+        if self.rules.dealing_mode == DealingMode::CutOnly {
+            self.deck = Deck::from_gathered_tricks(&round.gathered_tricks())?;
+        }
+        self.rounds.push(round);
+        self.bid_phase = None;
+        self.take_phase = None;
+
+        match self.standing().result {
+            MatchResult::Won(_) => (),
+            _ => self.new_round(),
+        }
+        Ok(())
+    }
+
+    /// Flags that the player revoked with that card, ie played it while the rules didn't allow it.
+    /// The round being played ends right away, otherwise the last round played is scored again,
+    /// even if it ended the game, and the standing is computed again.
+    /// Their team gets the penalty of `RuleSet::revoke_penalty`.
+    /// Errors if there is no such round or if the card isn't a revoke, see `Round::flag_revoke`
+    pub fn flag_revoke(&mut self, player: Player, card: Card) -> Result<(), Error> {
+        if let Some(ref mut round) = self.round {
+            round.flag_revoke(player, card, &self.rules)?;
+            return self.finish_round();
+        }
+
+        match self.rounds.last_mut() {
+            Some(round) => {
+                round.flag_revoke(player, card, &self.rules)?;
+                round.calculate_points(&self.rules);
+            },
+            None => bail!("No round was played yet"),
+        }
+        // The new scores can end the game, or resume it if it was over
+        match self.standing().result {
+            MatchResult::Won(_) => {
+                self.bid_phase = None;
+                self.take_phase = None;
+            },
+            _ if self.bid_phase.is_none() && self.take_phase.is_none() => self.new_round(),
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// All the actions the current player can do, in a stable order
+    pub fn legal_actions(&self) -> Vec<Action> {
+        let player = match self.current_player() {
+            Some(p) => p,
+            None => return vec![],
+        };
+        if let Some(ref round) = self.round {
+            return round.playable_cards(player, &self.rules).into_iter().map(Action::Play).collect();
+        }
+        if let Some(ref take_phase) = self.take_phase {
+            return actions::take_actions(&take_phase.available_suits());
+        }
+        match self.bid_phase {
+            Some(ref bid_phase) => actions::bid_actions(bid_phase.available_bid_set(player), bid_phase.allowed_suits()),
+            None => vec![],
+        }
+    }
+
+    /// Suggests a legal action for the player, with the reason it was picked.
+    /// Errors if it is not the turn of that player
+    pub fn hint(&self, player: Player) -> Result<Hint, Error> {
+        if self.current_player() != Some(player) {
+            return Err(ContreeError::NotYourTurn { player, current: self.current_player() }.into());
+        }
+        let view = self.player_view(player);
+        let bot = HeuristicBot::new();
+        if self.turned_up().is_some() {
+            let (suit, reason) = bot.advise_take(&view);
+            Ok(Hint { action: Action::Take(suit), reason })
+        } else if self.is_bidding() {
+            let ((bid, suit), reason) = bot.advise_bid(&view);
+            Ok(Hint { action: Action::Bid(bid, suit), reason })
+        } else {
+            let (card, reason) = bot.advise_card(&view);
+            Ok(Hint { action: Action::Play(card), reason })
+        }
+    }
+
+    /// Same as `legal_actions` with a small number attached to each action,
+    /// so clients can choose an action by number with `apply_index`
+    pub fn legal_actions_indexed(&self) -> Vec<(usize, Action)> {
+        self.legal_actions().into_iter().enumerate().collect()
+    }
+
+    /// Bids, takes or plays a card for the player
+    pub fn apply(&mut self, player: Player, action: Action) -> Result<(), Error> {
+        match action {
+            Action::Bid(bid, suit) => self.bid(player, bid, suit),
+            Action::Play(card) => self.play(player, card),
+            Action::Take(suit) => self.take(player, suit),
+        }
+    }
+
+    /// Checks whether the player can make the action right now, without making it.
+    /// Invalid bids are refused even in permissive mode, where `apply` records them as violations
+    pub fn check(&self, player: Player, action: Action) -> Result<(), ContreeError> {
+        match action {
+            Action::Bid(bid, suit) => match self.bid_phase {
+                Some(ref bid_phase) if self.is_bidding() && bid_phase.state == BiddingState::Ongoing => {
+                    bid_phase.check_bid(player, bid, suit)
+                },
+                _ => Err(ContreeError::PhaseMismatch { expected: Phase::Bidding }),
+            },
+            Action::Play(card) => match self.round {
+                Some(ref round) => round.check_play(player, card, &self.rules),
+                None => Err(ContreeError::PhaseMismatch { expected: Phase::Playing }),
+            },
+            Action::Take(suit) => match self.take_phase {
+                Some(ref take_phase) if self.round.is_none() => take_phase.check(player, suit),
+                _ => Err(ContreeError::PhaseMismatch { expected: Phase::Taking }),
+            },
+        }
+    }
+
+    /// Same as `apply` but the action comes with an id chosen by the client, eg a sequence
+    /// number, so a request sent twice is only applied once: submitting the same id again
+    /// returns the result of the first submission without doing anything.
+    /// Only the last `MAX_SUBMISSIONS` ids are remembered and errors that aren't a
+    /// `ContreeError` are not, the action being tried again when submitted again.
+    /// Errors if the action fails or if the id was already used for another action
+    pub fn apply_with_id(&mut self, player: Player, action: Action, id: &str) -> Result<(), Error> {
+        if let Some(&(_, p, a, ref result)) = self.submissions.iter().find(|s| s.0 == id) {
+            if p != player || a != action {
+                bail!("The id {} was already used for another action", id);
+            }
+            return result.clone().map_err(|e| e.into());
+        }
+        let result = self.apply(player, action);
+        let kept = match result {
+            Ok(()) => Ok(()),
+            Err(ref e) => match e.downcast_ref::<ContreeError>() {
+                Some(e) => Err(e.clone()),
+                None => return result,
+            },
+        };
+        if self.submissions.len() == MAX_SUBMISSIONS {
+            self.submissions.pop_front();
+        }
+        self.submissions.push_back((id.to_string(), player, action, kept));
+        result
+    }
+
+    /// Applies the action with that number in `legal_actions_indexed`
+    pub fn apply_index(&mut self, player: Player, index: usize) -> Result<(), Error> {
+        let legal = self.legal_actions();
+        match legal.get(index) {
+            Some(action) => self.apply(player, *action),
+            None => Err(ContreeError::NoSuchAction { index, count: legal.len() }.into()),
+        }
+    }
+
+    /// The action made for the current player when they run out of time:
+    /// passing during the bidding, refusing to take in classic belote, the cheapest card
+    /// they can play otherwise, keeping their trumps if they can
+    pub fn timeout_action(&self) -> Option<Action> {
+        if let Some(ref round) = self.round {
+            let trump = round.contract().suit();
+            return round
+                .playable_cards(round.next_player(), &self.rules)
+                .into_iter()
+                .min_by_key(|c| (c.suit == trump, c.points(trump), c.strength(trump)))
+                .map(Action::Play);
+        }
+        let legal = self.legal_actions();
+        legal
+            .iter()
+            .find(|a| **a == Action::Bid(Bid::Pass, None) || **a == Action::Take(None))
+            .or_else(|| legal.first())
+            .cloned()
+    }
+
+    /// Makes the `timeout_action` for the player and returns it.
+    /// Errors if it is not the turn of that player
+    pub fn time_out(&mut self, player: Player) -> Result<Action, Error> {
+        if self.current_player() != Some(player) {
+            return Err(ContreeError::NotYourTurn { player, current: self.current_player() }.into());
+        }
+        let action = self.timeout_action().unwrap();
+        self.apply(player, action)?;
+        Ok(action)
+    }
+
+    /// What the given player can see of the game
+    pub fn player_view(&self, player: Player) -> PlayerView {
+        let hand = match self.round {
+            Some(ref round) => round.hand(player).clone(),
+            None => self.hands.get(&player).cloned().map(Hand::new).unwrap_or_default(),
+        };
+        let is_current = self.current_player() == Some(player);
+
+        let mut view = PlayerView {
+            player,
+            hand,
+            bids: vec![],
+            available_bids: BidSet::new(),
+            contract: None,
+            turned_up: self.turned_up(),
+            current_trick: vec![],
+            tricks: vec![],
+            playable_cards: vec![],
+            totals: self.standing().totals,
+            legal_actions: vec![],
+        };
+        if is_current {
+            view.legal_actions = self.legal_actions();
+        }
+        if let Some(ref bid_phase) = self.bid_phase {
+            view.bids = bid_phase.history().collect();
+            if is_current && self.round.is_none() {
+                view.available_bids = bid_phase.available_bid_set(player);
+            }
+        }
+        if let Some(ref round) = self.round {
+            view.contract = Some(round.contract().clone());
+            view.current_trick = round.current_trick().to_vec();
+            view.tricks = round.tricks().iter().map(|(trick, winner)| (trick.to_vec(), *winner)).collect();
+            if is_current {
+                view.playable_cards = round.playable_cards(player, &self.rules);
+            }
+        }
+        view
+    }
+
+    /// What spectators can see of the game: the hands are never shown while they are
+    /// being played, but the hands of the last round played are included once it is over
+    /// if `reveal_last_round` is set. This is the live view: `Table::spectator_view`
+    /// gives it behind the table so watching doesn't help the players
+    pub fn spectator_view(&self, reveal_last_round: bool) -> SpectatorView {
+        let view = self.player_view(Player::South);
+        let hand_sizes = Player::iterator()
+            .map(|p| {
+                let size = match self.round {
+                    Some(ref round) => round.hand(*p).len(),
+                    None => self.hands.get(p).map_or(0, |h| h.len()),
+                };
+                (*p, size)
+            })
+            .collect();
+        let last_round_hands = match self.rounds.last() {
+            Some(round) if reveal_last_round => {
+                let mut hands: HashMap<Player, Vec<Card>> = HashMap::new();
+                for (trick, _) in round.tricks() {
+                    for &(player, card) in trick {
+                        hands.entry(player).or_default().push(card);
+                    }
+                }
+                Some(hands)
+            }
+            _ => None,
+        };
+
+        SpectatorView {
+            hand_sizes,
+            bids: view.bids,
+            contract: view.contract,
+            turned_up: view.turned_up,
+            current_trick: view.current_trick,
+            tricks: view.tricks,
+            totals: view.totals,
+            last_round_hands,
+        }
+    }
+
+    /// The score sheet of all the rounds played so far
+    pub fn score_sheet(&self) -> ScoreSheet {
+        ScoreSheet::new(&self.rounds, &self.adjustments, &self.rules)
+    }
+
+    /// Corrects the score of a team, eg following a ruling by a referee.
+    /// The adjustment is kept with the reason and who made it and counts towards the totals.
+    /// A total cannot go below 0.
+    pub fn adjust_score(&mut self, team: Team, delta: isize, reason: &str, authority: &str) -> Result<(), Error> {
+        if delta == 0 {
+            bail!("A score adjustment cannot be 0");
+        }
+        if reason.is_empty() || authority.is_empty() {
+            bail!("A score adjustment needs a reason and an authority");
+        }
+
+        self.adjustments.push(ScoreAdjustment {
+            team,
+            delta,
+            reason: reason.to_string(),
+            authority: authority.to_string(),
+            after_round: self.rounds.len(),
+        });
+        Ok(())
+    }
+
+    /// All the score adjustments made during the game
+    pub fn adjustments(&self) -> &[ScoreAdjustment] {
+        &self.adjustments
+    }
+
+    /// Total score of each team, adjustments included
+    pub fn totals(&self) -> TeamScores {
+        self.score_sheet().totals()
+    }
+
+    /// The current totals and whether the game is over
+    pub fn standing(&self) -> Standing {
+        let totals = self.totals();
+        let sn_score = totals[Team::SouthNorth];
+        let ew_score = totals[Team::EastWest];
+
+        let result = match self.rules.game_length {
+            GameLength::ScoreGoal(goal) => match (sn_score > goal, ew_score > goal) {
+                (false, false) => MatchResult::Ongoing,
+                (true, false) => MatchResult::Won(Team::SouthNorth),
+                (false, true) => MatchResult::Won(Team::EastWest),
+                (true, true) => {
+                    // The first round after which both teams were over the goal
+                    let tied_after = self.score_sheet().lines
+                        .iter()
+                        .position(|l| l.totals.south_north > goal && l.totals.east_west > goal)
+                        .map_or(self.rounds.len(), |i| i + 1);
+                    self.break_tie(sn_score, ew_score, tied_after)
+                },
+            },
+            GameLength::Deals(deals) => {
+                if self.rounds.len() < deals {
+                    MatchResult::Ongoing
+                } else if sn_score == ew_score {
+                    self.break_tie(sn_score, ew_score, deals)
+                } else if sn_score > ew_score {
+                    MatchResult::Won(Team::SouthNorth)
+                } else {
+                    MatchResult::Won(Team::EastWest)
+                }
+            },
+        };
+
+        Standing { totals, result }
+    }
+
+    /// Who wins when the game should have ended after `tied_after` rounds but no team
+    /// could be declared the winner from the scores alone
+    fn break_tie(&self, sn_score: usize, ew_score: usize, tied_after: usize) -> MatchResult {
+        match self.rules.tie_break {
+            TieBreak::HighestTotal => {
+                if sn_score > ew_score {
+                    MatchResult::Won(Team::SouthNorth)
+                } else if ew_score > sn_score {
+                    MatchResult::Won(Team::EastWest)
+                } else {
+                    MatchResult::ExtraDeal
+                }
+            },
+            TieBreak::AttackerPriority => match self.rounds.last() {
+                Some(round) => MatchResult::Won(round.contract().player().team()),
+                None => MatchResult::ExtraDeal,
+            },
+            // Once the extra deals are played, the highest total wins
+            TieBreak::PlayAnotherDeal if self.rounds.len() > tied_after && sn_score > ew_score => {
+                MatchResult::Won(Team::SouthNorth)
+            },
+            TieBreak::PlayAnotherDeal if self.rounds.len() > tied_after && ew_score > sn_score => {
+                MatchResult::Won(Team::EastWest)
+            },
+            TieBreak::PlayAnotherDeal => MatchResult::ExtraDeal,
+        }
     }
 
     /// Returns the winner team if there is one
     pub fn has_winner(&self) -> Option<Team> {
-        let mut sn_score = 0;
-        let mut ew_score = 0;
-        for round in &self.rounds {
-            sn_score += round.scores[&Team::SouthNorth];
-            ew_score += round.scores[&Team::EastWest];
-        }
-        if sn_score > SCORE_GOAL && sn_score > ew_score {
-            Some(Team::SouthNorth)
-        } else if ew_score > SCORE_GOAL {
-            Some(Team::EastWest)
-        } else {
-            None
+        match self.standing().result {
+            MatchResult::Won(team) => Some(team),
+            _ => None,
         }
     }
 }
 
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Contract;
+    use bots::RandomBot;
+    use cards::{Rank, Suit};
+    use deck::DeckOrigin;
+    use round::Revoke;
+    use rules::ValidationMode;
+    use strategy::run_game;
+
+    fn add_round(game: &mut Game, sn_score: usize, ew_score: usize) {
+        let mut round = Round::with_hands(Contract::taken(Player::South, Suit::Hearts), Player::South, HashMap::new());
+        round.scores[Team::SouthNorth] = sn_score;
+        round.scores[Team::EastWest] = ew_score;
+        game.rounds.push(round);
+    }
+
+    #[test]
+    fn can_get_score_sheet() {
+        let mut game = Game::new(RuleSet { round_to_ten: true, ..RuleSet::default() });
+        add_round(&mut game, 0, 162);
+        game.rounds[0].trick_points[Team::EastWest] = 162;
+        game.rounds[0].belote = Some(Team::SouthNorth);
+        add_round(&mut game, 96, 66);
+
+        let sheet = game.score_sheet();
+        assert_eq!(sheet.lines.len(), 2);
+        assert_eq!(sheet.lines[0].contract, Contract::taken(Player::South, Suit::Hearts));
+        assert!(!sheet.lines[0].made);
+        assert_eq!(sheet.lines[0].trick_points[Team::EastWest], 162);
+        assert_eq!(sheet.lines[0].belote, Some(Team::SouthNorth));
+        assert_eq!(sheet.lines[0].scores[Team::EastWest], 160);
+        assert_eq!(sheet.lines[1].scores[Team::SouthNorth], 100);
+        assert_eq!(sheet.lines[1].totals[Team::EastWest], 230);
+        assert_eq!(sheet.totals()[Team::SouthNorth], 100);
+    }
+
+    #[test]
+    fn can_adjust_scores() {
+        let mut game = Game::default();
+        add_round(&mut game, 900, 0);
+        assert!(game.adjust_score(Team::SouthNorth, 0, "nothing", "referee").is_err());
+        assert!(game.adjust_score(Team::SouthNorth, 20, "", "referee").is_err());
+        assert!(game.adjust_score(Team::SouthNorth, 150, "revoke by East", "referee").is_ok());
+        assert_eq!(game.has_winner(), Some(Team::SouthNorth));
+        assert!(game.adjust_score(Team::SouthNorth, -100, "mistake", "referee").is_ok());
+        assert_eq!(game.has_winner(), None);
+        add_round(&mut game, 60, 0);
+        assert_eq!(game.has_winner(), Some(Team::SouthNorth));
+
+        let sheet = game.score_sheet();
+        assert_eq!(sheet.adjustments.len(), 2);
+        assert_eq!(sheet.lines[1].totals[Team::SouthNorth], 1010);
+        assert_eq!(game.adjustments()[0].after_round, 1);
+    }
+
+    #[test]
+    fn can_break_ties() {
+        let mut game = Game::default();
+        add_round(&mut game, 900, 900);
+        add_round(&mut game, 200, 300);
+        assert_eq!(game.standing().result, MatchResult::Won(Team::EastWest));
+        assert_eq!(game.standing().totals[Team::SouthNorth], 1100);
+
+        let mut game = Game::default();
+        add_round(&mut game, 1200, 1200);
+        assert_eq!(game.standing().result, MatchResult::ExtraDeal);
+        assert_eq!(game.has_winner(), None);
+
+        game.rules.tie_break = TieBreak::AttackerPriority;
+        assert_eq!(game.standing().result, MatchResult::Won(Team::SouthNorth));
+
+        game.rules.tie_break = TieBreak::PlayAnotherDeal;
+        add_round(&mut game, 0, 0);
+        assert_eq!(game.standing().result, MatchResult::ExtraDeal);
+        add_round(&mut game, 0, 100);
+        assert_eq!(game.standing().result, MatchResult::Won(Team::EastWest));
+    }
+
+    #[test]
+    fn extra_deals_end_the_game() {
+        let rules = RuleSet { tie_break: TieBreak::PlayAnotherDeal, ..RuleSet::default() };
+        let mut game = Game::from_seed(rules, 2);
+        // Both teams are over the goal with different totals: one more deal decides
+        add_round(&mut game, 1100, 1050);
+        assert_eq!(game.standing().result, MatchResult::ExtraDeal);
+        game.new_round();
+        while game.has_winner().is_none() {
+            assert!(game.rounds().len() < 20, "The extra deals never end");
+            let player = game.current_player().unwrap();
+            let action = game.hint(player).unwrap().action;
+            game.apply(player, action).unwrap();
+        }
+        assert!(game.rounds().len() >= 2);
+        assert!(game.current_player().is_none());
+    }
+
+    #[test]
+    fn can_get_hints() {
+        let mut game = Game::default();
+        game.new_round();
+        assert!(game.hint(Player::West).is_err());
+        // Follow the hints for a whole round
+        while game.score_sheet().lines.is_empty() {
+            let player = game.current_player().unwrap();
+            let hint = game.hint(player).unwrap();
+            assert!(game.legal_actions().contains(&hint.action));
+            game.apply(player, hint.action).unwrap();
+        }
+    }
+
+    #[test]
+    fn timing_out_passes_or_plays_the_weakest_card() {
+        let mut game = Game::default();
+        game.new_round();
+        assert!(game.time_out(Player::West).is_err());
+        assert!(game.bid(Player::South, Bid::Eighty, Some(Suit::Hearts)).is_ok());
+        for player in &[Player::West, Player::North, Player::East] {
+            assert_eq!(game.time_out(*player).unwrap(), Action::Bid(Bid::Pass, None));
+        }
+
+        let weakest = game.player_view(Player::South)
+            .playable_cards
+            .into_iter()
+            .min_by_key(|c| (c.suit == Suit::Hearts, c.points(Suit::Hearts), c.strength(Suit::Hearts)))
+            .unwrap();
+        assert_eq!(game.time_out(Player::South).unwrap(), Action::Play(weakest));
+        assert_eq!(game.current_player(), Some(Player::West));
+
+        // A side card is played before a weaker trump
+        let mut hands = HashMap::new();
+        hands.insert(Player::South, vec![Card::new(Suit::Hearts, Rank::Seven), Card::new(Suit::Spades, Rank::Eight)]);
+        game.round = Some(Round::with_hands(Contract::taken(Player::South, Suit::Hearts), Player::South, hands));
+        assert_eq!(game.timeout_action(), Some(Action::Play(Card::new(Suit::Spades, Rank::Eight))));
+    }
+
+    #[test]
+    fn spectators_cannot_see_hands() {
+        let mut game = Game::from_seed(RuleSet::default(), 0);
+        game.new_round();
+        let view = game.spectator_view(true);
+        assert!(view.hand_sizes.values().all(|s| *s == 8));
+        assert!(view.last_round_hands.is_none());
+
+        while game.score_sheet().lines.is_empty() {
+            let player = game.current_player().unwrap();
+            let hint = game.hint(player).unwrap();
+            game.apply(player, hint.action).unwrap();
+        }
+        let view = game.spectator_view(true);
+        assert_eq!(view.totals, game.standing().totals);
+        assert!(view.tricks.is_empty());
+        let hands = view.last_round_hands.unwrap();
+        assert!(Player::iterator().all(|p| hands[p].len() == 8));
+        assert!(game.spectator_view(false).last_round_hands.is_none());
+    }
+
+    #[test]
+    fn deals_are_committed_then_revealed() {
+        let mut game = Game::from_seed(RuleSet::default(), 4);
+        assert!(game.deal_commitment().is_none());
+        game.new_round();
+        let commitment = game.deal_commitment().unwrap();
+        assert!(game.revealed_deals().is_empty());
+
+        // Everyone passes so the cards are dealt again
+        for player in &[Player::South, Player::West, Player::North, Player::East] {
+            game.bid(*player, Bid::Pass, None).unwrap();
+        }
+        assert_ne!(game.deal_commitment(), Some(commitment));
+        let revealed = game.revealed_deals();
+        assert_eq!(revealed.len(), 1);
+        assert_eq!(revealed[0].commitment, commitment);
+        let audit = revealed[0].audit().unwrap();
+        assert!(audit.verify().is_ok());
+
+        let mut other = Game::default();
+        let hands = revealed[0].hands.clone();
+        other.new_round_with_hands(Player::South, &hands).unwrap();
+        assert!(other.deal_commitment().is_none());
+    }
+
+    #[test]
+    fn cut_decks_can_be_audited() {
+        let rules = RuleSet { dealing_mode: DealingMode::CutOnly, ..RuleSet::default() };
+        let mut game = Game::from_seed(rules, 5);
+        game.new_round();
+        // Play until the deck made of the gathered tricks is cut and dealt
+        while game.revealed_deals().len() < 2 {
+            let player = game.current_player().unwrap();
+            let action = game.hint(player).unwrap().action;
+            game.apply(player, action).unwrap();
+        }
+        for deal in game.revealed_deals() {
+            assert!(deal.audit().is_ok());
+        }
+        match game.revealed_deals()[1].origin {
+            DeckOrigin::Cut { .. } => (),
+            ref origin => panic!("Expected a cut deck, got {}", origin),
+        }
+    }
+
+    #[test]
+    fn actions_with_an_id_are_applied_once() {
+        let mut game = Game::default();
+        game.new_round();
+        let pass = Action::Bid(Bid::Pass, None);
+        assert!(game.apply_with_id(Player::South, pass, "1").is_ok());
+        assert_eq!(game.current_player(), Some(Player::West));
+        // Retrying doesn't make South pass again or fail
+        assert!(game.apply_with_id(Player::South, pass, "1").is_ok());
+        assert_eq!(game.current_player(), Some(Player::West));
+        assert!(game.apply_with_id(Player::West, pass, "1").is_err());
+
+        // Failures are remembered too
+        let error = game.apply_with_id(Player::North, pass, "2").unwrap_err().to_string();
+        assert_eq!(game.apply_with_id(Player::North, pass, "2").unwrap_err().to_string(), error);
+        assert!(game.apply_with_id(Player::West, pass, "3").is_ok());
+        assert_eq!(game.current_player(), Some(Player::North));
+        let error = game.apply_with_id(Player::South, pass, "4").unwrap_err();
+        assert_eq!(error.downcast_ref::<ContreeError>().map(|e| e.code()), Some("wrong_player"));
+
+        // Only the last ids are kept
+        for i in 0..MAX_SUBMISSIONS {
+            let _ = game.apply_with_id(Player::South, pass, &format!("old {}", i));
+        }
+        assert_eq!(game.submissions.len(), MAX_SUBMISSIONS);
+        // The id is free again
+        assert!(game.apply_with_id(Player::North, pass, "1").is_ok());
+        assert_eq!(game.current_player(), Some(Player::East));
+    }
+
+    #[test]
+    fn errors_can_be_matched() {
+        let mut game = Game::from_seed(RuleSet::default(), 3);
+        game.new_round();
+        let reason = |result: Result<(), Error>| result.unwrap_err().downcast::<ContreeError>().unwrap();
+
+        let card = game.player_view(Player::South).hand[0];
+        assert_eq!(reason(game.play(Player::South, card)), ContreeError::PhaseMismatch { expected: Phase::Playing });
+        assert_eq!(
+            reason(game.bid(Player::West, Bid::Pass, None)),
+            ContreeError::WrongPlayer { expected: Player::South, got: Player::West }
+        );
+        assert_eq!(reason(game.bid(Player::South, Bid::Eighty, None)), ContreeError::SuitRequired(Bid::Eighty));
+        assert_eq!(reason(game.apply_index(Player::South, 41)), ContreeError::NoSuchAction { index: 41, count: 41 });
+        assert_eq!(
+            game.hint(Player::North).unwrap_err().downcast::<ContreeError>().unwrap(),
+            ContreeError::NotYourTurn { player: Player::North, current: Some(Player::South) }
+        );
+    }
+
+    #[test]
+    fn check_agrees_with_apply() {
+        let mut game = Game::from_seed(RuleSet::default(), 5);
+        game.new_round();
+        for _ in 0..40 {
+            for player in Player::iterator() {
+                for action in actions::action_space() {
+                    let checked = game.check(*player, action);
+                    let applied = game.clone().apply(*player, action);
+                    assert_eq!(checked.is_ok(), applied.is_ok());
+                    if let Err(e) = applied {
+                        assert_eq!(e.downcast::<ContreeError>().ok(), checked.err());
+                    }
+                }
+            }
+            let player = game.current_player().unwrap();
+            let action = game.hint(player).unwrap().action;
+            game.apply(player, action).unwrap();
+        }
+    }
+
+    #[test]
+    fn can_inspect_the_game() {
+        let mut game = Game::from_seed(RuleSet::default(), 3);
+        assert!(game.current_bid_phase().is_none());
+        game.new_round();
+        assert_eq!(game.current_bid_phase().unwrap().current_player(), Some(Player::South));
+        game.bid(Player::South, Bid::Eighty, Some(Suit::Hearts)).unwrap();
+        for player in &[Player::West, Player::North, Player::East] {
+            game.bid(*player, Bid::Pass, None).unwrap();
+        }
+        assert_eq!(game.current_round().unwrap().contract().bid(), Some(Bid::Eighty));
+        assert_eq!(game.current_bid_phase().unwrap().history().count(), 4);
+
+        for _ in 0..32 {
+            let player = game.current_player().unwrap();
+            let action = game.hint(player).unwrap().action;
+            game.apply(player, action).unwrap();
+        }
+        assert_eq!(game.rounds().len(), 1);
+        assert!(game.current_round().is_none());
+        assert_eq!(game.first_player(), Player::West);
+        assert_eq!(game.totals(), game.rounds()[0].scores);
+        assert_eq!(game.totals(), game.standing().totals);
+    }
+
+    #[test]
+    fn forks_are_independent() {
+        let mut game = Game::from_seed(RuleSet::default(), 11);
+        game.new_round();
+        game.bid(Player::South, Bid::Eighty, Some(Suit::Spades)).unwrap();
+        let mut fork = game.fork();
+        fork.bid(Player::West, Bid::Hundred, Some(Suit::Hearts)).unwrap();
+        assert_eq!(game.current_player(), Some(Player::West));
+        assert_eq!(fork.current_player(), Some(Player::North));
+        assert_eq!(fork.player_view(Player::South).hand, game.player_view(Player::South).hand);
+    }
+
+    #[test]
+    fn determinized_forks_keep_what_the_player_knows() {
+        let mut game = Game::from_seed(RuleSet::default(), 12);
+        game.new_round();
+        let fork = game.fork_determinized(Player::South, 1).unwrap();
+        assert_eq!(fork.player_view(Player::South).hand, game.player_view(Player::South).hand);
+        assert_ne!(fork.player_view(Player::West).hand, game.player_view(Player::West).hand);
+
+        game.bid(Player::South, Bid::Eighty, Some(Suit::Spades)).unwrap();
+        for player in &[Player::West, Player::North, Player::East] {
+            game.bid(*player, Bid::Pass, None).unwrap();
+        }
+        for _ in 0..10 {
+            let player = game.current_player().unwrap();
+            let action = game.hint(player).unwrap().action;
+            game.apply(player, action).unwrap();
+        }
+
+        let mut fork = game.fork_determinized(Player::East, 2).unwrap();
+        let (view, fork_view) = (game.player_view(Player::East), fork.player_view(Player::East));
+        assert_eq!(fork_view.hand, view.hand);
+        assert_eq!(fork_view.tricks, view.tricks);
+        assert_eq!(fork_view.current_trick, view.current_trick);
+        assert_eq!(fork.current_player(), game.current_player());
+        // The fork can be played to the end with its own hands
+        while fork.rounds().is_empty() {
+            let player = fork.current_player().unwrap();
+            let action = fork.hint(player).unwrap().action;
+            fork.apply(player, action).unwrap();
+        }
+        // and the real deal isn't revealed once it's over
+        let real = &game.committed_deals.last().unwrap().1.hands;
+        assert!(fork.revealed_deals().iter().all(|deal| deal.hands != *real));
+        assert!(fork.revealed_deals().iter().all(|deal| !deal.hands.contains(&real[1])));
+    }
+
+    #[test]
+    fn can_play_classic_belote() {
+        let rules = RuleSet { variant: Variant::Classic, ..RuleSet::default() };
+        let mut game = Game::from_seed(rules.clone(), 5);
+        game.new_round();
+        assert!(game.is_bidding());
+        assert!(Player::iterator().all(|p| game.player_view(*p).hand.len() == 5));
+        let turned_up = game.turned_up().unwrap();
+        assert_eq!(game.player_view(Player::West).turned_up, Some(turned_up));
+        assert_eq!(game.legal_actions(), vec![Action::Take(None), Action::Take(Some(turned_up.suit()))]);
+        assert_eq!(game.timeout_action(), Some(Action::Take(None)));
+        assert_eq!(
+            game.check(Player::South, Action::Bid(Bid::Eighty, Some(Suit::Hearts))),
+            Err(ContreeError::PhaseMismatch { expected: Phase::Bidding }),
+        );
+        assert!(game.bid(Player::South, Bid::Pass, None).is_err());
+
+        game.apply(Player::South, Action::Take(None)).unwrap();
+        let other = Suit::iterator().cloned().find(|s| *s != turned_up.suit()).unwrap();
+        assert_eq!(
+            game.check(Player::West, Action::Take(Some(other))),
+            Err(ContreeError::SuitNotAllowed { suit: other, allowed: vec![turned_up.suit()] }),
+        );
+        game.apply(Player::West, Action::Take(Some(turned_up.suit()))).unwrap();
+        assert!(!game.is_bidding());
+        assert_eq!(game.turned_up(), None);
+        assert_eq!(game.current_player(), Some(Player::South));
+        let round = game.current_round().unwrap();
+        assert_eq!(round.contract(), &Contract::taken(Player::West, turned_up.suit()));
+        assert!(round.hand(Player::West).to_vec().contains(&turned_up));
+        assert!(Player::iterator().all(|p| round.hand(*p).len() == 8));
+        let hands: Vec<Vec<Card>> = Player::iterator().map(|p| round.hand(*p).to_vec()).collect();
+        assert!(validate_deal(&[hands[0].clone(), hands[1].clone(), hands[2].clone(), hands[3].clone()]).is_ok());
+
+        let mut game = Game::from_seed(rules, 6);
+        assert!(run_game(&mut game, &mut [
+            Box::new(HeuristicBot::new()),
+            Box::new(RandomBot::from_seed(1)),
+            Box::new(HeuristicBot::new()),
+            Box::new(RandomBot::from_seed(2)),
+        ]).is_ok());
+        assert!(game.rounds().iter().all(|r| r.contract().is_taken()));
+    }
+
+    #[test]
+    fn everyone_refusing_to_take_deals_again() {
+        let mut game = Game::from_seed(RuleSet { variant: Variant::Classic, ..RuleSet::default() }, 7);
+        game.new_round();
+        let first = game.player_view(Player::South).hand;
+        for _ in 0..8 {
+            let player = game.current_player().unwrap();
+            assert_eq!(game.time_out(player).unwrap(), Action::Take(None));
+        }
+        assert_eq!(game.redeals(), &[Redeal { deal: 1, reason: RedealReason::AllPassed }]);
+        assert_eq!(game.current_player(), Some(Player::West));
+        assert!(game.turned_up().is_some());
+        assert_ne!(game.player_view(Player::South).hand, first);
+        let hands = [vec![], vec![], vec![], vec![]];
+        assert!(game.new_round_with_hands(Player::South, &hands).is_err());
+    }
+
+    #[test]
+    fn can_deal_again() {
+        let mut game = Game::from_seed(RuleSet::default(), 4);
+        game.new_round();
+        for player in &[Player::South, Player::West, Player::North, Player::East] {
+            game.bid(*player, Bid::Pass, None).unwrap();
+        }
+        assert_eq!(game.redeals(), &[Redeal { deal: 1, reason: RedealReason::AllPassed }]);
+        assert_eq!(game.first_player(), Player::West);
+
+        let hand = game.player_view(Player::West).hand;
+        game.bid(Player::West, Bid::Eighty, Some(Suit::Clubs)).unwrap();
+        game.claim_misdeal(Player::North, "East has 9 cards").unwrap();
+        assert_eq!(game.redeals().len(), 2);
+        assert_eq!(game.redeals()[1].deal, 2);
+        // Same player bids first, with new cards and a new bidding phase
+        assert_eq!(game.first_player(), Player::West);
+        assert_eq!(game.current_player(), Some(Player::West));
+        assert_ne!(game.player_view(Player::West).hand, hand);
+        assert_eq!(game.current_bid_phase().unwrap().history().count(), 0);
+        assert_eq!(game.revealed_deals().len(), 2);
+
+        game.bid(Player::West, Bid::Eighty, Some(Suit::Clubs)).unwrap();
+        for player in &[Player::North, Player::East, Player::South] {
+            game.bid(*player, Bid::Pass, None).unwrap();
+        }
+        assert!(game.claim_misdeal(Player::North, "too late").is_err());
+    }
+
+    /// Bids 80 hearts for the first player then plays the first playable card
+    /// until a player of the team can revoke, and does it
+    fn play_until_revoke(game: &mut Game, team: Team) -> (Player, Card) {
+        let first = game.current_player().unwrap();
+        game.bid(first, Bid::Eighty, Some(Suit::Hearts)).unwrap();
+        while game.is_bidding() {
+            let player = game.current_player().unwrap();
+            game.bid(player, Bid::Pass, None).unwrap();
+        }
+        loop {
+            let player = game.current_player().unwrap();
+            let legal = game.current_round().unwrap().playable_cards(player, &game.rules);
+            let illegal = game.player_view(player).hand.iter().find(|c| !legal.contains(c)).cloned();
+            match illegal {
+                Some(card) if player.team() == team => {
+                    game.play(player, card).unwrap();
+                    return (player, card);
+                },
+                _ => game.play(player, legal[0]).unwrap(),
+            }
+        }
+    }
+
+    #[test]
+    fn can_flag_a_revoke() {
+        let rules = RuleSet { validation: ValidationMode::Permissive, ..RuleSet::default() };
+        let mut game = Game::from_seed(rules, 4);
+        game.new_round();
+        assert!(game.flag_revoke(Player::South, Card::new(Suit::Hearts, Rank::Jack)).is_err());
+        let (player, card) = play_until_revoke(&mut game, Team::EastWest);
+        // Only cards the player wasn't allowed to play are revokes
+        let round = game.current_round().unwrap();
+        let (leader, lead) = round.tricks().first().map_or(round.current_trick()[0], |(trick, _)| trick[0]);
+        assert!(game.flag_revoke(leader, lead).is_err());
+        game.flag_revoke(player, card).unwrap();
+
+        // The round ends right away and East/West lose it, keeping only their belote
+        assert_eq!(game.rounds().len(), 1);
+        assert!(game.current_round().is_none());
+        assert!(game.is_bidding());
+        let line = &game.score_sheet().lines[0];
+        let belote = |team| if line.belote == Some(team) { 20 } else { 0 };
+        assert_eq!(line.revoke, Some(Revoke { player, card }));
+        assert!(line.made);
+        assert_eq!(line.scores, TeamScores::new(240 + belote(Team::SouthNorth), belote(Team::EastWest)));
+
+        // A revoke can also be found once the round is over
+        let totals = game.totals();
+        let (player, card) = play_until_revoke(&mut game, Team::SouthNorth);
+        while game.rounds().len() == 1 {
+            let player = game.current_player().unwrap();
+            let card = game.current_round().unwrap().playable_cards(player, &game.rules)[0];
+            game.play(player, card).unwrap();
+        }
+        game.flag_revoke(player, card).unwrap();
+        assert!(game.flag_revoke(player, card).is_err());
+        let line = &game.score_sheet().lines[1];
+        assert_eq!(line.revoke, Some(Revoke { player, card }));
+        assert_eq!(line.scores.south_north, if line.belote == Some(Team::SouthNorth) { 20 } else { 0 });
+        assert!(line.scores.east_west >= 240);
+        assert_eq!(game.totals(), totals + line.scores);
+        assert!(game.is_bidding());
+
+        // Still only once when the game is over
+        game.rules.game_length = GameLength::ScoreGoal(100);
+        game.rules.tie_break = TieBreak::AttackerPriority;
+        assert!(game.has_winner().is_some());
+        assert!(game.flag_revoke(player, card).is_err());
+    }
+
+    #[test]
+    fn flagging_a_revoke_can_change_the_winner() {
+        let rules = RuleSet {
+            validation: ValidationMode::Permissive,
+            game_length: GameLength::ScoreGoal(100),
+            ..RuleSet::default()
+        };
+        let mut game = Game::from_seed(rules, 0);
+        game.new_round();
+        let (player, card) = play_until_revoke(&mut game, Team::SouthNorth);
+        while game.current_round().is_some() {
+            let player = game.current_player().unwrap();
+            let card = game.current_round().unwrap().playable_cards(player, &game.rules)[0];
+            game.play(player, card).unwrap();
+        }
+        assert_eq!(game.has_winner(), Some(Team::SouthNorth));
+        assert_eq!(game.totals(), TeamScores::new(182, 60));
+
+        game.flag_revoke(player, card).unwrap();
+        assert_eq!(game.has_winner(), Some(Team::EastWest));
+        assert_eq!(game.totals().south_north, 0);
+        assert_eq!(game.rounds().len(), 1);
+        assert!(!game.is_bidding());
+        assert_eq!(game.current_player(), None);
+    }
+
+    #[test]
+    fn bidding_without_contract_deals_again() {
+        let rules = RuleSet { validation: ValidationMode::Permissive, ..RuleSet::default() };
+        let mut game = Game::from_seed(rules, 6);
+        game.new_round();
+        game.bid(Player::South, Bid::Counter, None).unwrap();
+        for player in &[Player::West, Player::North, Player::East] {
+            game.bid(*player, Bid::Pass, None).unwrap();
+        }
+        assert_eq!(game.redeals(), &[Redeal { deal: 1, reason: RedealReason::NoContract }]);
+        assert_eq!(game.current_player(), Some(Player::West));
+        assert!(!game.legal_actions().is_empty());
+    }
+
+    #[test]
+    fn can_seat_players() {
+        let mut game = Game::default();
+        assert!(game.player_info(Seat::South).is_none());
+        game.seat(Seat::South, PlayerInfo::new("42", "Alice")).unwrap();
+        game.seat(Seat::North, PlayerInfo::new("43", "Bob")).unwrap();
+        assert_eq!(game.player_info(Seat::South).unwrap().name, "Alice");
+        assert_eq!(game.seat_of("43"), Some(Seat::North));
+        assert_eq!(game.seat_of("43").map(|s| s.player()), Some(Player::North));
+        assert!(game.seat(Seat::East, PlayerInfo::new("43", "Bob")).is_err());
+        // Changing the name at the same seat is fine
+        assert!(game.seat(Seat::North, PlayerInfo::new("43", "Robert")).is_ok());
+
+        assert_eq!(game.leave(Seat::North).unwrap().name, "Robert");
+        assert_eq!(game.seat_of("43"), None);
+        assert!(game.seat(Seat::East, PlayerInfo::new("43", "Bob")).is_ok());
+    }
+
+    #[test]
+    fn can_act_by_index() {
+        let mut game = Game::default();
+        game.new_round();
+        let actions = game.legal_actions_indexed();
+        // pass + 9 levels and capot in 4 suits
+        assert_eq!(actions.len(), 1 + 10 * 4);
+        assert_eq!(actions[0], (0, Action::Bid(Bid::Pass, None)));
+        assert_eq!(actions[1], (1, Action::Bid(Bid::Eighty, Some(Suit::Clubs))));
+        assert_eq!(game.player_view(Player::South).legal_actions.len(), actions.len());
+        assert!(game.player_view(Player::West).legal_actions.is_empty());
+
+        assert!(game.apply_index(Player::South, 41).is_err());
+        assert!(game.apply_index(Player::South, 1).is_ok());
+        assert_eq!(game.current_player(), Some(Player::West));
+        for player in &[Player::West, Player::North, Player::East] {
+            assert!(game.apply_index(*player, 0).is_ok());
+        }
+        assert!(!game.is_bidding());
+        let actions = game.legal_actions_indexed();
+        assert_eq!(actions.len(), 8);
+        match actions[0].1 {
+            Action::Play(card) => assert_eq!(game.player_view(Player::South).hand[0], card),
+            _ => panic!("Expected a card"),
+        }
+    }
+
+    #[test]
+    fn can_use_another_score_goal() {
+        let mut game = Game::new(RuleSet { game_length: GameLength::ScoreGoal(1500), ..RuleSet::default() });
+        add_round(&mut game, 1200, 0);
+        assert_eq!(game.has_winner(), None);
+        add_round(&mut game, 301, 0);
+        assert_eq!(game.has_winner(), Some(Team::SouthNorth));
+    }
+
+    #[test]
+    fn can_play_a_fixed_number_of_deals() {
+        let mut game = Game::new(RuleSet { game_length: GameLength::Deals(3), ..RuleSet::default() });
+        add_round(&mut game, 2000, 0);
+        add_round(&mut game, 0, 1000);
+        assert_eq!(game.has_winner(), None);
+        add_round(&mut game, 0, 1000);
+        assert_eq!(game.has_winner(), None);
+        add_round(&mut game, 0, 1);
+        assert_eq!(game.has_winner(), Some(Team::EastWest));
+    }
+
+    #[test]
+    fn can_round_scores_to_ten() {
+        let mut game = Game::new(RuleSet { round_to_ten: true, ..RuleSet::default() });
+        for _ in 0..10 {
+            add_round(&mut game, 96, 66);
+        }
+        // 96 is rounded to 100 each time
+        assert_eq!(game.has_winner(), None);
+        add_round(&mut game, 4, 0);
+        assert_eq!(game.has_winner(), None);
+        add_round(&mut game, 5, 0);
+        assert_eq!(game.has_winner(), Some(Team::SouthNorth));
+
+        assert_eq!(game.rules().round_score(81), 80);
+        assert_eq!(game.rules().round_score(85), 90);
+    }
+}
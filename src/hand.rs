@@ -0,0 +1,161 @@
+use std::cmp::Reverse;
+use std::ops::Deref;
+use std::slice;
+use std::vec;
+
+use cards::{Card, Suit};
+use errors::ContreeError;
+
+
+/// The cards held by a player. It derefs to a slice of cards for everything else
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Hand {
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    pub fn new(cards: Vec<Card>) -> Hand {
+        Hand { cards }
+    }
+
+    pub fn has_suit(&self, suit: Suit) -> bool {
+        self.cards.iter().any(|c| c.suit == suit)
+    }
+
+    /// The cards of that suit, in the order of the hand
+    pub fn cards_of(&self, suit: Suit) -> Vec<Card> {
+        self.cards.iter().filter(|c| c.suit == suit).cloned().collect()
+    }
+
+    pub fn trumps(&self, trump: Suit) -> Vec<Card> {
+        self.cards_of(trump)
+    }
+
+    pub fn highest_trump(&self, trump: Suit) -> Option<Card> {
+        self.cards.iter().filter(|c| c.suit == trump).max_by_key(|c| c.strength(trump)).cloned()
+    }
+
+    /// Takes the card out of the hand. Errors if it isn't in it, with the cards of the hand
+    pub fn remove(&mut self, card: Card) -> Result<(), ContreeError> {
+        match self.cards.iter().position(|c| *c == card) {
+            Some(index) => {
+                self.cards.remove(index);
+                Ok(())
+            },
+            None => Err(ContreeError::CardNotInHand { card, playable: self.cards.clone() }),
+        }
+    }
+
+    /// The cards grouped by suit, strongest first within a suit
+    pub fn sorted(&self, trump: Suit) -> Vec<Card> {
+        let mut cards = self.cards.clone();
+        cards.sort_by_key(|c| (c.suit as usize, Reverse(c.strength(trump))));
+        cards
+    }
+
+    /// Same as `sorted` with the suits in `cards::DISPLAY_ORDER`
+    pub fn sorted_for_display(&self, trump: Suit) -> Vec<Card> {
+        let mut cards = self.cards.clone();
+        cards.sort_by_key(|c| (c.suit.display_position(), Reverse(c.strength(trump))));
+        cards
+    }
+
+    pub fn into_vec(self) -> Vec<Card> {
+        self.cards
+    }
+}
+
+impl Deref for Hand {
+    type Target = [Card];
+
+    fn deref(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
+impl IntoIterator for Hand {
+    type Item = Card;
+    type IntoIter = vec::IntoIter<Card>;
+
+    fn into_iter(self) -> vec::IntoIter<Card> {
+        self.cards.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Hand {
+    type Item = &'a Card;
+    type IntoIter = slice::Iter<'a, Card>;
+
+    fn into_iter(self) -> slice::Iter<'a, Card> {
+        self.cards.iter()
+    }
+}
+
+impl From<Vec<Card>> for Hand {
+    fn from(cards: Vec<Card>) -> Hand {
+        Hand::new(cards)
+    }
+}
+
+impl<'a> From<&'a [Card]> for Hand {
+    fn from(cards: &'a [Card]) -> Hand {
+        Hand::new(cards.to_vec())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Rank;
+
+    fn hand() -> Hand {
+        Hand::new(vec![
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Spades, Rank::Seven),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Clubs, Rank::Ten),
+            Card::new(Suit::Hearts, Rank::King),
+        ])
+    }
+
+    #[test]
+    fn can_query_a_hand() {
+        let hand = hand();
+        assert!(hand.has_suit(Suit::Spades));
+        assert!(!hand.has_suit(Suit::Diamonds));
+        assert_eq!(hand.cards_of(Suit::Hearts).len(), 3);
+        assert_eq!(hand.highest_trump(Suit::Hearts), Some(Card::new(Suit::Hearts, Rank::Nine)));
+        assert_eq!(hand.highest_trump(Suit::Diamonds), None);
+        // Works as a slice too
+        assert_eq!(hand.len(), 5);
+        assert!(hand.contains(&Card::new(Suit::Clubs, Rank::Ten)));
+    }
+
+    #[test]
+    fn can_sort_and_remove() {
+        let mut hand = hand();
+        assert_eq!(hand.sorted(Suit::Hearts), vec![
+            Card::new(Suit::Clubs, Rank::Ten),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Spades, Rank::Seven),
+        ]);
+        assert_eq!(hand.sorted(Suit::Spades)[1], Card::new(Suit::Hearts, Rank::Ace));
+        assert_eq!(hand.sorted_for_display(Suit::Hearts), vec![
+            Card::new(Suit::Spades, Rank::Seven),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Clubs, Rank::Ten),
+        ]);
+
+        assert!(hand.remove(Card::new(Suit::Spades, Rank::Seven)).is_ok());
+        assert_eq!(
+            hand.remove(Card::new(Suit::Spades, Rank::Seven)),
+            Err(ContreeError::CardNotInHand { card: Card::new(Suit::Spades, Rank::Seven), playable: hand.to_vec() }),
+        );
+        assert!(!hand.has_suit(Suit::Spades));
+    }
+}
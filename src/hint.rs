@@ -0,0 +1,31 @@
+use actions::Action;
+use cards::Suit;
+
+
+/// Why an action is suggested, for clients to display their own explanation
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum HintReason {
+    /// The team is expected to make `estimate` trick points with that suit as trump
+    HandEstimate { suit: Suit, estimate: usize },
+    /// No suit has enough trumps and points to bid
+    NoSuitWorthBidding,
+    /// There is no other choice
+    OnlyCard,
+    /// Leading with an ace should win the trick
+    LeadAce,
+    /// Leading with the card that gives away the fewest points
+    LeadCheapest,
+    /// The partner is winning the trick so give them points
+    FeedPartner,
+    /// Winning the trick with the weakest card that can win it
+    WinCheaply,
+    /// The trick cannot be won so give away the fewest points
+    Discard,
+}
+
+/// A suggested legal action for a player
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Hint {
+    pub action: Action,
+    pub reason: HintReason,
+}
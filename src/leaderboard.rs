@@ -0,0 +1,179 @@
+//! A leaderboard of people rather than seats: every finished game is recorded with who sat
+//! where, and each player accumulates wins, score differential and streaks across games.
+
+use std::collections::HashMap;
+
+use failure::Error;
+
+use game::Game;
+use players::{Player, Team};
+
+
+/// Everything the leaderboard knows about a player
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LeaderboardEntry {
+    pub player_id: String,
+    pub games: usize,
+    pub wins: usize,
+    /// Sum over all the games of the final total of the team of the player minus the opponents one
+    pub score_differential: isize,
+    /// Consecutive games won, or lost if negative, up to the last game recorded
+    pub current_streak: isize,
+    /// Most consecutive games won
+    pub best_streak: usize,
+}
+
+impl LeaderboardEntry {
+    fn new(player_id: &str) -> LeaderboardEntry {
+        LeaderboardEntry {
+            player_id: player_id.to_string(),
+            games: 0,
+            wins: 0,
+            score_differential: 0,
+            current_streak: 0,
+            best_streak: 0,
+        }
+    }
+
+    fn record(&mut self, won: bool, differential: isize) {
+        self.games += 1;
+        self.score_differential += differential;
+        if won {
+            self.wins += 1;
+            self.current_streak = self.current_streak.max(0) + 1;
+            self.best_streak = self.best_streak.max(self.current_streak as usize);
+        } else {
+            self.current_streak = self.current_streak.min(0) - 1;
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+
+    pub fn average_differential(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.score_differential as f64 / self.games as f64
+    }
+}
+
+/// Results of players across many games
+#[derive(Debug, Clone, Default)]
+pub struct Leaderboard {
+    entries: HashMap<String, LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    pub fn new() -> Leaderboard {
+        Leaderboard::default()
+    }
+
+    /// Records a finished game, `player_ids` being who sat at each seat, indexed by `Player::index`.
+    /// Errors if the game is not over or if a player is given several seats
+    pub fn record_game(&mut self, player_ids: [&str; 4], game: &Game) -> Result<(), Error> {
+        for (i, id) in player_ids.iter().enumerate() {
+            if player_ids[..i].contains(id) {
+                bail!("{} cannot sit at several seats", id);
+            }
+        }
+        let winner = match game.has_winner() {
+            Some(team) => team,
+            None => bail!("Only finished games can be recorded"),
+        };
+        let totals = game.standing().totals;
+        for player in Player::iterator() {
+            let team: Team = player.team();
+            let differential = totals[team] as isize - totals[team.opponent()] as isize;
+            let id = player_ids[player.index()];
+            self.entries
+                .entry(id.to_string())
+                .or_insert_with(|| LeaderboardEntry::new(id))
+                .record(team == winner, differential);
+        }
+        Ok(())
+    }
+
+    pub fn entry(&self, player_id: &str) -> Option<&LeaderboardEntry> {
+        self.entries.get(player_id)
+    }
+
+    /// All the players, best first: most wins, then best win rate, then best average
+    /// differential, then id
+    pub fn ranking(&self) -> Vec<&LeaderboardEntry> {
+        let mut entries: Vec<&LeaderboardEntry> = self.entries.values().collect();
+        entries.sort_by(|a, b| {
+            b.wins.cmp(&a.wins)
+                .then(b.win_rate().partial_cmp(&a.win_rate()).unwrap())
+                .then(b.average_differential().partial_cmp(&a.average_differential()).unwrap())
+                .then(a.player_id.cmp(&b.player_id))
+        });
+        entries
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::heuristic_table;
+    use strategy::run_game;
+
+    fn play(seed: u64) -> Game {
+        let mut game = Game::from_seed(Default::default(), seed);
+        run_game(&mut game, &mut heuristic_table()).unwrap();
+        game
+    }
+
+    #[test]
+    fn can_rank_players() {
+        let mut leaderboard = Leaderboard::new();
+        assert!(leaderboard.record_game(["a", "b", "c", "d"], &Game::default()).is_err());
+
+        let mut games = vec![];
+        for seed in 0..3 {
+            let game = play(seed);
+            // Alice and Carol always sit together, Bob changes partner
+            let ids = if seed == 1 { ["alice", "bob", "carol", "erin"] } else { ["alice", "bob", "carol", "dave"] };
+            leaderboard.record_game(ids, &game).unwrap();
+            games.push(game);
+        }
+
+        let alice = leaderboard.entry("alice").unwrap();
+        let carol = leaderboard.entry("carol").unwrap();
+        assert_eq!(alice.games, 3);
+        assert_eq!(alice.wins, carol.wins);
+        assert_eq!(alice.score_differential, -leaderboard.entry("bob").unwrap().score_differential);
+        assert_eq!(leaderboard.entry("erin").unwrap().games, 1);
+        assert!(leaderboard.entry("nobody").is_none());
+
+        let wins = games.iter().filter(|g| g.has_winner() == Some(Team::SouthNorth)).count();
+        assert_eq!(alice.wins, wins);
+        let last_won = games[2].has_winner() == Some(Team::SouthNorth);
+        assert_eq!(alice.current_streak > 0, last_won);
+
+        assert!(leaderboard.record_game(["alice", "bob", "alice", "dave"], &games[0]).is_err());
+        assert_eq!(leaderboard.entry("alice").unwrap().games, 3);
+
+        let ranking = leaderboard.ranking();
+        assert_eq!(ranking.len(), 5);
+        assert!(ranking.windows(2).all(|w| w[0].wins >= w[1].wins));
+    }
+
+    #[test]
+    fn streaks_reset() {
+        let mut entry = LeaderboardEntry::new("a");
+        for won in &[true, true, false, true] {
+            entry.record(*won, 0);
+        }
+        assert_eq!(entry.best_streak, 2);
+        assert_eq!(entry.current_streak, 1);
+        entry.record(false, 0);
+        entry.record(false, 0);
+        assert_eq!(entry.current_streak, -2);
+    }
+}
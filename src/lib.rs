@@ -1,10 +1,58 @@
 extern crate rand;
 #[macro_use] extern crate failure;
 
+pub mod actions;
+pub mod advisor;
+pub mod ai;
+pub mod announcements;
+pub mod audit;
+pub mod card_set;
+pub mod constraints;
 pub mod cards;
+pub mod csv;
+pub mod deal_generator;
 pub mod deck;
+pub mod diff;
+pub mod duplicate;
+pub mod env;
+pub mod errors;
+pub mod evaluation;
+pub mod export;
 pub mod game;
+pub mod hand;
+pub mod hint;
 pub mod bids;
+pub mod bots;
+pub mod capabilities;
+pub mod leaderboard;
+pub mod locale;
+pub mod neural;
 pub mod players;
+pub mod puzzle;
+pub mod rating;
+pub mod registry;
+pub mod report;
 pub mod round;
+pub mod rubber;
+pub mod rules;
+pub mod sampling;
+pub mod score_sheet;
+pub mod session;
+pub mod significance;
+pub mod simulation;
+pub mod solver;
+pub mod stats;
+pub mod store;
+pub mod strategy;
+pub mod table_id;
+pub mod take;
+pub mod trick;
+pub mod team_scores;
+pub mod tournament;
+pub mod vectors;
+pub mod view;
+pub mod zobrist;
+mod sha256;
+
+pub use capabilities::capabilities;
 
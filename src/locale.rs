@@ -0,0 +1,189 @@
+//! Names of cards, suits, bids and players in English or French, for UIs and logs.
+//! `localized(value, locale)` wraps a value so it can be formatted with `{}`.
+//!
+//! Suits, ranks and cards also have a compact form, selected with the alternate flag `{:#}`:
+//! suits are their symbol and ranks their number or initial, eg `V♥` or `J♥` for the
+//! jack of hearts in French or English.
+
+use std::fmt;
+
+use bids::Bid;
+use cards::{Card, Rank, Suit};
+use players::Player;
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    French,
+}
+
+/// A value displayed in a given language
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Localized<T> {
+    pub value: T,
+    pub locale: Locale,
+}
+
+pub fn localized<T>(value: T, locale: Locale) -> Localized<T> {
+    Localized { value, locale }
+}
+
+fn suit_name(suit: Suit, locale: Locale) -> &'static str {
+    match (locale, suit) {
+        (Locale::English, Suit::Clubs) => "Clubs",
+        (Locale::English, Suit::Diamonds) => "Diamonds",
+        (Locale::English, Suit::Hearts) => "Hearts",
+        (Locale::English, Suit::Spades) => "Spades",
+        (Locale::French, Suit::Clubs) => "Trèfle",
+        (Locale::French, Suit::Diamonds) => "Carreau",
+        (Locale::French, Suit::Hearts) => "Cœur",
+        (Locale::French, Suit::Spades) => "Pique",
+    }
+}
+
+fn suit_symbol(suit: Suit) -> char {
+    match suit {
+        Suit::Clubs => '♣',
+        Suit::Diamonds => '♦',
+        Suit::Hearts => '♥',
+        Suit::Spades => '♠',
+    }
+}
+
+fn rank_abbreviation(rank: Rank, locale: Locale) -> &'static str {
+    match (locale, rank) {
+        (_, Rank::Seven) => "7",
+        (_, Rank::Eight) => "8",
+        (_, Rank::Nine) => "9",
+        (_, Rank::Ten) => "10",
+        (_, Rank::Ace) => "A",
+        (Locale::English, Rank::Jack) => "J",
+        (Locale::English, Rank::Queen) => "Q",
+        (Locale::English, Rank::King) => "K",
+        (Locale::French, Rank::Jack) => "V",
+        (Locale::French, Rank::Queen) => "D",
+        (Locale::French, Rank::King) => "R",
+    }
+}
+
+fn rank_name(rank: Rank, locale: Locale) -> &'static str {
+    match (locale, rank) {
+        (Locale::English, Rank::Seven) => "Seven",
+        (Locale::English, Rank::Eight) => "Eight",
+        (Locale::English, Rank::Nine) => "Nine",
+        (Locale::English, Rank::Ten) => "Ten",
+        (Locale::English, Rank::Jack) => "Jack",
+        (Locale::English, Rank::Queen) => "Queen",
+        (Locale::English, Rank::King) => "King",
+        (Locale::English, Rank::Ace) => "Ace",
+        (Locale::French, Rank::Seven) => "Sept",
+        (Locale::French, Rank::Eight) => "Huit",
+        (Locale::French, Rank::Nine) => "Neuf",
+        (Locale::French, Rank::Ten) => "Dix",
+        (Locale::French, Rank::Jack) => "Valet",
+        (Locale::French, Rank::Queen) => "Dame",
+        (Locale::French, Rank::King) => "Roi",
+        (Locale::French, Rank::Ace) => "As",
+    }
+}
+
+impl fmt::Display for Localized<Suit> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", suit_symbol(self.value));
+        }
+        write!(f, "{}", suit_name(self.value, self.locale))
+    }
+}
+
+impl fmt::Display for Localized<Rank> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", rank_abbreviation(self.value, self.locale));
+        }
+        write!(f, "{}", rank_name(self.value, self.locale))
+    }
+}
+
+/// `Jack of Hearts` or `Valet de Cœur`, `J♥` or `V♥` in the compact form
+impl fmt::Display for Localized<Card> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            let (rank, suit) = (self.value.rank, self.value.suit);
+            return write!(f, "{}{}", rank_abbreviation(rank, self.locale), suit_symbol(suit));
+        }
+        let of = match self.locale {
+            Locale::English => "of",
+            Locale::French => "de",
+        };
+        let (rank, suit) = (self.value.rank, self.value.suit);
+        write!(f, "{} {} {}", rank_name(rank, self.locale), of, suit_name(suit, self.locale))
+    }
+}
+
+impl fmt::Display for Localized<Bid> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match (self.locale, self.value) {
+            (Locale::English, Bid::Pass) => "pass",
+            (Locale::English, Bid::Counter) => "counter",
+            (Locale::English, Bid::DoubleCounter) => "double counter",
+            (Locale::French, Bid::Pass) => "passe",
+            (Locale::French, Bid::Counter) => "contrée",
+            (Locale::French, Bid::DoubleCounter) => "surcontrée",
+            (_, Bid::Capot) => "capot",
+            (_, bid) => return write!(f, "{}", bid.value()),
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for Localized<Player> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match (self.locale, self.value) {
+            (Locale::English, Player::South) => "South",
+            (Locale::English, Player::West) => "West",
+            (Locale::English, Player::North) => "North",
+            (Locale::English, Player::East) => "East",
+            (Locale::French, Player::South) => "Sud",
+            (Locale::French, Player::West) => "Ouest",
+            (Locale::French, Player::North) => "Nord",
+            (Locale::French, Player::East) => "Est",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_name_things_in_french() {
+        let card = Card::new(Suit::Hearts, Rank::Jack);
+        assert_eq!(localized(card, Locale::French).to_string(), "Valet de Cœur");
+        assert_eq!(localized(card, Locale::English).to_string(), "Jack of Hearts");
+        assert_eq!(localized(Suit::Spades, Locale::French).to_string(), "Pique");
+        assert_eq!(localized(Rank::Queen, Locale::French).to_string(), "Dame");
+        assert_eq!(localized(Bid::Counter, Locale::French).to_string(), "contrée");
+        assert_eq!(localized(Bid::HundredTwenty, Locale::French).to_string(), "120");
+        assert_eq!(localized(Bid::Capot, Locale::English).to_string(), "capot");
+        assert_eq!(localized(Player::West, Locale::French).to_string(), "Ouest");
+        assert_eq!(Locale::default(), Locale::English);
+    }
+
+    #[test]
+    fn can_use_symbols() {
+        let jack = Card::new(Suit::Hearts, Rank::Jack);
+        let ten = Card::new(Suit::Spades, Rank::Ten);
+        assert_eq!(format!("{:#}", localized(jack, Locale::French)), "V♥");
+        assert_eq!(format!("{:#}", localized(jack, Locale::English)), "J♥");
+        assert_eq!(format!("{:#}", localized(ten, Locale::French)), "10♠");
+        assert_eq!(format!("{:#}", localized(Rank::King, Locale::French)), "R");
+        assert_eq!(format!("{:#}", localized(Suit::Clubs, Locale::English)), "♣");
+        // The symbols are accepted by the parser
+        assert_eq!(format!("{:#}", localized(ten, Locale::English)).parse(), Ok(ten));
+    }
+}
@@ -0,0 +1,106 @@
+//! A bot driven by a policy model, eg a neural network: the model scores every action of
+//! `action_space()` from `PlayerView::encode` and the bot plays the best legal one.
+//! The model itself is provided by the caller, whatever runtime it uses.
+
+use actions::{action_space, Action};
+use bids::Bid;
+use cards::{Card, Suit};
+use strategy::Strategy;
+use view::{PlayerView, ENCODING_LEN};
+
+
+/// A model scoring actions from an encoded view
+pub trait PolicyModel {
+    /// Takes `ENCODING_LEN` features and returns one score (eg a logit) per action
+    /// of `action_space()`, the higher the better
+    fn scores(&self, features: &[f32]) -> Vec<f32>;
+}
+
+/// Plays the legal action with the highest score according to the model
+#[derive(Debug, Clone)]
+pub struct NeuralBot<M: PolicyModel> {
+    model: M,
+    actions: Vec<Action>,
+}
+
+impl<M: PolicyModel> NeuralBot<M> {
+    pub fn new(model: M) -> NeuralBot<M> {
+        NeuralBot { model, actions: action_space() }
+    }
+
+    /// The best legal action for the view. Panics if the model doesn't return one score per action
+    pub fn choose_action(&self, view: &PlayerView) -> Action {
+        let features = view.encode();
+        debug_assert_eq!(features.len(), ENCODING_LEN);
+        let scores = self.model.scores(&features);
+        assert_eq!(scores.len(), self.actions.len(), "The model must return one score per action");
+
+        self.actions
+            .iter()
+            .zip(scores.iter())
+            .filter(|&(action, _)| view.legal_actions.contains(action))
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(::std::cmp::Ordering::Equal))
+            .map(|(action, _)| *action)
+            // Only called when it's our turn so there is always a legal action
+            .unwrap()
+    }
+}
+
+impl<M: PolicyModel> Strategy for NeuralBot<M> {
+    fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>) {
+        match self.choose_action(view) {
+            Action::Bid(bid, suit) => (bid, suit),
+            Action::Play(_) | Action::Take(_) => (Bid::Pass, None),
+        }
+    }
+
+    fn choose_card(&mut self, view: &PlayerView) -> Card {
+        match self.choose_action(view) {
+            Action::Play(card) => card,
+            Action::Bid(..) | Action::Take(_) => view.playable_cards[0],
+        }
+    }
+
+    fn choose_take(&mut self, view: &PlayerView) -> Option<Suit> {
+        match self.choose_action(view) {
+            Action::Take(suit) => suit,
+            Action::Bid(..) | Action::Play(_) => None,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::Game;
+    use strategy::run_game;
+
+    /// Prefers passing and the last actions of the action space: the highest cards
+    struct LastAction;
+
+    impl PolicyModel for LastAction {
+        fn scores(&self, features: &[f32]) -> Vec<f32> {
+            assert_eq!(features.len(), ENCODING_LEN);
+            let mut scores: Vec<f32> = (0..action_space().len()).map(|i| i as f32).collect();
+            scores[0] = 1000.0;
+            scores
+        }
+    }
+
+    #[test]
+    fn only_plays_legal_actions() {
+        let mut game = Game::default();
+        game.new_round();
+        let bot = NeuralBot::new(LastAction);
+        assert_eq!(bot.choose_action(&game.player_view(game.current_player().unwrap())), Action::Bid(Bid::Pass, None));
+
+        let mut strategies: [Box<dyn Strategy>; 4] = [
+            Box::new(NeuralBot::new(LastAction)),
+            Box::new(::bots::HeuristicBot::new()),
+            Box::new(NeuralBot::new(LastAction)),
+            Box::new(::bots::HeuristicBot::new()),
+        ];
+        assert!(run_game(&mut game, &mut strategies).is_ok());
+    }
+}
@@ -1,3 +1,6 @@
+use std::slice::Iter;
+
+/// Whoever sits at one of the four positions, as seen by the rules of the game
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub enum Player {
     North,
@@ -16,6 +19,22 @@ impl Player {
         }
     }
 
+    // Same as for cards, in playing order starting from south
+    pub fn iterator() -> Iter<'static, Player> {
+        [Player::South, Player::West, Player::North, Player::East].iter()
+    }
+
+    /// Position of the player in the playing order starting from south,
+    /// useful to store things per player in arrays
+    pub fn index(&self) -> usize {
+        match *self {
+            Player::South => 0,
+            Player::West => 1,
+            Player::North => 2,
+            Player::East => 3,
+        }
+    }
+
     pub fn team(&self) -> Team {
         match *self {
             Player::South | Player::North => Team::SouthNorth,
@@ -24,8 +43,64 @@ impl Player {
     }
 }
 
+/// A position at the table. Who sits there is a `PlayerInfo`, see `Game::seat`,
+/// and plays as the `Player` of the same name
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum Seat {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl Seat {
+    /// The player playing from that seat
+    pub fn player(&self) -> Player {
+        match *self {
+            Seat::North => Player::North,
+            Seat::West => Player::West,
+            Seat::South => Player::South,
+            Seat::East => Player::East,
+        }
+    }
+
+    /// The seat of that player
+    pub fn of(player: Player) -> Seat {
+        match player {
+            Player::North => Seat::North,
+            Player::West => Seat::West,
+            Player::South => Seat::South,
+            Player::East => Seat::East,
+        }
+    }
+}
+
+/// Someone sitting at the table
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct PlayerInfo {
+    /// Unique across the players of a game, eg an account id
+    pub id: String,
+    pub name: String,
+}
+
+impl PlayerInfo {
+    pub fn new(id: &str, name: &str) -> PlayerInfo {
+        PlayerInfo { id: id.to_string(), name: name.to_string() }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
 pub enum Team {
     SouthNorth,
     EastWest,
 }
+
+impl Team {
+    /// The other team
+    pub fn opponent(&self) -> Team {
+        match *self {
+            Team::SouthNorth => Team::EastWest,
+            Team::EastWest => Team::SouthNorth,
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! "Find the best play" problems: positions where the attack has several cards to choose from
+//! but only one of them makes the contract, as found by the double-dummy solver.
+//! Deals are played by the hint bot and every position where the attack is to play is checked.
+
+use bids::Bid;
+use cards::Card;
+use game::Game;
+use players::Player;
+use round::Round;
+use rules::RuleSet;
+use solver::{solve_with_table, TranspositionTable, DEFAULT_TABLE_CAPACITY};
+
+
+/// A position to solve with its answer
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Puzzle {
+    /// The round at the position of the puzzle, with the cards already played
+    pub round: Round,
+    /// The only card making the contract followed by the perfect play of everyone
+    /// until the end of the round
+    pub solution: Vec<(Player, Card)>,
+}
+
+impl Puzzle {
+    /// Whether that card is the one to play
+    pub fn is_solution(&self, card: Card) -> bool {
+        self.solution.first().map(|&(_, c)| c) == Some(card)
+    }
+
+    /// The hands as they were dealt, indexed by `Player::index`
+    pub fn deal(&self) -> [Vec<Card>; 4] {
+        let mut hands: [Vec<Card>; 4] = Default::default();
+        for player in Player::iterator() {
+            hands[player.index()] = self.round.hand(*player).to_vec();
+        }
+        let played = self.round.tricks().iter().flat_map(|(trick, _)| trick.iter());
+        for &(player, card) in played.chain(self.round.current_trick().iter()) {
+            hands[player.index()].push(card);
+        }
+        hands
+    }
+
+    /// Checks with the solver that the solution is still the only card making the contract
+    pub fn verify(&self, rules: &RuleSet) -> bool {
+        find_puzzle(&self.round, rules).map_or(false, |p| p.solution == self.solution)
+    }
+}
+
+/// The line starting with the card if it makes the contract, the rest of the round played perfectly
+fn makes_contract(round: &Round, card: Card, rules: &RuleSet, table: &mut TranspositionTable) -> Option<Vec<(Player, Card)>> {
+    let mut next = round.clone();
+    next.play(round.next_player(), card, rules).unwrap();
+    let mut line = vec![(round.next_player(), card)];
+    line.extend(solve_with_table(&next, rules, table).line);
+    for &(player, card) in &line[1..] {
+        next.play(player, card, rules).unwrap();
+    }
+    if next.is_made(rules) { Some(line) } else { None }
+}
+
+/// A puzzle if the attack is to play in that position with several cards
+/// to choose from and exactly one of them makes the contract.
+/// Capot contracts are skipped: the solver maximizes points, not tricks, so its line
+/// can give a trick away while another line wins all of them
+pub fn find_puzzle(round: &Round, rules: &RuleSet) -> Option<Puzzle> {
+    let player = round.next_player();
+    if round.is_over() || player.team() != round.contract().player().team() {
+        return None;
+    }
+    if round.contract().bid() == Some(Bid::Capot) {
+        return None;
+    }
+    let cards = round.playable_cards(player, rules);
+    if cards.len() < 2 {
+        return None;
+    }
+
+    let mut table = TranspositionTable::new(DEFAULT_TABLE_CAPACITY);
+    let mut solution = None;
+    for card in cards {
+        if let Some(line) = makes_contract(round, card, rules, &mut table) {
+            if solution.is_some() {
+                return None;
+            }
+            solution = Some(line);
+        }
+    }
+    solution.map(|solution| Puzzle { round: round.clone(), solution })
+}
+
+/// Plays the first round of the game with that seed with the hint bot and returns the
+/// puzzles found once at least `min_tricks` tricks were played.
+/// Solving is quicker with fewer tricks left so a higher `min_tricks` scans faster
+pub fn scan_deal(seed: u64, rules: &RuleSet, min_tricks: usize) -> Vec<Puzzle> {
+    let mut game = Game::from_seed(rules.clone(), seed);
+    game.new_round();
+    let mut puzzles = vec![];
+
+    while game.rounds().is_empty() {
+        let player = match game.current_player() {
+            Some(player) => player,
+            None => break,
+        };
+        if let Some(round) = game.current_round() {
+            if round.tricks().len() >= min_tricks {
+                puzzles.extend(find_puzzle(round, rules));
+            }
+        }
+        let action = game.hint(player).unwrap().action;
+        game.apply(player, action).unwrap();
+    }
+    puzzles
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions::Action;
+    use cards::Suit;
+
+    #[test]
+    fn can_find_and_verify_puzzles() {
+        let rules = RuleSet::default();
+        let puzzles: Vec<Puzzle> = (0..20).flat_map(|seed| scan_deal(seed, &rules, 5)).collect();
+        assert!(!puzzles.is_empty());
+
+        for puzzle in &puzzles {
+            assert!(puzzle.verify(&rules));
+            let (player, card) = puzzle.solution[0];
+            assert_eq!(player, puzzle.round.next_player());
+            assert!(puzzle.is_solution(card));
+            let cards_left: usize = Player::iterator().map(|p| puzzle.round.hand(*p).len()).sum();
+            assert_eq!(puzzle.solution.len(), cards_left);
+
+            let hands = puzzle.deal();
+            assert!(hands.iter().all(|h| h.len() == 8));
+            // Any other card fails the contract
+            let others = puzzle.round.playable_cards(player, &rules).into_iter().filter(|c| *c != card);
+            let mut table = TranspositionTable::new(DEFAULT_TABLE_CAPACITY);
+            for other in others {
+                assert!(makes_contract(&puzzle.round, other, &rules, &mut table).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn capot_contracts_are_not_puzzles() {
+        let rules = RuleSet::default();
+        let mut game = Game::from_seed(rules.clone(), 0);
+        game.new_round();
+        let taker = game.current_player().unwrap();
+        game.apply(taker, Action::Bid(Bid::Capot, Some(Suit::Hearts))).unwrap();
+        let mut checked = 0;
+        while game.rounds().is_empty() {
+            let player = game.current_player().unwrap();
+            if let Some(round) = game.current_round() {
+                if round.tricks().len() >= 5 && player.team() == taker.team() {
+                    assert!(find_puzzle(round, &rules).is_none());
+                    checked += 1;
+                }
+            }
+            let action = game.hint(player).unwrap().action;
+            game.apply(player, action).unwrap();
+        }
+        assert!(checked > 0);
+    }
+}
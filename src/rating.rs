@@ -0,0 +1,133 @@
+//! Elo ratings for strategies, to measure the progress of bots over simulated matches.
+
+use std::collections::HashMap;
+
+use failure::Error;
+
+use game::Game;
+use players::Team;
+use registry::Registry;
+use rules::RuleSet;
+use strategy::run_game;
+
+
+/// Rating of a strategy that hasn't played yet
+pub const INITIAL_RATING: f64 = 1500.0;
+/// How much a single game can change the ratings
+pub const DEFAULT_K_FACTOR: f64 = 32.0;
+
+/// Elo ratings of strategies, by id
+#[derive(Debug, Clone, PartialEq)]
+pub struct EloRatings {
+    k_factor: f64,
+    ratings: HashMap<String, f64>,
+    games: HashMap<String, usize>,
+}
+
+impl Default for EloRatings {
+    fn default() -> EloRatings {
+        EloRatings::new(DEFAULT_K_FACTOR)
+    }
+}
+
+impl EloRatings {
+    pub fn new(k_factor: f64) -> EloRatings {
+        EloRatings { k_factor, ratings: HashMap::new(), games: HashMap::new() }
+    }
+
+    pub fn rating(&self, id: &str) -> f64 {
+        self.ratings.get(id).cloned().unwrap_or(INITIAL_RATING)
+    }
+
+    /// How many games the strategy played
+    pub fn games(&self, id: &str) -> usize {
+        self.games.get(id).cloned().unwrap_or(0)
+    }
+
+    /// Probability of `a` beating `b` according to their ratings
+    pub fn expected_score(&self, a: &str, b: &str) -> f64 {
+        1.0 / (1.0 + 10f64.powf((self.rating(b) - self.rating(a)) / 400.0))
+    }
+
+    /// Updates the ratings after a game between `winner` and `loser`
+    pub fn record_win(&mut self, winner: &str, loser: &str) {
+        let change = self.k_factor * (1.0 - self.expected_score(winner, loser));
+        let winner_rating = self.rating(winner) + change;
+        let loser_rating = self.rating(loser) - change;
+        self.ratings.insert(winner.to_string(), winner_rating);
+        self.ratings.insert(loser.to_string(), loser_rating);
+        *self.games.entry(winner.to_string()).or_insert(0) += 1;
+        *self.games.entry(loser.to_string()).or_insert(0) += 1;
+    }
+
+    /// All the strategies rated, best first
+    pub fn leaderboard(&self) -> Vec<(&str, f64)> {
+        let mut ratings: Vec<(&str, f64)> = self.ratings.iter().map(|(id, r)| (id.as_str(), *r)).collect();
+        ratings.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(b.0)));
+        ratings
+    }
+}
+
+/// Plays `games` games between the two strategies of the registry, both players of a team
+/// using the same strategy and the teams swapping seats every game, and updates the ratings.
+/// Game `i` and its strategies use the seed `seed + i`, so the same seed gives the same ratings
+pub fn rate_match(
+    ratings: &mut EloRatings,
+    registry: &Registry,
+    rules: &RuleSet,
+    a: &str,
+    b: &str,
+    games: usize,
+    seed: u64,
+) -> Result<(), Error> {
+    for i in 0..games {
+        // Even games: `a` plays South/North, odd games: East/West
+        let (south_north, east_west) = if i % 2 == 0 { (a, b) } else { (b, a) };
+        let game_seed = seed.wrapping_add(i as u64);
+        let mut strategies = registry.team_strategies(south_north, east_west, rules, game_seed)?;
+        let mut game = Game::from_seed(rules.clone(), game_seed);
+        match run_game(&mut game, &mut strategies)? {
+            Team::SouthNorth => ratings.record_win(south_north, east_west),
+            Team::EastWest => ratings.record_win(east_west, south_north),
+        }
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_update_ratings() {
+        let mut ratings = EloRatings::default();
+        assert_eq!(ratings.rating("a"), INITIAL_RATING);
+        assert_eq!(ratings.expected_score("a", "b"), 0.5);
+
+        ratings.record_win("a", "b");
+        assert_eq!(ratings.rating("a"), 1516.0);
+        assert_eq!(ratings.rating("b"), 1484.0);
+        assert_eq!(ratings.games("a"), 1);
+        assert!(ratings.expected_score("a", "b") > 0.5);
+
+        // Beating a weaker opponent is worth less
+        ratings.record_win("a", "b");
+        assert!(ratings.rating("a") < 1532.0);
+        assert_eq!(ratings.leaderboard()[0].0, "a");
+    }
+
+    #[test]
+    fn heuristic_rates_higher_than_random() {
+        let mut ratings = EloRatings::default();
+        let registry = Registry::default();
+        rate_match(&mut ratings, &registry, &RuleSet::default(), "heuristic", "random", 10, 0).unwrap();
+        assert!(ratings.rating("heuristic") > ratings.rating("random"));
+        assert_eq!(ratings.games("random"), 10);
+        assert!(rate_match(&mut ratings, &registry, &RuleSet::default(), "heuristic", "nope", 1, 0).is_err());
+
+        let mut again = EloRatings::default();
+        rate_match(&mut again, &registry, &RuleSet::default(), "heuristic", "random", 10, 0).unwrap();
+        assert_eq!(again.rating("random"), ratings.rating("random"));
+    }
+}
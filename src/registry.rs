@@ -0,0 +1,165 @@
+//! Named rulesets and strategies, so servers can offer house variants and custom bots
+//! that are picked by a string id instead of patching the crate.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use failure::Error;
+
+use ai::{Budget, IsmctsBot};
+use bots::{HeuristicBot, RandomBot};
+use game::Game;
+use players::Player;
+use rules::{DealingMode, RuleSet, Variant};
+use strategy::Strategy;
+
+
+/// Creates a new instance of a strategy for a game played with the given rules.
+/// Strategies making random choices must only depend on the seed, so games can be replayed
+pub type StrategyFactory = Box<dyn Fn(&RuleSet, u64) -> Box<dyn Strategy>>;
+
+/// A collection of rulesets and strategies referenced by id.
+/// `Registry::default()` contains the built-in ones, `Registry::new()` is empty.
+pub struct Registry {
+    rulesets: HashMap<String, RuleSet>,
+    strategies: HashMap<String, StrategyFactory>,
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("rulesets", &self.ruleset_ids())
+            .field("strategies", &self.strategy_ids())
+            .finish()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        let mut registry = Registry::new();
+        registry.register_ruleset("contree", RuleSet::default()).unwrap();
+        registry
+            .register_ruleset("traditional", RuleSet { dealing_mode: DealingMode::CutOnly, ..RuleSet::default() })
+            .unwrap();
+        registry.register_ruleset("classic", RuleSet { variant: Variant::Classic, ..RuleSet::default() }).unwrap();
+        registry.register_strategy("random", |_, seed| Box::new(RandomBot::from_seed(seed))).unwrap();
+        registry.register_strategy("heuristic", |_, _| Box::new(HeuristicBot::new())).unwrap();
+        registry
+            .register_strategy("ismcts", |rules, seed| {
+                Box::new(IsmctsBot::from_seed(Budget::Iterations(1000), rules.clone(), seed))
+            })
+            .unwrap();
+        registry
+    }
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry { rulesets: HashMap::new(), strategies: HashMap::new() }
+    }
+
+    /// Errors if a ruleset with that id already exists
+    pub fn register_ruleset(&mut self, id: &str, rules: RuleSet) -> Result<(), Error> {
+        if self.rulesets.contains_key(id) {
+            bail!("A ruleset named {} is already registered", id);
+        }
+        self.rulesets.insert(id.to_string(), rules);
+        Ok(())
+    }
+
+    /// Errors if a strategy with that id already exists
+    pub fn register_strategy<F>(&mut self, id: &str, factory: F) -> Result<(), Error>
+        where F: Fn(&RuleSet, u64) -> Box<dyn Strategy> + 'static
+    {
+        if self.strategies.contains_key(id) {
+            bail!("A strategy named {} is already registered", id);
+        }
+        self.strategies.insert(id.to_string(), Box::new(factory));
+        Ok(())
+    }
+
+    pub fn ruleset(&self, id: &str) -> Option<&RuleSet> {
+        self.rulesets.get(id)
+    }
+
+    /// A new instance of the strategy, set up for a game with the given rules.
+    /// The same seed gives a strategy making the same choices
+    pub fn strategy(&self, id: &str, rules: &RuleSet, seed: u64) -> Result<Box<dyn Strategy>, Error> {
+        match self.strategies.get(id) {
+            Some(factory) => Ok(factory(rules, seed)),
+            None => bail!("Unknown strategy {}", id),
+        }
+    }
+
+    /// The strategies of a game between two teams, indexed by `Player::index`: both players
+    /// of a team use the same strategy, each with their own seed derived from `seed`
+    pub fn team_strategies(&self, south_north: &str, east_west: &str, rules: &RuleSet, seed: u64)
+        -> Result<[Box<dyn Strategy>; 4], Error>
+    {
+        let seed = |player: Player| seed.wrapping_mul(4).wrapping_add(player.index() as u64);
+        Ok([
+            self.strategy(south_north, rules, seed(Player::South))?,
+            self.strategy(east_west, rules, seed(Player::West))?,
+            self.strategy(south_north, rules, seed(Player::North))?,
+            self.strategy(east_west, rules, seed(Player::East))?,
+        ])
+    }
+
+    /// A new game using the ruleset with that id
+    pub fn new_game(&self, ruleset_id: &str) -> Result<Game, Error> {
+        match self.ruleset(ruleset_id) {
+            Some(rules) => Ok(Game::new(rules.clone())),
+            None => bail!("Unknown ruleset {}", ruleset_id),
+        }
+    }
+
+    /// Ids of all the rulesets, sorted
+    pub fn ruleset_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.rulesets.keys().map(|k| k.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Ids of all the strategies, sorted
+    pub fn strategy_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.strategies.keys().map(|k| k.as_str()).collect();
+        ids.sort();
+        ids
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rules::GameLength;
+    use strategy::run_game;
+
+    #[test]
+    fn has_builtins() {
+        let registry = Registry::default();
+        assert_eq!(registry.ruleset_ids(), vec!["classic", "contree", "traditional"]);
+        assert_eq!(registry.strategy_ids(), vec!["heuristic", "ismcts", "random"]);
+        assert!(registry.new_game("contree").is_ok());
+        assert!(registry.new_game("tarot").is_err());
+        assert!(registry.strategy("minimax", &RuleSet::default(), 0).is_err());
+        assert!(Registry::new().ruleset_ids().is_empty());
+    }
+
+    #[test]
+    fn can_register_house_variants() {
+        let mut registry = Registry::default();
+        let rules = RuleSet { game_length: GameLength::Deals(2), ..RuleSet::default() };
+        registry.register_ruleset("quick", rules.clone()).unwrap();
+        assert!(registry.register_ruleset("quick", rules).is_err());
+        registry.register_strategy("always-random", |_, _| Box::new(RandomBot::from_seed(0))).unwrap();
+        assert!(registry.register_strategy("random", |_, seed| Box::new(RandomBot::from_seed(seed))).is_err());
+
+        let mut game = registry.new_game("quick").unwrap();
+        let rules = game.rules().clone();
+        let mut strategies = registry.team_strategies("always-random", "heuristic", &rules, 0).unwrap();
+        let winner = run_game(&mut game, &mut strategies).unwrap();
+        assert_eq!(game.has_winner(), Some(winner));
+        assert!(game.score_sheet().lines.len() >= 2);
+    }
+}
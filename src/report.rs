@@ -0,0 +1,162 @@
+//! Markdown reports of tournament standings and leaderboards, so results of bots can be
+//! published as they are, eg in a README or a pull request.
+
+use std::io::Write;
+
+use failure::Error;
+
+use leaderboard::Leaderboard;
+use tournament::{Fixture, TournamentResult};
+
+
+/// Escapes the pipes that would end a table cell
+fn cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+fn write_table_row<W: Write>(writer: &mut W, cells: &[String]) -> Result<(), Error> {
+    writeln!(writer, "| {} |", cells.join(" | "))?;
+    Ok(())
+}
+
+/// The points of the winner and of the loser of the game
+fn fixture_scores(fixture: &Fixture) -> (usize, usize) {
+    let (south_north, east_west) = fixture.totals;
+    if fixture.winner == fixture.south_north { (south_north, east_west) } else { (east_west, south_north) }
+}
+
+/// Writes the standings of the tournament as a table, best first, followed by the winner
+/// and the game won by the most points
+pub fn write_tournament<W: Write>(writer: &mut W, title: &str, result: &TournamentResult) -> Result<(), Error> {
+    writeln!(writer, "# {}\n", title)?;
+    write_table_row(writer, &["Rank", "Entrant", "Played", "Won", "Points for", "Points against", "Difference"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect::<Vec<_>>())?;
+    writeln!(writer, "|-----:|---------|-------:|----:|-----------:|---------------:|-----------:|")?;
+    for (i, standing) in result.standings.iter().enumerate() {
+        let difference = standing.points_for as isize - standing.points_against as isize;
+        write_table_row(writer, &[
+            (i + 1).to_string(),
+            cell(&standing.id),
+            standing.played.to_string(),
+            standing.won.to_string(),
+            standing.points_for.to_string(),
+            standing.points_against.to_string(),
+            format!("{:+}", difference),
+        ])?;
+    }
+
+    let fixtures: Vec<&Fixture> = result.rounds.iter().flat_map(|r| r.iter()).collect();
+    writeln!(writer)?;
+    if let Some(winner) = result.winner() {
+        writeln!(writer, "Winner: **{}** ({} games played).", cell(winner), fixtures.len())?;
+    }
+    let biggest = fixtures.iter().max_by_key(|f| {
+        let (won, lost) = fixture_scores(f);
+        won.saturating_sub(lost)
+    });
+    if let Some(fixture) = biggest {
+        let (won, lost) = fixture_scores(fixture);
+        let loser = if fixture.winner == fixture.south_north { &fixture.east_west } else { &fixture.south_north };
+        writeln!(
+            writer,
+            "Biggest win: {} over {}, {} to {} (seed {}).",
+            cell(&fixture.winner), cell(loser), won, lost, fixture.seed,
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the ranking of the leaderboard as a table, best first, followed by the longest
+/// winning streak
+pub fn write_leaderboard<W: Write>(writer: &mut W, title: &str, leaderboard: &Leaderboard) -> Result<(), Error> {
+    let ranking = leaderboard.ranking();
+    writeln!(writer, "# {}\n", title)?;
+    write_table_row(writer, &["Rank", "Player", "Games", "Wins", "Win rate", "Average differential", "Best streak"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect::<Vec<_>>())?;
+    writeln!(writer, "|-----:|--------|------:|-----:|---------:|---------------------:|------------:|")?;
+    for (i, entry) in ranking.iter().enumerate() {
+        write_table_row(writer, &[
+            (i + 1).to_string(),
+            cell(&entry.player_id),
+            entry.games.to_string(),
+            entry.wins.to_string(),
+            format!("{:.1}%", entry.win_rate() * 100.0),
+            format!("{:+.1}", entry.average_differential()),
+            entry.best_streak.to_string(),
+        ])?;
+    }
+
+    // The best ranked player among those with the same streak
+    let longest = ranking.iter().map(|e| e.best_streak).max().unwrap_or(0);
+    if let Some(entry) = ranking.iter().find(|e| longest > 0 && e.best_streak == longest) {
+        writeln!(writer)?;
+        writeln!(writer, "Longest winning streak: {} ({}).", cell(&entry.player_id), longest)?;
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::heuristic_table;
+    use game::Game;
+    use strategy::run_game;
+    use tournament::TournamentStanding;
+
+    fn standing(id: &str, played: usize, won: usize, points_for: usize, points_against: usize) -> TournamentStanding {
+        TournamentStanding { id: id.to_string(), played, won, points_for, points_against }
+    }
+
+    #[test]
+    fn can_write_tournament() {
+        let fixture = |south_north: &str, east_west: &str, seed, winner: &str, totals| Fixture {
+            south_north: south_north.to_string(),
+            east_west: east_west.to_string(),
+            seed,
+            winner: winner.to_string(),
+            totals,
+        };
+        let result = TournamentResult {
+            rounds: vec![vec![fixture("a|b", "random", 0, "a|b", (1010, 600)), fixture("random", "a|b", 1, "a|b", (300, 1200))]],
+            standings: vec![standing("a|b", 2, 2, 2210, 900), standing("random", 2, 0, 900, 2210)],
+        };
+        let mut output = vec![];
+        write_tournament(&mut output, "Bots", &result).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\
+# Bots
+
+| Rank | Entrant | Played | Won | Points for | Points against | Difference |
+|-----:|---------|-------:|----:|-----------:|---------------:|-----------:|
+| 1 | a\\|b | 2 | 2 | 2210 | 900 | +1310 |
+| 2 | random | 2 | 0 | 900 | 2210 | -1310 |
+
+Winner: **a\\|b** (2 games played).
+Biggest win: a\\|b over random, 1200 to 300 (seed 1).
+");
+    }
+
+    #[test]
+    fn can_write_leaderboard() {
+        let mut leaderboard = Leaderboard::new();
+        let mut output = vec![];
+        write_leaderboard(&mut output, "Club", &leaderboard).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap().lines().count(), 4);
+
+        let mut game = Game::from_seed(Default::default(), 0);
+        run_game(&mut game, &mut heuristic_table()).unwrap();
+        leaderboard.record_game(["alice", "bob", "carol", "dave"], &game).unwrap();
+        let mut output = vec![];
+        write_leaderboard(&mut output, "Club", &leaderboard).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4 + 4 + 2);
+        let winner = leaderboard.ranking()[0].player_id.clone();
+        assert!(lines[4].starts_with(&format!("| 1 | {} | 1 | 1 | 100.0% | +", winner)));
+        assert_eq!(lines[9], format!("Longest winning streak: {} (1).", winner));
+    }
+}
@@ -1,32 +1,784 @@
 use std::collections::HashMap;
+use std::mem;
 
-use cards::Suit;
-use bids::Contract;
+use failure::Error;
+
+use announcements::{Announcement, AnnouncementError};
+use bids::{Bid, Contract};
+use card_set::CardSet;
+use cards::{Card, Rank};
+use errors::{ContreeError, Phase};
+use hand::Hand;
 use players::{Player, Team};
+use rules::{RevokePenalty, RuleSet, ValidationMode};
+use team_scores::TeamScores;
+use trick::Trick;
+use zobrist;
+
 
+/// Points of all the tricks, including the 10 points of the last trick
+pub const TOTAL_TRICK_POINTS: usize = 162;
+/// Points won by a team winning all the tricks
+pub const CAPOT_POINTS: usize = 250;
+/// Points of the King and Queen of trump
+pub const BELOTE_POINTS: usize = 20;
+/// Points the winning team gets on top of the contract value when the contract
+/// is failed or countered
+pub const CONTRACT_POINTS: usize = 160;
+/// Bonus points for winning the last trick
+pub const LAST_TRICK_POINTS: usize = 10;
+
+/// A card played against the rules, noticed after the fact
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub struct Revoke {
+    pub player: Player,
+    pub card: Card,
+}
 
 /// A round of the actual game, after a contract has been established
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Round {
     contract: Contract,
-    pub scores: HashMap<Team, usize>,
-    //hands: Vec<Vec<Cards>>,
+    pub scores: TeamScores,
+    /// Points won in tricks by each team, including the 10 points of the last trick
+    pub trick_points: TeamScores,
+    /// How many tricks each team won
+    pub tricks_won: TeamScores,
+    /// The team that announced the King and Queen of trump, if any
+    pub belote: Option<Team>,
+    /// The announcements made by the players during the round, checked against their hand
+    announcements: Vec<(Player, Announcement)>,
+    /// The revoke that decided the round, if someone was caught revoking
+    pub revoke: Option<Revoke>,
+    /// The cards still in the hands of each player
+    hands: HashMap<Player, Hand>,
+    /// The cards played in the current trick, in order
+    current_trick: Trick,
+    /// All the completed tricks, in order, with the player who won them
+    tricks: Vec<(Trick, Player)>,
+    /// The player who has to play the next card
+    next_player: Player,
+    /// Kept up to date as cards are played, see the `zobrist` module
+    zobrist: u64,
 }
 
 impl Round {
+    /// A round without any cards: the trick points need to be filled manually
+    #[cfg(test)]
     fn new(contract: Contract) -> Round {
-        let mut scores = HashMap::new();
-        scores.insert(Team::SouthNorth, 0);
-        scores.insert(Team::EastWest, 0);
+        let next_player = contract.player();
+        Round::with_hands(contract, next_player, HashMap::new())
+    }
 
-        Round {
+    /// A round to be played with the given hands, `first_player` leading the first trick.
+    /// The belote is counted for the team holding the King and Queen of trump
+    pub fn with_hands(contract: Contract, first_player: Player, hands: HashMap<Player, Vec<Card>>) -> Round {
+        let trump = contract.suit();
+        let mut hands: HashMap<Player, Hand> = hands.into_iter().map(|(p, h)| (p, Hand::new(h))).collect();
+        for player in Player::iterator() {
+            hands.entry(*player).or_default();
+        }
+        let belote = hands
+            .iter()
+            .find(|&(_, hand)| {
+                hand.contains(&Card::new(trump, Rank::King)) && hand.contains(&Card::new(trump, Rank::Queen))
+            })
+            .map(|(player, _)| player.team());
+
+        let mut round = Round {
             contract,
-            scores,
+            scores: TeamScores::default(),
+            trick_points: TeamScores::default(),
+            tricks_won: TeamScores::default(),
+            belote,
+            announcements: vec![],
+            revoke: None,
+            hands,
+            current_trick: Trick::new(),
+            tricks: vec![],
+            next_player: first_player,
+            zobrist: 0,
+        };
+        round.zobrist = zobrist::hash(&round);
+        round
+    }
+
+    pub fn contract(&self) -> &Contract {
+        &self.contract
+    }
+
+    /// The player who has to play the next card
+    pub fn next_player(&self) -> Player {
+        self.next_player
+    }
+
+    /// The cards the player still has in hand
+    pub fn hand(&self, player: Player) -> &Hand {
+        &self.hands[&player]
+    }
+
+    /// The announcements made so far, in order
+    pub fn announcements(&self) -> &[(Player, Announcement)] {
+        &self.announcements
+    }
+
+    /// Records an announcement after checking the player was dealt the cards it needs.
+    /// A card can only be part of one sequence of the player.
+    /// The belote is already counted for the team holding it so announcing it only checks it
+    pub fn announce(&mut self, player: Player, announcement: Announcement) -> Result<(), AnnouncementError> {
+        if self.is_over() {
+            return Err(AnnouncementError::RoundOver);
+        }
+        if self.announcements.contains(&(player, announcement)) {
+            return Err(AnnouncementError::AlreadyAnnounced);
+        }
+        let mut dealt = self.hand(player).to_vec();
+        let played = self.tricks.iter().flat_map(|(trick, _)| trick.iter()).chain(self.current_trick.iter());
+        dealt.extend(played.filter(|&&(p, _)| p == player).map(|&(_, c)| c));
+        let trump = self.contract.suit();
+        announcement.validate(&dealt, trump)?;
+
+        if let Announcement::Sequence { .. } = announcement {
+            let cards = announcement.cards(trump)?;
+            let earlier = self.announcements.iter().filter(|&&(p, a)| {
+                p == player && matches!(a, Announcement::Sequence { .. })
+            });
+            for &(_, other) in earlier {
+                if other.cards(trump)?.iter().any(|c| cards.contains(c)) {
+                    return Err(AnnouncementError::OverlapsAnnouncement(other));
+                }
+            }
+        }
+
+        if announcement != Announcement::Belote {
+            self.announcements.push((player, announcement));
+        }
+        Ok(())
+    }
+
+    /// Copies the announcements of another round of the same deal, eg a determinized copy
+    pub(crate) fn copy_announcements(&mut self, other: &Round) {
+        self.announcements = other.announcements.clone();
+    }
+
+    /// A 64-bit hash of the hands, the current trick and the player to act, updated
+    /// with each card played. Equal positions have equal hashes, whatever the order of
+    /// the cards in the tricks already played
+    pub fn zobrist_hash(&self) -> u64 {
+        self.zobrist
+    }
+
+    /// The cards played in the trick being played, in order
+    pub fn current_trick(&self) -> &Trick {
+        &self.current_trick
+    }
+
+    /// The completed tricks in order, each with the player who won it
+    pub fn tricks(&self) -> &[(Trick, Player)] {
+        &self.tricks
+    }
+
+    /// All the cards played so far, current trick included
+    pub fn played_cards(&self) -> CardSet {
+        self.tricks
+            .iter()
+            .flat_map(|(trick, _)| trick.iter())
+            .chain(self.current_trick.iter())
+            .map(|&(_, card)| card)
+            .collect()
+    }
+
+    /// Points of a completed trick, including the 10 points of the last trick
+    pub fn trick_points_of(&self, index: usize) -> Option<usize> {
+        let (trick, _) = self.tricks.get(index)?;
+        let points = trick.points(self.contract.suit());
+        Some(if index == 7 { points + LAST_TRICK_POINTS } else { points })
+    }
+
+    /// All 8 tricks have been played
+    pub fn is_over(&self) -> bool {
+        self.tricks.len() == 8
+    }
+
+    /// The cards of all tricks in the order they were won, to put the deck back together.
+    /// If the round was stopped early, the current trick and the hands come after them
+    pub(crate) fn gathered_tricks(&self) -> Vec<Vec<Card>> {
+        let mut gathered: Vec<Vec<Card>> = self.tricks
+            .iter()
+            .map(|(trick, _)| trick.iter().map(|&(_, card)| card).collect())
+            .collect();
+        if !self.is_over() {
+            gathered.push(self.current_trick.iter().map(|&(_, card)| card).collect());
+            gathered.extend(Player::iterator().map(|p| self.hand(*p).to_vec()));
+        }
+        gathered
+    }
+
+    /// Records that the player revoked with that card, ie played it while the rules didn't allow it,
+    /// which decides the round against their team. Such cards are only accepted in
+    /// `ValidationMode::Permissive`.
+    /// Errors if a revoke was already flagged, if they didn't play that card in this round
+    /// or if they were allowed to play it
+    pub fn flag_revoke(&mut self, player: Player, card: Card, rules: &RuleSet) -> Result<(), Error> {
+        if let Some(revoke) = self.revoke {
+            bail!("{:?} was already caught revoking in this round", revoke.player);
+        }
+        let played: Vec<(Player, Card)> = self.tricks
+            .iter()
+            .flat_map(|(trick, _)| trick.iter())
+            .chain(self.current_trick.iter())
+            .cloned()
+            .collect();
+        let position = match played.iter().position(|&(p, c)| p == player && c == card) {
+            Some(position) => position,
+            None => bail!("{:?} did not play the {} in this round", player, card),
+        };
+
+        // Replay the round up to that card to know what the player could play
+        let mut hands: HashMap<Player, Vec<Card>> = Player::iterator()
+            .map(|p| (*p, self.hand(*p).to_vec()))
+            .collect();
+        for &(p, c) in &played {
+            hands.get_mut(&p).unwrap().push(c);
+        }
+        let permissive = RuleSet { validation: ValidationMode::Permissive, ..rules.clone() };
+        let mut replay = Round::with_hands(self.contract.clone(), played[0].0, hands);
+        for &(p, c) in &played[..position] {
+            replay.play(p, c, &permissive)?;
+        }
+        if replay.playable_cards(player, rules).contains(&card) {
+            bail!("{:?} was allowed to play the {}", player, card);
+        }
+
+        self.revoke = Some(Revoke { player, card });
+        Ok(())
+    }
+
+    /// The cards the player is allowed to play right now, following the usual rules:
+    /// follow the suit led, otherwise trump if the opponents are winning the trick,
+    /// and always play a higher trump than the ones already played if possible.
+    pub fn playable_cards(&self, player: Player, rules: &RuleSet) -> Vec<Card> {
+        let hand = self.hand(player);
+        if self.current_trick.is_empty() {
+            return hand.to_vec();
+        }
+
+        let trump = self.contract.suit();
+        let lead = self.current_trick.lead_suit().unwrap();
+        let trumps = hand.trumps(trump);
+        // only keep the trumps higher than the ones already played, if there are any
+        let higher_trumps = || -> Vec<Card> {
+            let highest = self.current_trick
+                .iter()
+                .filter(|&&(_, c)| c.suit == trump)
+                .map(|&(_, c)| c.strength(trump))
+                .max();
+            let higher: Vec<Card> = match highest {
+                Some(highest) => trumps.iter().filter(|c| c.strength(trump) > highest).cloned().collect(),
+                None => trumps.clone(),
+            };
+            if higher.is_empty() { trumps.clone() } else { higher }
+        };
+
+        if lead == trump {
+            return if trumps.is_empty() { hand.to_vec() } else { higher_trumps() };
+        }
+
+        let following = hand.cards_of(lead);
+        if !following.is_empty() {
+            return following;
+        }
+        if trumps.is_empty() {
+            return hand.to_vec();
+        }
+
+        let partner_winning = self.current_trick.winner(trump).unwrap().team() == player.team();
+        if partner_winning && !rules.must_overtrump_partner {
+            return hand.to_vec();
+        }
+        higher_trumps()
+    }
+
+    /// Checks whether the player can play that card right now
+    pub(crate) fn check_play(&self, player: Player, card: Card, rules: &RuleSet) -> Result<(), ContreeError> {
+        if self.is_over() {
+            return Err(ContreeError::PhaseMismatch { expected: Phase::Playing });
+        }
+        if player != self.next_player {
+            return Err(ContreeError::WrongPlayer { expected: self.next_player, got: player });
+        }
+        let playable = self.playable_cards(player, rules);
+        if !self.hand(player).contains(&card) {
+            return Err(ContreeError::CardNotInHand { card, playable });
+        }
+        if !playable.contains(&card) {
+            // Only a card that doesn't follow or isn't a high enough trump can be refused
+            let trump = self.contract.suit();
+            let lead = self.current_trick.lead_suit().unwrap();
+            let error = if card.suit != lead && self.hand(player).has_suit(lead) {
+                ContreeError::MustFollowSuit { card, playable }
+            } else if card.suit != trump {
+                ContreeError::MustTrump { card, playable }
+            } else {
+                ContreeError::MustOvertrump { card, playable }
+            };
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Plays a card for the player and resolves the trick once everyone played.
+    /// In `ValidationMode::Permissive`, a card breaking the rules of the trick is played anyway
+    /// and can be flagged later with `flag_revoke`
+    pub fn play(&mut self, player: Player, card: Card, rules: &RuleSet) -> Result<(), Error> {
+        if let Err(e) = self.check_play(player, card, rules) {
+            let revoke = matches!(
+                e,
+                ContreeError::MustFollowSuit { .. } | ContreeError::MustTrump { .. } | ContreeError::MustOvertrump { .. }
+            );
+            if !revoke || rules.validation != ValidationMode::Permissive {
+                return Err(e.into());
+            }
+        }
+
+        self.hands.get_mut(&player).unwrap().remove(card)?;
+        self.current_trick.push(player, card);
+        self.zobrist ^= zobrist::hand_key(player, card) ^ zobrist::trick_key(player, card) ^ zobrist::turn_key(player);
+
+        if !self.current_trick.is_complete() {
+            self.next_player = player.next_player();
+            self.zobrist ^= zobrist::turn_key(self.next_player);
+            return Ok(());
+        }
+
+        let winner = self.current_trick.winner(self.contract.suit()).unwrap();
+        let trick = mem::take(&mut self.current_trick);
+        for &(p, c) in &trick {
+            self.zobrist ^= zobrist::trick_key(p, c);
+        }
+        self.tricks.push((trick, winner));
+        self.trick_points[winner.team()] += self.trick_points_of(self.tricks.len() - 1).unwrap();
+        self.tricks_won[winner.team()] += 1;
+        self.next_player = winner;
+        self.zobrist ^= zobrist::turn_key(winner);
+        Ok(())
+    }
+
+    /// Points from tricks, with a capot being worth more than all the tricks points
+    fn points_from_tricks(&self, team: Team) -> usize {
+        if self.tricks_won[team] == 8 {
+            CAPOT_POINTS
+        } else {
+            self.trick_points[team]
+        }
+    }
+
+    fn announcement_points(&self, team: Team, rules: &RuleSet) -> usize {
+        if !rules.count_announcements {
+            return 0;
+        }
+        self.announcements
+            .iter()
+            .filter(|&&(player, _)| player.team() == team)
+            .map(|&(_, announcement)| announcement.points())
+            .sum()
+    }
+
+    fn belote_points(&self, team: Team) -> usize {
+        if self.belote == Some(team) { BELOTE_POINTS } else { 0 }
+    }
+
+    /// Whether the team that took the contract fulfilled it
+    pub fn is_made(&self, rules: &RuleSet) -> bool {
+        let attack = self.contract.player().team();
+        if let Some(revoke) = self.revoke {
+            return revoke.player.team() != attack;
+        }
+        let points = self.points_from_tricks(attack)
+            + self.announcement_points(attack, rules)
+            + self.belote_points(attack);
+
+        match self.contract.bid() {
+            Some(Bid::Capot) => self.tricks_won[attack] == 8,
+            Some(bid) => points >= bid.value(),
+            // Classic belote: the taker needs more than half of the points
+            None => points > TOTAL_TRICK_POINTS / 2,
         }
     }
 
     /// Calculates the points for each team according to the contract
-    fn calculate_points(&mut self) {
+    pub(crate) fn calculate_points(&mut self, rules: &RuleSet) {
+        let attack = self.contract.player().team();
+        let defense = attack.opponent();
+        let made = self.is_made(rules);
+
+        let multiplier = if self.contract.double_countered() {
+            4
+        } else if self.contract.countered() {
+            2
+        } else {
+            1
+        };
+        let announcements_multiplier = if rules.counter_multiplies_announcements { multiplier } else { 1 };
+        let all_announcements = (self.announcement_points(attack, rules) + self.announcement_points(defense, rules))
+            * announcements_multiplier;
+        let bid_value = self.contract.value();
+
+        let (attack_score, defense_score) = if let Some(revoke) = self.revoke {
+            // The other team takes everything, as for a failed contract
+            let penalty = match rules.revoke_penalty {
+                RevokePenalty::ForfeitRound if self.contract.is_taken() => TOTAL_TRICK_POINTS + all_announcements,
+                RevokePenalty::ForfeitRound => CONTRACT_POINTS + bid_value * multiplier + all_announcements,
+                RevokePenalty::FixedPoints(points) => points,
+            };
+            if revoke.player.team() == attack { (0, penalty) } else { (penalty, 0) }
+        } else if made && multiplier == 1 {
+            (
+                bid_value + self.points_from_tricks(attack) + self.announcement_points(attack, rules),
+                self.points_from_tricks(defense) + self.announcement_points(defense, rules),
+            )
+        } else if made {
+            // When countered, the winning team takes everything
+            (CONTRACT_POINTS + bid_value * multiplier + all_announcements, 0)
+        } else if self.contract.is_taken() {
+            (0, TOTAL_TRICK_POINTS + all_announcements)
+        } else {
+            (0, CONTRACT_POINTS + bid_value * multiplier + all_announcements)
+        };
+
+        // The belote is always kept by the team that had it
+        self.scores[attack] = attack_score + self.belote_points(attack);
+        self.scores[defense] = defense_score + self.belote_points(defense);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::BidPhase;
+    use cards::{Rank, Suit};
+
+    fn round(bids: &[(Bid, Option<Suit>)], sn_points: usize, sn_tricks: usize) -> Round {
+        let mut bid_phase = BidPhase::new(Player::South);
+        let mut player = Player::South;
+        for &(bid, suit) in bids {
+            bid_phase.bid(player, bid, suit).unwrap();
+            player = player.next_player();
+        }
+        let mut round = Round::new(bid_phase.get_contract().unwrap());
+        round.trick_points[Team::SouthNorth] = sn_points;
+        round.trick_points[Team::EastWest] = TOTAL_TRICK_POINTS - sn_points;
+        round.tricks_won[Team::SouthNorth] = sn_tricks;
+        round.tricks_won[Team::EastWest] = 8 - sn_tricks;
+        round
+    }
+
+    fn hundred_hearts() -> Vec<(Bid, Option<Suit>)> {
+        vec![
+            (Bid::Hundred, Some(Suit::Hearts)),
+            (Bid::Pass, None),
+            (Bid::Pass, None),
+            (Bid::Pass, None),
+        ]
+    }
+
+    #[test]
+    fn can_score_made_contract() {
+        let mut round = round(&hundred_hearts(), 110, 5);
+        let rules = RuleSet::default();
+        assert!(round.is_made(&rules));
+        round.calculate_points(&rules);
+        assert_eq!(round.scores[Team::SouthNorth], 210);
+        assert_eq!(round.scores[Team::EastWest], 52);
+    }
+
+    #[test]
+    fn can_score_failed_contract() {
+        let mut round = round(&hundred_hearts(), 90, 4);
+        let rules = RuleSet::default();
+        assert!(!round.is_made(&rules));
+        round.calculate_points(&rules);
+        assert_eq!(round.scores[Team::SouthNorth], 0);
+        assert_eq!(round.scores[Team::EastWest], 260);
+    }
+
+    #[test]
+    fn belote_and_announcements_count_towards_contract() {
+        let mut round = round(&hundred_hearts(), 82, 4);
+        round.belote = Some(Team::SouthNorth);
+        let rules = RuleSet::default();
+        assert!(round.is_made(&rules));
+        round.calculate_points(&rules);
+        assert_eq!(round.scores[Team::SouthNorth], 202);
+        assert_eq!(round.scores[Team::EastWest], 80);
+
+        let mut round = round.clone();
+        round.belote = None;
+        round.announcements.push((Player::North, Announcement::Square(Rank::Queen)));
+        assert!(round.is_made(&rules));
+        assert!(!round.is_made(&RuleSet { count_announcements: false, ..RuleSet::default() }));
+    }
+
+    #[test]
+    fn announcements_are_checked() {
+        let rules = RuleSet::default();
+        let mut round = playing_round();
+        let tierce = Announcement::Sequence { suit: Suit::Hearts, highest: Rank::Jack, length: 3 };
+        assert_eq!(
+            round.announce(Player::South, tierce),
+            Err(AnnouncementError::MissingCard(card(Suit::Hearts, Rank::Nine)))
+        );
+        assert_eq!(
+            round.announce(Player::South, Announcement::Belote),
+            Err(AnnouncementError::MissingCard(card(Suit::Hearts, Rank::King)))
+        );
+        assert!(round.announcements().is_empty());
+
+        let mut hands = HashMap::new();
+        hands.insert(Player::West, vec![
+            card(Suit::Clubs, Rank::Ace), card(Suit::Clubs, Rank::King), card(Suit::Clubs, Rank::Queen),
+        ]);
+        let mut round = Round::with_hands(Contract::taken(Player::West, Suit::Hearts), Player::West, hands);
+        let tierce = Announcement::Sequence { suit: Suit::Clubs, highest: Rank::Ace, length: 3 };
+        round.play(Player::West, card(Suit::Clubs, Rank::Ace), &rules).unwrap();
+        // Cards already played still count
+        assert!(round.announce(Player::West, tierce).is_ok());
+        assert_eq!(round.announce(Player::West, tierce), Err(AnnouncementError::AlreadyAnnounced));
+        assert_eq!(round.announcements(), &[(Player::West, tierce)]);
+    }
+
+    #[test]
+    fn sequences_cannot_overlap() {
+        let hearts = || {
+            let mut hands = HashMap::new();
+            hands.insert(Player::West, Rank::iterator().map(|r| card(Suit::Hearts, *r)).collect());
+            Round::with_hands(Contract::taken(Player::West, Suit::Spades), Player::West, hands)
+        };
+        let mut round = hearts();
+        let sequence = |highest, length| Announcement::Sequence { suit: Suit::Hearts, highest, length };
+
+        // A tierce inside a quarte
+        round.announce(Player::West, sequence(Rank::Ace, 4)).unwrap();
+        assert_eq!(
+            round.announce(Player::West, sequence(Rank::King, 3)),
+            Err(AnnouncementError::OverlapsAnnouncement(sequence(Rank::Ace, 4)))
+        );
+        assert_eq!(
+            round.announce(Player::West, sequence(Rank::Ace, 8)),
+            Err(AnnouncementError::OverlapsAnnouncement(sequence(Rank::Ace, 4)))
+        );
+
+        // A-K-Q then K-Q-J
+        let mut round = hearts();
+        round.announce(Player::West, sequence(Rank::Ace, 3)).unwrap();
+        assert_eq!(
+            round.announce(Player::West, sequence(Rank::King, 3)),
+            Err(AnnouncementError::OverlapsAnnouncement(sequence(Rank::Ace, 3)))
+        );
+        // The rest of the suit is a separate sequence
+        assert!(round.announce(Player::West, sequence(Rank::Jack, 3)).is_ok());
+        assert_eq!(round.announcements().len(), 2);
+    }
+
+    #[test]
+    fn can_score_countered_contract() {
+        let bids = vec![
+            (Bid::Hundred, Some(Suit::Hearts)),
+            (Bid::Counter, None),
+            (Bid::Pass, None),
+            (Bid::Pass, None),
+            (Bid::Pass, None),
+        ];
+        let mut round = round(&bids, 110, 5);
+        round.belote = Some(Team::EastWest);
+        round.calculate_points(&RuleSet::default());
+        assert_eq!(round.scores[Team::SouthNorth], 360);
+        assert_eq!(round.scores[Team::EastWest], 20);
+    }
+
+    #[test]
+    fn can_score_capot() {
+        let bids = vec![
+            (Bid::Capot, Some(Suit::Hearts)),
+            (Bid::Pass, None),
+            (Bid::Pass, None),
+            (Bid::Pass, None),
+        ];
+        let mut round = round(&bids, 162, 8);
+        round.calculate_points(&RuleSet::default());
+        assert_eq!(round.scores[Team::SouthNorth], 500);
+        assert_eq!(round.scores[Team::EastWest], 0);
+
+        let mut round = round.clone();
+        round.trick_points[Team::SouthNorth] = 150;
+        round.tricks_won[Team::SouthNorth] = 7;
+        round.tricks_won[Team::EastWest] = 1;
+        round.calculate_points(&RuleSet::default());
+        assert_eq!(round.scores[Team::SouthNorth], 0);
+        assert_eq!(round.scores[Team::EastWest], 410);
+    }
+
+    #[test]
+    fn can_score_classic_take() {
+        let mut round = Round::new(Contract::taken(Player::West, Suit::Spades));
+        round.trick_points[Team::SouthNorth] = 81;
+        round.trick_points[Team::EastWest] = 81;
+        round.tricks_won[Team::SouthNorth] = 4;
+        round.tricks_won[Team::EastWest] = 4;
+        let rules = RuleSet::default();
+        assert!(!round.is_made(&rules));
+        round.calculate_points(&rules);
+        assert_eq!(round.scores[Team::EastWest], 0);
+        assert_eq!(round.scores[Team::SouthNorth], 162);
+    }
+
+    fn card(suit: Suit, rank: Rank) -> Card {
+        Card::new(suit, rank)
+    }
+
+    fn playing_round() -> Round {
+        let mut hands = HashMap::new();
+        hands.insert(Player::South, vec![
+            card(Suit::Hearts, Rank::Jack), card(Suit::Spades, Rank::Ace), card(Suit::Clubs, Rank::Seven),
+        ]);
+        hands.insert(Player::West, vec![
+            card(Suit::Spades, Rank::Seven), card(Suit::Hearts, Rank::Seven), card(Suit::Clubs, Rank::Ace),
+        ]);
+        hands.insert(Player::North, vec![
+            card(Suit::Diamonds, Rank::Ten), card(Suit::Hearts, Rank::Nine), card(Suit::Clubs, Rank::King),
+        ]);
+        hands.insert(Player::East, vec![
+            card(Suit::Hearts, Rank::Ace), card(Suit::Hearts, Rank::Eight), card(Suit::Diamonds, Rank::Ace),
+        ]);
+        Round::with_hands(Contract::taken(Player::South, Suit::Hearts), Player::South, hands)
+    }
+
+    #[test]
+    fn must_follow_suit_and_trump() {
+        let rules = RuleSet::default();
+        let mut round = playing_round();
+        assert!(round.play(Player::West, card(Suit::Spades, Rank::Seven), &rules).is_err());
+        assert!(round.play(Player::South, card(Suit::Spades, Rank::Seven), &rules).is_err());
+        assert!(round.play(Player::South, card(Suit::Spades, Rank::Ace), &rules).is_ok());
+        assert_eq!(round.playable_cards(Player::West, &rules), vec![card(Suit::Spades, Rank::Seven)]);
+        assert!(round.play(Player::West, card(Suit::Spades, Rank::Seven), &rules).is_ok());
+        // Partner is winning, North can play anything
+        assert_eq!(round.playable_cards(Player::North, &rules).len(), 3);
+        let strict = RuleSet { must_overtrump_partner: true, ..RuleSet::default() };
+        assert_eq!(round.playable_cards(Player::North, &strict), vec![card(Suit::Hearts, Rank::Nine)]);
+        assert!(round.play(Player::North, card(Suit::Diamonds, Rank::Ten), &rules).is_ok());
+        // East can't follow and opponents are winning: has to trump
+        assert_eq!(
+            round.playable_cards(Player::East, &rules),
+            vec![card(Suit::Hearts, Rank::Ace), card(Suit::Hearts, Rank::Eight)]
+        );
+        assert!(round.play(Player::East, card(Suit::Hearts, Rank::Eight), &rules).is_ok());
+        assert_eq!(round.tricks().len(), 1);
+        assert_eq!(round.next_player(), Player::East);
+        assert_eq!(round.trick_points[Team::EastWest], 21);
+    }
+
+    #[test]
+    fn can_read_the_trick_history() {
+        let rules = RuleSet::default();
+        let mut round = playing_round();
+        for &(player, c) in &[
+            (Player::South, card(Suit::Spades, Rank::Ace)),
+            (Player::West, card(Suit::Spades, Rank::Seven)),
+            (Player::North, card(Suit::Clubs, Rank::King)),
+        ] {
+            round.play(player, c, &rules).unwrap();
+        }
+        assert_eq!(round.current_trick().len(), 3);
+        assert!(round.tricks().is_empty());
+        assert_eq!(round.trick_points_of(0), None);
+
+        round.play(Player::East, card(Suit::Hearts, Rank::Eight), &rules).unwrap();
+        assert!(round.current_trick().is_empty());
+        let (ref trick, winner) = round.tricks()[0];
+        assert_eq!(trick[0], (Player::South, card(Suit::Spades, Rank::Ace)));
+        assert_eq!(trick.len(), 4);
+        assert_eq!(winner, Player::East);
+        assert_eq!(round.trick_points_of(0), Some(15));
+    }
+
+    #[test]
+    fn revoking_forfeits_the_round() {
+        let rules = RuleSet { validation: ValidationMode::Permissive, ..RuleSet::default() };
+        let mut round = playing_round();
+        round.play(Player::South, card(Suit::Spades, Rank::Ace), &rules).unwrap();
+        // West has a spade but the rules are only checked if someone notices
+        assert!(round.play(Player::West, card(Suit::Clubs, Rank::Ace), &RuleSet::default()).is_err());
+        round.play(Player::West, card(Suit::Clubs, Rank::Ace), &rules).unwrap();
+        round.play(Player::North, card(Suit::Clubs, Rank::King), &rules).unwrap();
+        assert!(round.flag_revoke(Player::West, card(Suit::Spades, Rank::Seven), &rules).is_err());
+        assert!(round.flag_revoke(Player::South, card(Suit::Spades, Rank::Ace), &rules).is_err());
+        // Partner of the winner, North could discard
+        assert!(round.flag_revoke(Player::North, card(Suit::Clubs, Rank::King), &rules).is_err());
+
+        round.flag_revoke(Player::West, card(Suit::Clubs, Rank::Ace), &rules).unwrap();
+        assert_eq!(round.revoke, Some(Revoke { player: Player::West, card: card(Suit::Clubs, Rank::Ace) }));
+        assert!(round.flag_revoke(Player::West, card(Suit::Clubs, Rank::Ace), &rules).is_err());
+        assert!(round.is_made(&rules));
+        round.calculate_points(&rules);
+        assert_eq!(round.scores, TeamScores::new(TOTAL_TRICK_POINTS, 0));
+
+        let fixed = RuleSet { revoke_penalty: RevokePenalty::FixedPoints(100), ..RuleSet::default() };
+        round.calculate_points(&fixed);
+        assert_eq!(round.scores, TeamScores::new(100, 0));
+        // The deck can be put back together even though the round stopped early
+        assert_eq!(round.gathered_tricks().iter().map(|cards| cards.len()).sum::<usize>(), 12);
+    }
+
+    #[test]
+    fn refused_cards_give_the_reason() {
+        let rules = RuleSet::default();
+        let mut round = playing_round();
+        let reason = |round: &mut Round, player, card| {
+            round.play(player, card, &rules).unwrap_err().downcast::<ContreeError>().unwrap()
+        };
+        assert_eq!(
+            reason(&mut round, Player::West, card(Suit::Spades, Rank::Seven)),
+            ContreeError::WrongPlayer { expected: Player::South, got: Player::West }
+        );
+        assert_eq!(
+            reason(&mut round, Player::South, card(Suit::Spades, Rank::Seven)),
+            ContreeError::CardNotInHand {
+                card: card(Suit::Spades, Rank::Seven),
+                playable: round.hand(Player::South).to_vec(),
+            }
+        );
+        round.play(Player::South, card(Suit::Spades, Rank::Ace), &rules).unwrap();
+        assert_eq!(
+            reason(&mut round, Player::West, card(Suit::Clubs, Rank::Ace)),
+            ContreeError::MustFollowSuit {
+                card: card(Suit::Clubs, Rank::Ace),
+                playable: vec![card(Suit::Spades, Rank::Seven)],
+            }
+        );
+        round.play(Player::West, card(Suit::Spades, Rank::Seven), &rules).unwrap();
+        round.play(Player::North, card(Suit::Diamonds, Rank::Ten), &rules).unwrap();
+        assert_eq!(
+            reason(&mut round, Player::East, card(Suit::Diamonds, Rank::Ace)),
+            ContreeError::MustTrump {
+                card: card(Suit::Diamonds, Rank::Ace),
+                playable: vec![card(Suit::Hearts, Rank::Ace), card(Suit::Hearts, Rank::Eight)],
+            }
+        );
+    }
 
+    #[test]
+    fn must_overtrump_when_trump_is_led() {
+        let rules = RuleSet::default();
+        let mut round = playing_round();
+        assert!(round.play(Player::South, card(Suit::Hearts, Rank::Jack), &rules).is_ok());
+        assert!(round.play(Player::West, card(Suit::Hearts, Rank::Seven), &rules).is_ok());
+        assert!(round.play(Player::North, card(Suit::Hearts, Rank::Nine), &rules).is_ok());
+        // East cannot go over the Jack but still has to play a trump
+        assert_eq!(
+            round.playable_cards(Player::East, &rules),
+            vec![card(Suit::Hearts, Rank::Ace), card(Suit::Hearts, Rank::Eight)]
+        );
     }
 }
@@ -0,0 +1,136 @@
+//! Matches of several games, eg "belote in two winning games": the first team to win
+//! the given number of games wins the match. The rotation of the first player carries on
+//! from one game to the next as if it were a single long game.
+
+use failure::Error;
+use rand;
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+use game::Game;
+use players::Team;
+use rules::RuleSet;
+use strategy::{run_game, Strategy};
+
+
+/// A match of several games between the two teams
+#[derive(Debug, Clone)]
+pub struct Match {
+    rules: RuleSet,
+    games_to_win: usize,
+    /// All the games of the match, the last one being the one in progress
+    games: Vec<Game>,
+    /// Where the seeds of the games come from
+    rng: Isaac64Rng,
+}
+
+impl Match {
+    /// A match won by the first team to win `games_to_win` games
+    pub fn new(rules: RuleSet, games_to_win: usize) -> Match {
+        Match::from_seed(rules, games_to_win, rand::random())
+    }
+
+    /// A match where every game is dealt the same way for the same seed
+    pub fn from_seed(rules: RuleSet, games_to_win: usize, seed: u64) -> Match {
+        let mut rng = Isaac64Rng::from_seed(&[seed]);
+        let first_game = Game::from_seed(rules.clone(), rng.gen());
+        Match { rules, games_to_win: games_to_win.max(1), games: vec![first_game], rng }
+    }
+
+    /// A match of at most `games` games, eg 3 for a match in two winning games
+    pub fn best_of(rules: RuleSet, games: usize) -> Match {
+        Match::new(rules, games / 2 + 1)
+    }
+
+    pub fn games_to_win(&self) -> usize {
+        self.games_to_win
+    }
+
+    /// The game in progress, or the last one played if the match is over
+    pub fn game(&self) -> &Game {
+        self.games.last().unwrap()
+    }
+
+    pub fn game_mut(&mut self) -> &mut Game {
+        self.games.last_mut().unwrap()
+    }
+
+    /// All the games of the match so far
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// How many games the team has won
+    pub fn games_won(&self, team: Team) -> usize {
+        self.games.iter().filter(|g| g.has_winner() == Some(team)).count()
+    }
+
+    /// The team that won the match, if it is over
+    pub fn winner(&self) -> Option<Team> {
+        [Team::SouthNorth, Team::EastWest].iter().find(|t| self.games_won(**t) >= self.games_to_win).cloned()
+    }
+
+    /// Starts the next game once the current one is over, the first player being the one after
+    /// the first player of the last round of the previous game.
+    /// Errors if the current game is not over or if the match is over
+    pub fn next_game(&mut self) -> Result<&mut Game, Error> {
+        if self.winner().is_some() {
+            bail!("The match is over");
+        }
+        if self.game().has_winner().is_none() {
+            bail!("The current game is not over");
+        }
+        let first_player = self.game().first_player().next_player();
+        let mut game = Game::from_seed(self.rules.clone(), self.rng.gen());
+        game.set_first_player(first_player)?;
+        self.games.push(game);
+        Ok(self.game_mut())
+    }
+}
+
+/// Plays the rest of the match with one strategy per player, indexed by `Player::index`,
+/// and returns the team that won it.
+/// Errors if a strategy tries to do something invalid
+pub fn run_match(game_match: &mut Match, strategies: &mut [Box<dyn Strategy>; 4]) -> Result<Team, Error> {
+    loop {
+        if let Some(team) = game_match.winner() {
+            return Ok(team);
+        }
+        if game_match.game().has_winner().is_some() {
+            game_match.next_game()?;
+        }
+        run_game(game_match.game_mut(), strategies)?;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::heuristic_table;
+
+    #[test]
+    fn can_play_a_match() {
+        let mut game_match = Match::from_seed(RuleSet::default(), 2, 0);
+        assert!(game_match.next_game().is_err());
+        let mut strategies = heuristic_table();
+
+        run_game(game_match.game_mut(), &mut strategies).unwrap();
+        // The rotation carries on in the next game
+        let expected = game_match.game().first_player().next_player();
+        assert_eq!(game_match.next_game().unwrap().first_player(), expected);
+
+        let winner = run_match(&mut game_match, &mut strategies).unwrap();
+        assert_eq!(game_match.winner(), Some(winner));
+        assert_eq!(game_match.games_won(winner), 2);
+        assert!(game_match.games_won(winner.opponent()) < 2);
+        assert_eq!(game_match.games().len(), 2);
+        assert!(game_match.next_game().is_err());
+    }
+
+    #[test]
+    fn best_of_needs_a_majority() {
+        assert_eq!(Match::best_of(RuleSet::default(), 3).games_to_win(), 2);
+        assert_eq!(Match::best_of(RuleSet::default(), 5).games_to_win(), 3);
+    }
+}
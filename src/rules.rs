@@ -0,0 +1,147 @@
+use cards::Suit;
+use evaluation::TrumpMode;
+use players::Player;
+
+
+/// How the deck is prepared between two rounds
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum DealingMode {
+    /// The deck is shuffled before every round
+    Shuffle,
+    /// The tricks of the previous round are gathered and the deck is only cut,
+    /// as it is done traditionally
+    CutOnly,
+}
+
+/// What to do with actions that break the rules
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum ValidationMode {
+    /// Invalid actions are rejected with an error
+    Strict,
+    /// Every action is recorded and the invalid ones are flagged as violations.
+    /// Cards not following the rules of the trick are played anyway and can be flagged
+    /// as revokes afterwards.
+    /// Useful to transcribe games played at a real table, mistakes included
+    Permissive,
+}
+
+/// An invalid action that was recorded in permissive mode
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Violation {
+    /// Index of the action in the phase it was recorded in
+    pub action_index: usize,
+    pub player: Player,
+    pub reason: String,
+}
+
+/// When does a game end
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum GameLength {
+    /// The first team to go over that score wins, usually 1000, 1500, 2000 or 3000
+    ScoreGoal(usize),
+    /// The game lasts exactly that many deals and the team with the highest score wins
+    Deals(usize),
+}
+
+/// How to decide the winner when both teams go over the score goal on the same deal,
+/// or finish a fixed number of deals with the same score
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum TieBreak {
+    /// The team with the highest total wins, another deal is played if they are equal
+    HighestTotal,
+    /// The team that took the contract of the last deal wins
+    AttackerPriority,
+    /// Another deal is always played
+    PlayAnotherDeal,
+}
+
+/// What a team that revoked, ie played a card the rules didn't allow, loses
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum RevokePenalty {
+    /// The other team scores as if the revoking team had failed a contract: 160 plus the value
+    /// of the contract multiplied by the counters, and all the announcements
+    ForfeitRound,
+    /// The other team scores that many points and the revoking team nothing
+    FixedPoints(usize),
+}
+
+/// Which game is played: how the trump is chosen
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum Variant {
+    /// The players bid for the contract, see `bids::BidPhase`
+    Contree,
+    /// Classic belote: 5 cards are dealt, a card is turned up and the players can take it
+    /// as trump, see `take::TakePhase`. The rest of the cards are dealt afterwards
+    Classic,
+}
+
+/// All the rules that vary from one table to another.
+/// The default is the most common set of rules for contrée.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RuleSet {
+    /// Whether the trump is bid as in contrée or taken as in classic belote
+    pub variant: Variant,
+    /// When the game ends
+    pub game_length: GameLength,
+    /// Who wins when both teams finish the game at the same time
+    pub tie_break: TieBreak,
+    /// Whether the score of each team is rounded to the nearest ten at the end of a round,
+    /// before being added to the total. Halves are rounded up
+    pub round_to_ten: bool,
+    /// Whether the deck is shuffled or only cut between rounds
+    pub dealing_mode: DealingMode,
+    /// Whether invalid actions are rejected or only flagged
+    pub validation: ValidationMode,
+    /// Whether announcements (belote, sequences, squares) are counted at all
+    pub count_announcements: bool,
+    /// Whether a counter/double counter also multiplies the points of the announcements
+    pub counter_multiplies_announcements: bool,
+    /// Whether a player has to play a higher trump than the ones already played if they can,
+    /// even if their partner is currently winning the trick
+    pub must_overtrump_partner: bool,
+    /// What happens to a team caught revoking, see `Game::flag_revoke`
+    pub revoke_penalty: RevokePenalty,
+    /// The kinds of trump that can be bid, the four suits by default. Only used in contrée,
+    /// any suit can be taken in classic belote.
+    /// Contracts are always played with a suit as trump: no-trump and all-trump
+    /// are not playable yet and listing them has no effect.
+    /// Without any suit, nobody can ever bid and the cards are dealt again forever
+    pub allowed_trumps: Vec<TrumpMode>,
+}
+
+impl RuleSet {
+    /// The suits that can be bid as trump, in `Suit::iterator` order
+    pub fn allowed_suits(&self) -> Vec<Suit> {
+        Suit::iterator()
+            .filter(|s| self.allowed_trumps.contains(&TrumpMode::Suit(**s)))
+            .cloned()
+            .collect()
+    }
+
+    /// The score of a team for a round, as it will be added to the total
+    pub fn round_score(&self, score: usize) -> usize {
+        if self.round_to_ten {
+            (score + 5) / 10 * 10
+        } else {
+            score
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> RuleSet {
+        RuleSet {
+            variant: Variant::Contree,
+            game_length: GameLength::ScoreGoal(1000),
+            tie_break: TieBreak::HighestTotal,
+            round_to_ten: false,
+            dealing_mode: DealingMode::Shuffle,
+            validation: ValidationMode::Strict,
+            count_announcements: true,
+            counter_multiplies_announcements: false,
+            must_overtrump_partner: false,
+            revoke_penalty: RevokePenalty::ForfeitRound,
+            allowed_trumps: Suit::iterator().map(|s| TrumpMode::Suit(*s)).collect(),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Random distributions of the cards a player can't see, consistent with everything
+//! that player knows: their own hand, the cards already played and the cards the other
+//! players have shown they don't have, see `constraints`.
+
+use std::collections::HashMap;
+
+use rand;
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+use bids::Contract;
+use cards::{Card, Suit};
+use constraints::Constraints;
+use players::Player;
+use round::Round;
+use rules::RuleSet;
+use view::PlayerView;
+
+
+/// How many times we try to deal the unseen cards before giving up
+const MAX_SAMPLING_ATTEMPTS: usize = 100;
+
+/// All the cards played in the round so far, in order
+pub(crate) fn played_cards(view: &PlayerView) -> Vec<(Player, Card)> {
+    view.tricks
+        .iter()
+        .flat_map(|(trick, _)| trick.iter().cloned())
+        .chain(view.current_trick.iter().cloned())
+        .collect()
+}
+
+/// The suits each player has shown they don't have anymore, see `Constraints`.
+/// Players without any known void are left out
+pub fn known_voids(view: &PlayerView) -> HashMap<Player, Vec<Suit>> {
+    let constraints = Constraints::from_view(view);
+    Player::iterator()
+        .map(|p| (*p, constraints.for_player(*p).voids()))
+        .filter(|(_, voids)| !voids.is_empty())
+        .collect()
+}
+
+/// Deals the cards the player can't see to the other players, respecting the constraints.
+/// The hands returned are the cards each player still holds, including the ones of the player.
+/// Returns `None` if no consistent deal was found.
+pub(crate) fn sample_hands<R: Rng>(view: &PlayerView, constraints: &Constraints, rng: &mut R) -> Option<HashMap<Player, Vec<Card>>> {
+    let played = played_cards(view);
+    let mut unknown = view.unseen_cards().to_vec();
+    // Kept in a `Vec` rather than a `HashMap` so the sampling only depends on the seed
+    let missing: Vec<(Player, usize)> = Player::iterator()
+        .filter(|p| **p != view.player)
+        .map(|p| (*p, 8 - played.iter().filter(|&&(player, _)| player == *p).count()))
+        .collect();
+
+    'attempts: for _ in 0..MAX_SAMPLING_ATTEMPTS {
+        rng.shuffle(&mut unknown);
+        let mut hands: HashMap<Player, Vec<Card>> = HashMap::new();
+        hands.insert(view.player, view.hand.to_vec());
+        let mut remaining = missing.clone();
+
+        for card in &unknown {
+            let candidates: Vec<usize> = (0..remaining.len())
+                .filter(|&i| {
+                    let (player, count) = remaining[i];
+                    count > 0 && constraints.can_hold(player, *card)
+                })
+                .collect();
+            match rng.choose(&candidates) {
+                Some(&i) => {
+                    remaining[i].1 -= 1;
+                    hands.entry(remaining[i].0).or_default().push(*card);
+                },
+                None => continue 'attempts,
+            }
+        }
+        return Some(hands);
+    }
+    None
+}
+
+/// Samples hands consistent with the view and replays the round so far to check every card
+/// played was legal with those hands, which catches more than the constraints alone.
+/// `leader` is the player who led the first trick.
+/// Returns `None` if no consistent hands were found.
+pub(crate) fn sample_round<R: Rng>(view: &PlayerView, contract: &Contract, leader: Player, rules: &RuleSet, rng: &mut R) -> Option<Round> {
+    let played = played_cards(view);
+    let constraints = Constraints::with_rules(view, rules);
+
+    for _ in 0..MAX_SAMPLING_ATTEMPTS {
+        let mut hands = sample_hands(view, &constraints, rng)?;
+        // Put the played cards back in the hands and replay them
+        for &(player, card) in &played {
+            hands.entry(player).or_default().push(card);
+        }
+        let mut round = Round::with_hands(contract.clone(), leader, hands);
+        if played.iter().all(|&(player, card)| round.play(player, card, rules).is_ok()) {
+            return Some(round);
+        }
+    }
+    None
+}
+
+/// Generates `n` random distributions of the cards unseen by the player of the view,
+/// consistent with their hand, the cards played and the `Constraints` they show.
+/// Each distribution contains the cards every player still holds.
+/// Returns fewer than `n` distributions only if no consistent one can be found.
+pub fn sample_consistent_deals(view: &PlayerView, n: usize) -> Vec<HashMap<Player, Vec<Card>>> {
+    sample_consistent_deals_from_seed(view, n, rand::random())
+}
+
+/// Same as `sample_consistent_deals` but always returns the same distributions for the same seed
+pub fn sample_consistent_deals_from_seed(view: &PlayerView, n: usize, seed: u64) -> Vec<HashMap<Player, Vec<Card>>> {
+    let mut rng = Isaac64Rng::from_seed(&[seed]);
+    let constraints = Constraints::from_view(view);
+    (0..n).map_while(|_| sample_hands(view, &constraints, &mut rng)).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::RandomBot;
+    use cards::Rank;
+    use game::Game;
+    use strategy::Strategy;
+
+    /// A view of South after a few tricks played by random bots, always the same deal
+    fn view_after_tricks(tricks: usize) -> PlayerView {
+        let mut game = Game::from_seed(RuleSet::default(), 3);
+        let mut bots: Vec<RandomBot> = (0..4).map(RandomBot::from_seed).collect();
+        game.new_round();
+        while game.player_view(Player::South).tricks.len() < tricks {
+            let player = game.current_player().unwrap();
+            let view = game.player_view(player);
+            if game.is_bidding() {
+                let (bid, suit) = bots[player.index()].choose_bid(&view);
+                game.bid(player, bid, suit).unwrap();
+            } else {
+                let card = bots[player.index()].choose_card(&view);
+                game.play(player, card).unwrap();
+            }
+        }
+        game.player_view(Player::South)
+    }
+
+    #[test]
+    fn can_sample_consistent_deals() {
+        let view = view_after_tricks(4);
+        let played = played_cards(&view);
+        let constraints = Constraints::from_view(&view);
+        let deals = sample_consistent_deals_from_seed(&view, 20, 7);
+        assert_eq!(deals.len(), 20);
+
+        for hands in &deals {
+            assert_eq!(hands[&Player::South], view.hand.to_vec());
+            let mut cards: Vec<Card> = hands.values().flat_map(|h| h.iter().cloned()).collect();
+            cards.extend(played.iter().map(|&(_, c)| c));
+            cards.sort_by_key(|c| (c.suit as u8, c.rank as u8));
+            cards.dedup();
+            assert_eq!(cards.len(), 32);
+
+            for (player, hand) in hands {
+                let played_by = played.iter().filter(|&&(p, _)| p == *player).count();
+                assert_eq!(hand.len() + played_by, 8);
+                assert!(hand.iter().all(|c| constraints.can_hold(*player, *c)));
+            }
+        }
+    }
+
+    #[test]
+    fn discarding_on_partner_is_not_a_trump_void() {
+        let mut view = view_after_tricks(0);
+        view.contract = Some(Contract::taken(Player::South, Suit::Hearts));
+        view.tricks.clear();
+        view.current_trick = vec![
+            (Player::West, Card::new(Suit::Spades, Rank::Ace)),
+            (Player::North, Card::new(Suit::Spades, Rank::Seven)),
+            (Player::East, Card::new(Suit::Clubs, Rank::Seven)),
+        ];
+        // East's partner is winning so East may still have hearts
+        assert_eq!(known_voids(&view)[&Player::East], vec![Suit::Spades]);
+    }
+
+    #[test]
+    fn same_seed_gives_same_deals() {
+        let view = view_after_tricks(2);
+        assert_eq!(
+            sample_consistent_deals_from_seed(&view, 5, 1),
+            sample_consistent_deals_from_seed(&view, 5, 1)
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+
+use announcements::Announcement;
+use bids::{Bid, Contract};
+use cards::Suit;
+use players::{Player, Team};
+use round::{Revoke, Round};
+use rules::RuleSet;
+use team_scores::TeamScores;
+
+
+/// A manual correction of the score of a team, eg after a ruling at the table
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct ScoreAdjustment {
+    pub team: Team,
+    /// Points added to (or removed from if negative) the total of the team
+    pub delta: isize,
+    pub reason: String,
+    /// Who decided the correction, eg the name of the referee
+    pub authority: String,
+    /// How many rounds had been played when the adjustment was made
+    pub after_round: usize,
+}
+
+/// One line of the score sheet, for a single round
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ScoreLine {
+    pub contract: Contract,
+    /// Whether the team that took the contract fulfilled it
+    pub made: bool,
+    /// Points won in tricks by each team
+    pub trick_points: TeamScores,
+    /// The team that had the King and Queen of trump, if any
+    pub belote: Option<Team>,
+    pub announcements: Vec<(Player, Announcement)>,
+    /// Who was caught revoking, which decided the round
+    pub revoke: Option<Revoke>,
+    /// Score of each team for that round, as added to the totals
+    pub scores: TeamScores,
+    /// Total score of each team after that round
+    pub totals: TeamScores,
+}
+
+/// The classic paper score sheet: one line per round with cumulative totals
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ScoreSheet {
+    pub lines: Vec<ScoreLine>,
+    pub adjustments: Vec<ScoreAdjustment>,
+}
+
+fn apply_adjustment(totals: &mut TeamScores, adjustment: &ScoreAdjustment) {
+    let total = &mut totals[adjustment.team];
+    *total = (*total as isize + adjustment.delta).max(0) as usize;
+}
+
+impl ScoreSheet {
+    /// Builds the score sheet. Totals include the adjustments made before each round
+    pub fn new(rounds: &[Round], adjustments: &[ScoreAdjustment], rules: &RuleSet) -> ScoreSheet {
+        let mut totals = TeamScores::default();
+
+        let mut lines = Vec::with_capacity(rounds.len());
+        for (i, round) in rounds.iter().enumerate() {
+            for adjustment in adjustments.iter().filter(|a| a.after_round == i) {
+                apply_adjustment(&mut totals, adjustment);
+            }
+
+            let scores = TeamScores::new(
+                rules.round_score(round.scores.south_north),
+                rules.round_score(round.scores.east_west),
+            );
+            totals += scores;
+
+            lines.push(ScoreLine {
+                contract: round.contract().clone(),
+                made: round.is_made(rules),
+                trick_points: round.trick_points,
+                belote: round.belote,
+                announcements: round.announcements().to_vec(),
+                revoke: round.revoke,
+                scores,
+                totals,
+            });
+        }
+
+        ScoreSheet { lines, adjustments: adjustments.to_vec() }
+    }
+
+    /// Total score of each team after the last round and all adjustments
+    pub fn totals(&self) -> TeamScores {
+        let mut totals = match self.lines.last() {
+            Some(line) => line.totals,
+            None => TeamScores::default(),
+        };
+        for adjustment in self.adjustments.iter().filter(|a| a.after_round >= self.lines.len()) {
+            apply_adjustment(&mut totals, adjustment);
+        }
+        totals
+    }
+}
+
+fn team_label(team: Team) -> &'static str {
+    match team {
+        Team::SouthNorth => "N/S",
+        Team::EastWest => "E/W",
+    }
+}
+
+fn player_label(player: Player) -> &'static str {
+    match player {
+        Player::South => "South",
+        Player::West => "West",
+        Player::North => "North",
+        Player::East => "East",
+    }
+}
+
+fn suit_label(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Spades => "Spades",
+        Suit::Hearts => "Hearts",
+        Suit::Diamonds => "Diamonds",
+        Suit::Clubs => "Clubs",
+    }
+}
+
+fn contract_label(contract: &Contract) -> String {
+    let bid = match contract.bid() {
+        Some(Bid::Capot) => "capot".to_string(),
+        Some(bid) => bid.value().to_string(),
+        None => "take".to_string(),
+    };
+    let counter = if contract.double_countered() {
+        " x4"
+    } else if contract.countered() {
+        " x2"
+    } else {
+        ""
+    };
+    format!("{} {} {}{}", player_label(contract.player()), bid, suit_label(contract.suit()), counter)
+}
+
+fn write_adjustment(f: &mut fmt::Formatter, adjustment: &ScoreAdjustment) -> fmt::Result {
+    writeln!(
+        f,
+        "    adjustment {} {:+} ({}, by {})",
+        team_label(adjustment.team),
+        adjustment.delta,
+        adjustment.reason,
+        adjustment.authority,
+    )
+}
+
+/// Renders the score sheet as fixed-width plain text, always using `\n` line endings
+/// so the output is identical on every platform
+impl fmt::Display for ScoreSheet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>3}  {:<24} {:<5} {:<6} {:>5} {:>5} {:>7} {:>7}",
+            "#", "Contract", "Made", "Belote", "N/S", "E/W", "Tot N/S", "Tot E/W",
+        )?;
+
+        for (i, line) in self.lines.iter().enumerate() {
+            for adjustment in self.adjustments.iter().filter(|a| a.after_round == i) {
+                write_adjustment(f, adjustment)?;
+            }
+            writeln!(
+                f,
+                "{:>3}  {:<24} {:<5} {:<6} {:>5} {:>5} {:>7} {:>7}",
+                i + 1,
+                contract_label(&line.contract),
+                if line.made { "yes" } else { "no" },
+                line.belote.map_or("-", team_label),
+                line.scores[Team::SouthNorth],
+                line.scores[Team::EastWest],
+                line.totals[Team::SouthNorth],
+                line.totals[Team::EastWest],
+            )?;
+        }
+        for adjustment in self.adjustments.iter().filter(|a| a.after_round >= self.lines.len()) {
+            write_adjustment(f, adjustment)?;
+        }
+
+        let totals = self.totals();
+        writeln!(
+            f,
+            "{:>3}  {:<24} {:<5} {:<6} {:>5} {:>5} {:>7} {:>7}",
+            "", "Total", "", "", "", "", totals[Team::SouthNorth], totals[Team::EastWest],
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::BidPhase;
+
+    fn contract(bids: &[(Player, Bid, Option<Suit>)]) -> Contract {
+        let mut bid_phase = BidPhase::new(bids[0].0);
+        for &(player, bid, suit) in bids {
+            bid_phase.bid(player, bid, suit).unwrap();
+        }
+        bid_phase.get_contract().unwrap()
+    }
+
+    fn line(contract: Contract, made: bool, belote: Option<Team>, scores: (usize, usize), totals: (usize, usize)) -> ScoreLine {
+        ScoreLine {
+            contract,
+            made,
+            trick_points: TeamScores::default(),
+            belote,
+            announcements: vec![],
+            revoke: None,
+            scores: TeamScores::new(scores.0, scores.1),
+            totals: TeamScores::new(totals.0, totals.1),
+        }
+    }
+
+    #[test]
+    fn can_print_score_sheet() {
+        let countered = contract(&[
+            (Player::West, Bid::HundredTen, Some(Suit::Spades)),
+            (Player::North, Bid::Counter, None),
+            (Player::East, Bid::Pass, None),
+            (Player::South, Bid::Pass, None),
+            (Player::West, Bid::Pass, None),
+        ]);
+        let double_countered = contract(&[
+            (Player::North, Bid::Capot, Some(Suit::Clubs)),
+            (Player::East, Bid::Counter, None),
+            (Player::South, Bid::DoubleCounter, None),
+        ]);
+        let sheet = ScoreSheet {
+            lines: vec![
+                line(Contract::taken(Player::South, Suit::Hearts), false, Some(Team::SouthNorth), (20, 162), (20, 162)),
+                line(countered, true, None, (0, 380), (20, 522)),
+                line(double_countered, true, None, (1160, 0), (1180, 522)),
+            ],
+            adjustments: vec![ScoreAdjustment {
+                team: Team::EastWest,
+                delta: -10,
+                reason: "revoke".to_string(),
+                authority: "referee".to_string(),
+                after_round: 1,
+            }],
+        };
+
+        let expected = concat!(
+            "  #  Contract                 Made  Belote   N/S   E/W Tot N/S Tot E/W\n",
+            "  1  South take Hearts        no    N/S       20   162      20     162\n",
+            "    adjustment E/W -10 (revoke, by referee)\n",
+            "  2  West 110 Spades x2       yes   -          0   380      20     522\n",
+            "  3  North capot Clubs x4     yes   -       1160     0    1180     522\n",
+            "     Total                                                1180     522\n",
+        );
+        assert_eq!(sheet.to_string(), expected);
+    }
+}
@@ -0,0 +1,399 @@
+//! A table where every seat has a secret session token and everything happening at the table
+//! is logged with a sequence number, so a client that lost its connection can come back with
+//! its token and get a snapshot of its view plus the events it missed.
+//! The log can only be appended to.
+
+use std::fmt;
+
+use failure::Error;
+use rand;
+use rand::Rng;
+
+use actions::Action;
+use game::{Game, Redeal, RedealReason};
+use players::{Player, PlayerInfo, Seat, Team};
+use score_sheet::ScoreAdjustment;
+use table_id::Uuid;
+use view::{PlayerView, SpectatorView};
+
+
+/// The secret a client uses to act for a seat and to resume it
+pub type SessionToken = Uuid;
+
+/// What happened at the table
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum EventKind {
+    /// A player made an action
+    Action { player: Player, action: Action },
+    /// The score of a team was corrected, see `Game::adjust_score`
+    ScoreAdjusted(ScoreAdjustment),
+    /// The cards were dealt again, after the action before it or because a player
+    /// claimed a misdeal
+    Redealt(Redeal),
+    /// Someone sat at the seat, see `Game::seat`
+    Joined(Seat, PlayerInfo),
+    /// Whoever sat at the seat left it
+    Left(Seat),
+}
+
+/// Something that happened at the table. Sequence numbers start at 1 and have no gaps
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Event {
+    pub seq: usize,
+    pub kind: EventKind,
+}
+
+impl Event {
+    /// Does again what the event recorded, eg to rebuild a game from a snapshot
+    pub fn apply_to(&self, game: &mut Game) -> Result<(), Error> {
+        match self.kind {
+            EventKind::Action { player, action } => game.apply(player, action),
+            EventKind::ScoreAdjusted(ref adjustment) => {
+                game.adjust_score(adjustment.team, adjustment.delta, &adjustment.reason, &adjustment.authority)
+            },
+            EventKind::Redealt(Redeal { reason: RedealReason::Misdeal { claimed_by, ref reason }, .. }) => {
+                game.claim_misdeal(claimed_by, reason)
+            },
+            // Already done by the action before it
+            EventKind::Redealt(_) => Ok(()),
+            EventKind::Joined(seat, ref info) => game.seat(seat, info.clone()),
+            EventKind::Left(seat) => {
+                game.leave(seat);
+                Ok(())
+            },
+        }
+    }
+}
+
+/// What a reconnecting client needs to catch up
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resume {
+    pub player: Player,
+    /// Who sits at the seat, if anyone joined it
+    pub info: Option<PlayerInfo>,
+    /// The current view of the seat
+    pub view: PlayerView,
+    /// The events after the sequence number the client asked for
+    pub events: Vec<Event>,
+    /// The sequence number of the last event, 0 if nothing happened yet
+    pub last_seq: usize,
+}
+
+/// A game with a session token per seat and a log of all the actions.
+/// Its `Debug` output leaves the tokens out
+#[derive(Clone)]
+pub struct Table {
+    game: Game,
+    /// Tokens indexed by `Player::index`
+    tokens: [SessionToken; 4],
+    events: Vec<Event>,
+    /// The game before the first event, to rebuild what spectators see
+    start: Game,
+}
+
+impl fmt::Debug for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Table")
+            .field("game", &self.game)
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+impl Table {
+    /// A table with tokens drawn from the random generator of the thread, itself seeded
+    /// by the operating system, so all their 122 random bits are unpredictable
+    pub fn new(game: Game) -> Table {
+        Table::with_rng(game, &mut rand::thread_rng())
+    }
+
+    /// A table whose tokens are always the same for the same seed
+    #[cfg(test)]
+    pub(crate) fn from_seed(game: Game, seed: u64) -> Table {
+        use rand::SeedableRng;
+        use rand::isaac::Isaac64Rng;
+        Table::with_rng(game, &mut Isaac64Rng::from_seed(&[seed]))
+    }
+
+    fn with_rng<R: Rng>(mut game: Game, rng: &mut R) -> Table {
+        let mut tokens = [Uuid([0; 16]); 4];
+        for token in &mut tokens {
+            *token = Uuid::random(rng);
+        }
+        if game.current_player().is_none() {
+            game.new_round();
+        }
+        Table { start: game.clone(), game, tokens, events: Vec::new() }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// The token to give to the client sitting at that seat
+    pub fn token(&self, player: Player) -> SessionToken {
+        self.tokens[player.index()]
+    }
+
+    /// The seat of the token, if it belongs to this table
+    pub fn player(&self, token: &SessionToken) -> Option<Player> {
+        Player::iterator().find(|p| self.tokens[p.index()] == *token).cloned()
+    }
+
+    /// The sequence number of the last event, 0 if nothing happened yet
+    pub fn last_seq(&self) -> usize {
+        self.events.len()
+    }
+
+    /// All the events after the sequence number `seq`
+    pub fn events_since(&self, seq: usize) -> &[Event] {
+        &self.events[seq.min(self.events.len())..]
+    }
+
+    /// The events spectators can see when the broadcast is `delay` actions behind the table,
+    /// so watching the game doesn't tell players what the others are doing in real time
+    pub fn spectator_events(&self, delay: usize) -> &[Event] {
+        &self.events[..self.events.len().saturating_sub(delay)]
+    }
+
+    /// What spectators see when the broadcast is `delay` actions behind the table,
+    /// see `spectator_events`. The hands of the last round are shown once it is over
+    /// if `reveal_last_round` is set
+    pub fn spectator_view(&self, delay: usize, reveal_last_round: bool) -> SpectatorView {
+        let mut game = self.start.clone();
+        for event in self.spectator_events(delay) {
+            // They were all applied to the same game already
+            event.apply_to(&mut game).unwrap();
+        }
+        game.spectator_view(reveal_last_round)
+    }
+
+    /// Makes the action for the seat of the token and returns the event logged.
+    /// If the cards are dealt again because of it, a `Redealt` event is logged after it.
+    /// Errors if the token is unknown or if the action is not allowed
+    pub fn apply(&mut self, token: &SessionToken, action: Action) -> Result<Event, Error> {
+        let player = match self.player(token) {
+            Some(p) => p,
+            None => bail!("Unknown session token"),
+        };
+        let redeals = self.game.redeals().len();
+        self.game.apply(player, action)?;
+        let event = self.log(EventKind::Action { player, action });
+        self.log_redeals(redeals);
+        Ok(event)
+    }
+
+    /// The seat of the token claims a misdeal, see `Game::claim_misdeal`, and the redeal is logged.
+    /// Errors if the token is unknown or if the cards can't be dealt again
+    pub fn claim_misdeal(&mut self, token: &SessionToken, reason: &str) -> Result<Event, Error> {
+        let player = match self.player(token) {
+            Some(p) => p,
+            None => bail!("Unknown session token"),
+        };
+        self.game.claim_misdeal(player, reason)?;
+        let redeal = self.game.redeals().last().unwrap().clone();
+        Ok(self.log(EventKind::Redealt(redeal)))
+    }
+
+    /// Sits someone at the seat and returns the token to give them.
+    /// Errors if they are already sitting at another seat
+    pub fn join(&mut self, seat: Seat, info: PlayerInfo) -> Result<SessionToken, Error> {
+        self.game.seat(seat, info.clone())?;
+        self.log(EventKind::Joined(seat, info));
+        Ok(self.token(seat.player()))
+    }
+
+    /// The seat of the token is freed, returning who was sitting there.
+    /// The token still works: it belongs to the seat, not to the person
+    pub fn leave(&mut self, token: &SessionToken) -> Result<Option<PlayerInfo>, Error> {
+        let seat = match self.player(token) {
+            Some(p) => Seat::of(p),
+            None => bail!("Unknown session token"),
+        };
+        let info = self.game.leave(seat);
+        if info.is_some() {
+            self.log(EventKind::Left(seat));
+        }
+        Ok(info)
+    }
+
+    /// Corrects the score of a team, see `Game::adjust_score`, and logs it.
+    /// Only the table can do it, not the players
+    pub fn adjust_score(&mut self, team: Team, delta: isize, reason: &str, authority: &str) -> Result<Event, Error> {
+        self.game.adjust_score(team, delta, reason, authority)?;
+        let adjustment = self.game.adjustments().last().unwrap().clone();
+        Ok(self.log(EventKind::ScoreAdjusted(adjustment)))
+    }
+
+    fn log_redeals(&mut self, from: usize) {
+        let redeals = self.game.redeals()[from..].to_vec();
+        for redeal in redeals {
+            self.log(EventKind::Redealt(redeal));
+        }
+    }
+
+    fn log(&mut self, kind: EventKind) -> Event {
+        let event = Event { seq: self.events.len() + 1, kind };
+        self.events.push(event.clone());
+        event
+    }
+
+    /// Everything a client that has seen the events up to `seq` needs to catch up.
+    /// Use 0 to get the full history.
+    /// Errors if the token is unknown
+    pub fn resume(&self, token: &SessionToken, seq: usize) -> Result<Resume, Error> {
+        let player = match self.player(token) {
+            Some(p) => p,
+            None => bail!("Unknown session token"),
+        };
+        Ok(Resume {
+            player,
+            info: self.game.player_info(Seat::of(player)).cloned(),
+            view: self.game.player_view(player),
+            events: self.events_since(seq).to_vec(),
+            last_seq: self.last_seq(),
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Bid;
+    use team_scores::TeamScores;
+
+    #[test]
+    fn can_resume_a_seat() {
+        let mut table = Table::from_seed(Game::from_seed(Default::default(), 0), 0);
+        let south = table.token(Player::South);
+        assert_ne!(south, table.token(Player::North));
+        assert_eq!(table.player(&south), Some(Player::South));
+        assert!(table.apply(&Uuid([0; 16]), Action::Bid(Bid::Pass, None)).is_err());
+        // Not the turn of West
+        assert!(table.apply(&table.token(Player::West), Action::Bid(Bid::Pass, None)).is_err());
+
+        for player in &[Player::South, Player::West, Player::North] {
+            let token = table.token(*player);
+            let event = table.apply(&token, Action::Bid(Bid::Pass, None)).unwrap();
+            assert_eq!(event.kind, EventKind::Action { player: *player, action: Action::Bid(Bid::Pass, None) });
+        }
+        assert_eq!(table.last_seq(), 3);
+
+        let resume = table.resume(&south, 1).unwrap();
+        assert_eq!(resume.player, Player::South);
+        assert_eq!(resume.view, table.game().player_view(Player::South));
+        assert_eq!(resume.events.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(resume.last_seq, 3);
+        assert_eq!(table.resume(&south, 0).unwrap().events.len(), 3);
+        assert!(table.resume(&south, 10).unwrap().events.is_empty());
+
+        assert_eq!(table.spectator_events(0).len(), 3);
+        assert_eq!(table.spectator_events(2), &table.events_since(0)[..1]);
+        assert!(table.spectator_events(5).is_empty());
+    }
+
+    #[test]
+    fn spectators_are_behind_the_table() {
+        let mut table = Table::from_seed(Game::from_seed(Default::default(), 0), 0);
+        for _ in 0..6 {
+            let player = table.game().current_player().unwrap();
+            let hint = table.game().hint(player).unwrap();
+            table.apply(&table.token(player), hint.action).unwrap();
+        }
+        let bids = table.game().player_view(Player::South).bids;
+        assert_eq!(table.spectator_view(0, false).bids, bids);
+        assert_eq!(table.spectator_view(2, false).bids, bids[..bids.len() - 2].to_vec());
+        assert!(table.spectator_view(10, false).bids.is_empty());
+    }
+
+    #[test]
+    fn redeals_are_logged() {
+        let mut table = Table::from_seed(Game::from_seed(Default::default(), 0), 0);
+        let snapshot = table.game().clone();
+        assert!(table.claim_misdeal(&Uuid([0; 16]), "nope").is_err());
+        let event = table.claim_misdeal(&table.token(Player::North), "East has 9 cards").unwrap();
+        assert_eq!(event.seq, 1);
+        for player in &[Player::South, Player::West, Player::North, Player::East] {
+            table.apply(&table.token(*player), Action::Bid(Bid::Pass, None)).unwrap();
+        }
+        assert_eq!(table.last_seq(), 6);
+        let reasons: Vec<RedealReason> = table.events_since(0).iter().filter_map(|e| match e.kind {
+            EventKind::Redealt(ref redeal) => Some(redeal.reason.clone()),
+            _ => None,
+        }).collect();
+        assert_eq!(reasons, vec![
+            RedealReason::Misdeal { claimed_by: Player::North, reason: "East has 9 cards".to_string() },
+            RedealReason::AllPassed,
+        ]);
+
+        let mut game = snapshot;
+        for event in table.events_since(0) {
+            event.apply_to(&mut game).unwrap();
+        }
+        assert_eq!(game.redeals(), table.game().redeals());
+        assert_eq!(game.player_view(Player::West), table.game().player_view(Player::West));
+    }
+
+    #[test]
+    fn players_can_join_and_leave() {
+        let mut table = Table::from_seed(Game::from_seed(Default::default(), 0), 0);
+        let snapshot = table.game().clone();
+        let token = table.join(Seat::West, PlayerInfo::new("42", "Alice")).unwrap();
+        assert_eq!(table.player(&token), Some(Player::West));
+        assert!(table.join(Seat::East, PlayerInfo::new("42", "Alice")).is_err());
+        assert_eq!(table.resume(&token, 0).unwrap().info, Some(PlayerInfo::new("42", "Alice")));
+        assert!(table.resume(&table.token(Player::South), 0).unwrap().info.is_none());
+
+        let mut game = snapshot.clone();
+        for event in table.events_since(0) {
+            event.apply_to(&mut game).unwrap();
+        }
+        assert_eq!(game.seat_of("42"), Some(Seat::West));
+
+        assert_eq!(table.leave(&token).unwrap().unwrap().name, "Alice");
+        assert!(table.leave(&token).unwrap().is_none());
+        assert_eq!(table.last_seq(), 2);
+        let mut game = snapshot;
+        for event in table.events_since(0) {
+            event.apply_to(&mut game).unwrap();
+        }
+        assert_eq!(game.seat_of("42"), None);
+    }
+
+    #[test]
+    fn tokens_are_random_and_hidden() {
+        let first = Table::new(Game::from_seed(Default::default(), 0));
+        let second = Table::new(Game::from_seed(Default::default(), 0));
+        assert_ne!(first.token(Player::South), second.token(Player::South));
+        let debug = format!("{:?}", first);
+        for player in Player::iterator() {
+            assert!(!debug.contains(&format!("{:?}", first.token(*player).0)));
+        }
+    }
+
+    #[test]
+    fn score_adjustments_are_logged() {
+        let mut table = Table::from_seed(Game::from_seed(Default::default(), 0), 0);
+        let snapshot = table.game().clone();
+        table.apply(&table.token(Player::South), Action::Bid(Bid::Pass, None)).unwrap();
+        assert!(table.adjust_score(Team::EastWest, 0, "nothing", "referee").is_err());
+        let event = table.adjust_score(Team::EastWest, 50, "slow play", "referee").unwrap();
+        assert_eq!(event.seq, 2);
+        match event.kind {
+            EventKind::ScoreAdjusted(ref adjustment) => {
+                assert_eq!(adjustment.delta, 50);
+                assert_eq!(adjustment.authority, "referee");
+            },
+            _ => panic!("Expected a score adjustment"),
+        }
+        assert_eq!(table.last_seq(), 2);
+
+        let mut game = snapshot;
+        for event in table.events_since(0) {
+            event.apply_to(&mut game).unwrap();
+        }
+        assert_eq!(game.adjustments(), table.game().adjustments());
+        assert_eq!(game.totals(), TeamScores::new(0, 50));
+    }
+}
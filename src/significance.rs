@@ -0,0 +1,220 @@
+//! Basic statistics helpers to compare results of games between strategies
+//! without mistaking noise for an actual improvement.
+
+use failure::Error;
+
+
+/// z-score for a 95% confidence level
+pub const Z_95: f64 = 1.959_963_985;
+
+/// A confidence interval around a proportion, for example a win rate
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ConfidenceInterval {
+    pub estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+}
+
+/// Wilson score interval for `successes` out of `trials`, eg games won out of games played.
+/// Use `Z_95` as `z` for the usual 95% confidence level.
+/// The Wilson interval behaves well even with few trials or rates close to 0 or 1.
+pub fn binomial_confidence_interval(successes: usize, trials: usize, z: f64) -> Result<ConfidenceInterval, Error> {
+    if trials == 0 {
+        bail!("Cannot compute a confidence interval without any trials");
+    }
+    if successes > trials {
+        bail!("There cannot be more successes than trials");
+    }
+
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+
+    Ok(ConfidenceInterval {
+        estimate: p,
+        lower: (center - margin).max(0.0),
+        upper: (center + margin).min(1.0),
+    })
+}
+
+/// Result of a paired t-test
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TTest {
+    /// Average of the differences `a - b`
+    pub mean_difference: f64,
+    pub t: f64,
+    pub degrees_of_freedom: usize,
+    /// Two-sided p-value: the probability of seeing such a difference if both were equal
+    pub p_value: f64,
+}
+
+impl TTest {
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+/// Paired t-test between two series of results on the same deals,
+/// eg the score of strategy A and strategy B when playing the same mirrored deals.
+/// Pairing removes most of the luck of the deal from the comparison.
+pub fn paired_t_test(a: &[f64], b: &[f64]) -> Result<TTest, Error> {
+    if a.len() != b.len() {
+        bail!("Both series of results must have the same length");
+    }
+    if a.len() < 2 {
+        bail!("A paired t-test needs at least 2 pairs of results");
+    }
+
+    let n = a.len() as f64;
+    let differences: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x - y).collect();
+    let mean = differences.iter().sum::<f64>() / n;
+    let variance = differences.iter().map(|d| (d - mean) * (d - mean)).sum::<f64>() / (n - 1.0);
+    let degrees_of_freedom = a.len() - 1;
+
+    if variance == 0.0 {
+        // Every pair has the exact same difference
+        let p_value = if mean == 0.0 { 1.0 } else { 0.0 };
+        let t = if mean == 0.0 { 0.0 } else { mean.signum() * f64::INFINITY };
+        return Ok(TTest { mean_difference: mean, t, degrees_of_freedom, p_value });
+    }
+
+    let t = mean / (variance / n).sqrt();
+    let df = degrees_of_freedom as f64;
+    let p_value = incomplete_beta(df / 2.0, 0.5, df / (df + t * t));
+
+    Ok(TTest { mean_difference: mean, t, degrees_of_freedom, p_value })
+}
+
+/// ln(Γ(x)) using the Lanczos approximation
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46, -86.505_320_329_416_77, 24.014_098_240_830_91,
+        -1.231_739_572_450_155, 0.001_208_650_973_866_179, -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000_000_000_190_015;
+    for (i, c) in COEFFICIENTS.iter().enumerate() {
+        series += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Continued fraction for the incomplete beta function
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-12;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..300 {
+        let m = f64::from(m);
+        let m2 = 2.0 * m;
+        let aa = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        let aa = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn can_compute_wilson_interval() {
+        let ci = binomial_confidence_interval(55, 100, Z_95).unwrap();
+        assert_close(ci.estimate, 0.55);
+        assert_close(ci.lower, 0.4524);
+        assert_close(ci.upper, 0.6438);
+        assert!(ci.contains(0.5));
+
+        let ci = binomial_confidence_interval(0, 10, Z_95).unwrap();
+        assert_close(ci.lower, 0.0);
+        assert!(ci.upper > 0.0);
+
+        assert!(binomial_confidence_interval(0, 0, Z_95).is_err());
+        assert!(binomial_confidence_interval(11, 10, Z_95).is_err());
+    }
+
+    #[test]
+    fn can_run_paired_t_test() {
+        let a = [72.0, 81.0, 90.0, 65.0, 100.0, 88.0, 79.0, 95.0];
+        let b = [70.0, 75.0, 91.0, 60.0, 92.0, 85.0, 80.0, 90.0];
+        let test = paired_t_test(&a, &b).unwrap();
+        assert_close(test.mean_difference, 3.375);
+        assert_eq!(test.degrees_of_freedom, 7);
+        assert_close(test.t, 2.9385);
+        assert_close(test.p_value, 0.0218);
+        assert!(test.is_significant(0.05));
+        assert!(!test.is_significant(0.01));
+    }
+
+    #[test]
+    fn paired_t_test_handles_edge_cases() {
+        assert!(paired_t_test(&[1.0], &[2.0]).is_err());
+        assert!(paired_t_test(&[1.0, 2.0], &[2.0]).is_err());
+        let test = paired_t_test(&[1.0, 2.0], &[1.0, 2.0]).unwrap();
+        assert_close(test.p_value, 1.0);
+    }
+}
@@ -0,0 +1,232 @@
+//! Plays many games between strategies and aggregates the results,
+//! to compare bots or rule settings.
+
+use std::collections::HashMap;
+use std::thread;
+
+use failure::Error;
+
+use game::Game;
+use players::{Player, Team};
+use rules::RuleSet;
+use team_scores::TeamScores;
+use strategy::{run_game, Strategy};
+
+
+/// Contracts of one seat over all the games
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct SeatStats {
+    /// How many contracts the player of that seat won the bidding with
+    pub contracts_taken: usize,
+    /// How many of those contracts were made
+    pub contracts_made: usize,
+}
+
+impl SeatStats {
+    /// Share of the contracts taken that were made, `None` if no contract was taken
+    pub fn success_rate(&self) -> Option<f64> {
+        if self.contracts_taken == 0 {
+            return None;
+        }
+        Some(self.contracts_made as f64 / self.contracts_taken as f64)
+    }
+}
+
+/// Aggregated results of a simulation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationStats {
+    pub games: usize,
+    /// Games won by each team
+    pub wins: HashMap<Team, usize>,
+    /// Average final total of each team
+    pub average_scores: HashMap<Team, f64>,
+    /// Stats of each seat, indexed by `Player::index`
+    pub seats: [SeatStats; 4],
+}
+
+impl SimulationStats {
+    fn empty() -> SimulationStats {
+        let mut wins = HashMap::new();
+        let mut average_scores = HashMap::new();
+        for team in &[Team::SouthNorth, Team::EastWest] {
+            wins.insert(*team, 0);
+            average_scores.insert(*team, 0.0);
+        }
+        SimulationStats { games: 0, wins, average_scores, seats: [SeatStats::default(); 4] }
+    }
+
+    /// Adds the results of another simulation to this one
+    fn merge(&mut self, other: &SimulationStats) {
+        let games = self.games + other.games;
+        if games == 0 {
+            return;
+        }
+        for (team, wins) in &other.wins {
+            *self.wins.get_mut(team).unwrap() += *wins;
+        }
+        for (team, average) in &other.average_scores {
+            let current = self.average_scores[team];
+            let merged = (current * self.games as f64 + average * other.games as f64) / games as f64;
+            self.average_scores.insert(*team, merged);
+        }
+        for (seat, other_seat) in self.seats.iter_mut().zip(other.seats.iter()) {
+            seat.contracts_taken += other_seat.contracts_taken;
+            seat.contracts_made += other_seat.contracts_made;
+        }
+        self.games = games;
+    }
+
+    /// Share of the games won by the team
+    pub fn win_rate(&self, team: Team) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins[&team] as f64 / self.games as f64
+    }
+
+    /// Share of all the contracts taken that were made, `None` if no contract was taken
+    pub fn contract_success_rate(&self) -> Option<f64> {
+        let total = self.seats.iter().fold(SeatStats::default(), |acc, s| SeatStats {
+            contracts_taken: acc.contracts_taken + s.contracts_taken,
+            contracts_made: acc.contracts_made + s.contracts_made,
+        });
+        total.success_rate()
+    }
+
+    pub fn seat(&self, player: Player) -> &SeatStats {
+        &self.seats[player.index()]
+    }
+}
+
+/// Plays `n_games` games with the default rules between the strategies, indexed by
+/// `Player::index`. Game `i` uses the seed `seed + i` so a simulation can be replayed.
+/// Errors if a strategy tries to do something invalid.
+pub fn simulate(strategies: &mut [Box<dyn Strategy>; 4], n_games: usize, seed: u64) -> Result<SimulationStats, Error> {
+    simulate_with_rules(RuleSet::default(), strategies, n_games, seed)
+}
+
+/// Same as `simulate` with other rules
+pub fn simulate_with_rules(
+    rules: RuleSet,
+    strategies: &mut [Box<dyn Strategy>; 4],
+    n_games: usize,
+    seed: u64,
+) -> Result<SimulationStats, Error> {
+    let seeds: Vec<u64> = (0..n_games).map(|i| seed.wrapping_add(i as u64)).collect();
+    play_games(&rules, strategies, &seeds)
+}
+
+/// Same as `simulate_with_rules` but the games are spread over `threads` threads.
+/// Each thread gets its own strategies from `new_strategies`, so the results only match
+/// the ones of `simulate_with_rules` for strategies that don't carry anything from a game to the next
+pub fn simulate_parallel<F>(
+    rules: RuleSet,
+    new_strategies: F,
+    n_games: usize,
+    seed: u64,
+    threads: usize,
+) -> Result<SimulationStats, Error>
+    where F: Fn() -> [Box<dyn Strategy>; 4] + Sync
+{
+    let threads = threads.max(1);
+    let results: Vec<Result<SimulationStats, String>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|t| {
+                let rules = &rules;
+                let new_strategies = &new_strategies;
+                scope.spawn(move || {
+                    let seeds: Vec<u64> = (t..n_games).step_by(threads).map(|i| seed.wrapping_add(i as u64)).collect();
+                    // failure errors are not `Send` so only the message crosses threads
+                    play_games(rules, &mut new_strategies(), &seeds).map_err(|e| e.to_string())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut stats = SimulationStats::empty();
+    for result in results {
+        match result {
+            Ok(s) => stats.merge(&s),
+            Err(e) => bail!(e),
+        }
+    }
+    Ok(stats)
+}
+
+fn play_games(rules: &RuleSet, strategies: &mut [Box<dyn Strategy>; 4], seeds: &[u64]) -> Result<SimulationStats, Error> {
+    let mut stats = SimulationStats::empty();
+    let mut totals = TeamScores::default();
+
+    for seed in seeds {
+        let mut game = Game::from_seed(rules.clone(), *seed);
+        let winner = run_game(&mut game, strategies)?;
+        *stats.wins.get_mut(&winner).unwrap() += 1;
+
+        let sheet = game.score_sheet();
+        totals += sheet.totals();
+        for line in &sheet.lines {
+            let seat = &mut stats.seats[line.contract.player().index()];
+            seat.contracts_taken += 1;
+            if line.made {
+                seat.contracts_made += 1;
+            }
+        }
+    }
+
+    stats.games = seeds.len();
+    let games = seeds.len().max(1) as f64;
+    stats.average_scores = totals.iter().map(|(team, total)| (team, total as f64 / games)).collect();
+    Ok(stats)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::{heuristic_table, HeuristicBot, RandomBot};
+
+    fn strategies() -> [Box<dyn Strategy>; 4] {
+        [
+            Box::new(HeuristicBot::new()),
+            Box::new(RandomBot::from_seed(1)),
+            Box::new(HeuristicBot::new()),
+            Box::new(RandomBot::from_seed(2)),
+        ]
+    }
+
+    #[test]
+    fn can_simulate_games() {
+        let stats = simulate(&mut strategies(), 10, 0).unwrap();
+        assert_eq!(stats.games, 10);
+        assert_eq!(stats.wins.values().sum::<usize>(), 10);
+        assert!(stats.win_rate(Team::SouthNorth) > stats.win_rate(Team::EastWest));
+        assert!(stats.average_scores[&Team::SouthNorth] >= 1000.0);
+        assert!(stats.seat(Player::West).contracts_taken > 0);
+        let rate = stats.contract_success_rate().unwrap();
+        assert!(rate > 0.0 && rate <= 1.0);
+    }
+
+    #[test]
+    fn can_simulate_in_parallel() {
+        let parallel = simulate_parallel(RuleSet::default(), heuristic_table, 9, 5, 3).unwrap();
+        let sequential = simulate(&mut heuristic_table(), 9, 5).unwrap();
+        assert_eq!(parallel.games, 9);
+        assert_eq!(parallel.wins, sequential.wins);
+        assert_eq!(parallel.seats, sequential.seats);
+        for team in &[Team::SouthNorth, Team::EastWest] {
+            assert!((parallel.average_scores[team] - sequential.average_scores[team]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn game_can_be_sent_to_threads() {
+        fn is_send<T: Send>() {}
+        is_send::<Game>();
+    }
+
+    #[test]
+    fn simulations_can_be_replayed() {
+        assert_eq!(simulate(&mut strategies(), 3, 42).unwrap(), simulate(&mut strategies(), 3, 42).unwrap());
+    }
+}
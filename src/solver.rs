@@ -0,0 +1,354 @@
+//! Double-dummy solver: finds the best play for everyone when all the hands are known.
+//! With only 8 tricks, an alpha-beta search with a transposition table on the trick
+//! boundaries can solve a round from any position. A full deal takes a few seconds in a
+//! release build and several times more in a debug one, the last tricks are instant.
+//! Winning all the tricks counts as `CAPOT_POINTS` for the attack.
+//! The table can be kept between calls with `solve_with_table` so analysing the same deal
+//! again, eg after each card played, reuses everything already found.
+
+
+use cards::{Card, Suit};
+use players::{Player, Team};
+use round::{Round, CAPOT_POINTS, TOTAL_TRICK_POINTS};
+use rules::RuleSet;
+use team_scores::TeamScores;
+
+
+/// The result of a perfect play of the rest of a round
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Solution {
+    /// Trick points of each team at the end of the round, including the points already won
+    /// and the 10 points of the last trick
+    pub trick_points: TeamScores,
+    /// The cards played until the end of the round if everyone plays perfectly.
+    /// When several cards are as good, the strongest one is picked
+    pub line: Vec<(Player, Card)>,
+    /// The team winning all the tricks at the end of the line, if any.
+    /// The attack counts a capot as `CAPOT_POINTS` instead of its trick points
+    pub capot: Option<Team>,
+}
+
+/// Lower and upper bounds of the points the attack will win from a position
+type Bounds = (i32, i32);
+
+/// Mixed into the key of positions where the attack can still win all the tricks,
+/// as they are worth more than the same cards after the defense won a trick
+const CAPOT_KEY: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Number of entries of the table used by `solve`
+pub const DEFAULT_TABLE_CAPACITY: usize = 1 << 18;
+
+/// What the bounds in a table depend on besides the position: the trump, the attack and
+/// whether partners must be overtrumped
+type TableContext = (Suit, Team, bool);
+
+/// Bounds of the points the attack wins from positions at the start of a trick, keyed by
+/// their `Round::zobrist_hash`. It has a fixed number of slots and a new position replaces
+/// the one in its slot, so it never grows beyond its capacity
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entries: Vec<Option<(u64, Bounds)>>,
+    context: Option<TableContext>,
+}
+
+impl TranspositionTable {
+    /// A table with that many slots, at least one
+    pub fn new(capacity: usize) -> TranspositionTable {
+        TranspositionTable { entries: vec![None; capacity.max(1)], context: None }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// How many slots are filled
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        for entry in &mut self.entries {
+            *entry = None;
+        }
+        self.context = None;
+    }
+
+    /// Empties the table if it was filled for another contract or rules
+    fn set_context(&mut self, context: TableContext) {
+        if self.context != Some(context) {
+            self.clear();
+            self.context = Some(context);
+        }
+    }
+
+    fn slot(&self, hash: u64) -> usize {
+        (hash % self.entries.len() as u64) as usize
+    }
+
+    fn get(&self, hash: u64) -> Option<Bounds> {
+        match self.entries[self.slot(hash)] {
+            Some((h, bounds)) if h == hash => Some(bounds),
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, hash: u64, bounds: Bounds) {
+        let slot = self.slot(hash);
+        self.entries[slot] = Some((hash, bounds));
+    }
+}
+
+struct Solver<'a> {
+    rules: &'a RuleSet,
+    /// The team that took the contract, maximizing its points. The defense minimizes them
+    attack: Team,
+    table: &'a mut TranspositionTable,
+}
+
+impl<'a> Solver<'a> {
+    fn new(round: &Round, rules: &'a RuleSet, table: &'a mut TranspositionTable) -> Solver<'a> {
+        let attack = round.contract().player().team();
+        table.set_context((round.contract().suit(), attack, rules.must_overtrump_partner));
+        Solver { rules, attack, table }
+    }
+
+    /// The cards the next player can play, strongest first to find cutoffs early
+    fn moves(&self, round: &Round) -> Vec<Card> {
+        let trump = round.contract().suit();
+        let mut cards = round.playable_cards(round.next_player(), self.rules);
+        cards.sort_by_key(|c| (c.suit == trump, c.strength(trump)));
+        cards.reverse();
+        cards
+    }
+
+    /// Plays the card on a copy of the round, returning it with the points the attack won
+    fn play(&self, round: &Round, card: Card) -> (Round, i32) {
+        let mut next = round.clone();
+        let before = next.trick_points[self.attack];
+        next.play(round.next_player(), card, self.rules).unwrap();
+        let gained = next.trick_points[self.attack] - before;
+        (next, gained as i32)
+    }
+
+    /// Points the attack wins from now on with perfect play, within the `alpha`/`beta` window
+    fn search(&mut self, round: &Round, mut alpha: i32, mut beta: i32) -> i32 {
+        if round.is_over() {
+            return if round.tricks_won[self.attack] == 8 { (CAPOT_POINTS - TOTAL_TRICK_POINTS) as i32 } else { 0 };
+        }
+
+        let key = if round.tricks_won[self.attack.opponent()] == 0 {
+            round.zobrist_hash() ^ CAPOT_KEY
+        } else {
+            round.zobrist_hash()
+        };
+        let at_trick_start = round.current_trick().is_empty();
+        if at_trick_start {
+            if let Some((lower, upper)) = self.table.get(key) {
+                if lower >= beta || lower == upper {
+                    return lower;
+                }
+                if upper <= alpha {
+                    return upper;
+                }
+                alpha = alpha.max(lower);
+                beta = beta.min(upper);
+            }
+        }
+
+        let (original_alpha, original_beta) = (alpha, beta);
+        let maximizing = round.next_player().team() == self.attack;
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+        for card in self.moves(round) {
+            let (next, gained) = self.play(round, card);
+            let value = gained + self.search(&next, alpha - gained, beta - gained);
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(value);
+            } else {
+                best = best.min(value);
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if at_trick_start {
+            let bounds = self.table.get(key).unwrap_or((0, i32::MAX));
+            let bounds = if best <= original_alpha {
+                (bounds.0, best)
+            } else if best >= original_beta {
+                (best, bounds.1)
+            } else {
+                (best, best)
+            };
+            self.table.insert(key, bounds);
+        }
+        best
+    }
+
+    /// Exact points the attack wins from now on with perfect play
+    fn value(&mut self, round: &Round) -> i32 {
+        self.search(round, i32::MIN / 2, i32::MAX / 2)
+    }
+}
+
+/// Solves the rest of the round with all hands visible: how many points each team
+/// ends up with and the line of play getting there, using the hands, the contract,
+/// the current trick and the tricks already played in `round`
+pub fn solve(round: &Round, rules: &RuleSet) -> Solution {
+    solve_with_table(round, rules, &mut TranspositionTable::new(DEFAULT_TABLE_CAPACITY))
+}
+
+/// Same as `solve`, keeping what was found in `table` for the next calls.
+/// The table is emptied first if it was used for another contract or rules
+pub fn solve_with_table(round: &Round, rules: &RuleSet, table: &mut TranspositionTable) -> Solution {
+    let mut solver = Solver::new(round, rules, table);
+    let mut current = round.clone();
+    let mut line = vec![];
+
+    // Once the value of the position is known, each card only needs to be checked
+    // against it with a minimal window: the first one reaching it is part of the line
+    let mut target = solver.value(&current);
+    while !current.is_over() {
+        let mut best = None;
+        for card in solver.moves(&current) {
+            let (next, gained) = solver.play(&current, card);
+            let rest = target - gained;
+            if solver.search(&next, rest - 1, rest + 1) == rest {
+                best = Some((card, next, rest));
+                break;
+            }
+        }
+        let (card, next, rest) = best.unwrap();
+        line.push((current.next_player(), card));
+        current = next;
+        target = rest;
+    }
+
+    let capot = [Team::SouthNorth, Team::EastWest].iter().cloned().find(|team| current.tricks_won[*team] == 8);
+    Solution { trick_points: current.trick_points, line, capot }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Contract;
+    use cards::{Rank, Suit};
+    use deck::Deck;
+    use std::collections::HashMap;
+
+    /// A round with the given number of tricks already played, always with the first playable card
+    fn round_after(tricks: usize, seed: u64) -> Round {
+        let rules = RuleSet::default();
+        let contract = Contract::taken(Player::South, Suit::Hearts);
+
+        let dealt = Deck::from_seed(seed).deal();
+        let hands: HashMap<Player, Vec<Card>> = Player::iterator().cloned().zip(dealt.iter().cloned()).collect();
+        let mut round = Round::with_hands(contract, Player::South, hands);
+        while round.tricks().len() < tricks || !round.current_trick().is_empty() {
+            let player = round.next_player();
+            let card = round.playable_cards(player, &rules)[0];
+            round.play(player, card, &rules).unwrap();
+        }
+        round
+    }
+
+    /// Plain minimax without any pruning or table
+    fn minimax(round: &Round, rules: &RuleSet) -> usize {
+        if round.is_over() {
+            let attack = round.contract().player().team();
+            return if round.tricks_won[attack] == 8 { CAPOT_POINTS } else { round.trick_points[attack] };
+        }
+        let player = round.next_player();
+        let values = round.playable_cards(player, rules).into_iter().map(|card| {
+            let mut next = round.clone();
+            next.play(player, card, rules).unwrap();
+            minimax(&next, rules)
+        });
+        if player.team() == round.contract().player().team() {
+            values.max().unwrap()
+        } else {
+            values.min().unwrap()
+        }
+    }
+
+    #[test]
+    fn matches_plain_minimax() {
+        let rules = RuleSet::default();
+        for seed in 0..5 {
+            let round = round_after(5, seed);
+            let solution = solve(&round, &rules);
+            assert_eq!(solution.trick_points[Team::SouthNorth], minimax(&round, &rules));
+            assert_eq!(solution.trick_points.total(), 162);
+        }
+    }
+
+    #[test]
+    fn line_reaches_the_solution() {
+        let rules = RuleSet::default();
+        let mut round = round_after(4, 7);
+        let solution = solve(&round, &rules);
+        assert_eq!(solution.line.len(), 16);
+        for &(player, card) in &solution.line {
+            round.play(player, card, &rules).unwrap();
+        }
+        assert!(round.is_over());
+        assert_eq!(round.trick_points, solution.trick_points);
+    }
+
+    #[test]
+    fn table_can_be_reused() {
+        let rules = RuleSet::default();
+        let mut table = TranspositionTable::new(1024);
+        assert!(table.is_empty());
+        let round = round_after(4, 3);
+        let first = solve_with_table(&round, &rules, &mut table);
+        assert!(!table.is_empty() && table.len() <= 1024);
+        assert_eq!(solve_with_table(&round, &rules, &mut table), first);
+        assert_eq!(solve(&round, &rules), first);
+
+        // Continuing the line finds the same points with the table already filled
+        let mut next = round.clone();
+        let (player, card) = first.line[0];
+        next.play(player, card, &rules).unwrap();
+        assert_eq!(solve_with_table(&next, &rules, &mut table).trick_points, first.trick_points);
+
+        // A tiny table still gives exact results
+        let mut tiny = TranspositionTable::new(0);
+        assert_eq!(tiny.capacity(), 1);
+        assert_eq!(solve_with_table(&round, &rules, &mut tiny), first);
+    }
+
+    #[test]
+    fn counts_capots() {
+        let rules = RuleSet::default();
+        let mut hands = HashMap::new();
+        for (player, suit) in Player::iterator().zip(&[Suit::Hearts, Suit::Clubs, Suit::Diamonds, Suit::Spades]) {
+            hands.insert(*player, Rank::iterator().map(|rank| Card::new(*suit, *rank)).collect());
+        }
+        let round = Round::with_hands(Contract::taken(Player::South, Suit::Hearts), Player::South, hands);
+        let solution = solve(&round, &rules);
+        assert_eq!(solution.capot, Some(Team::SouthNorth));
+        assert_eq!(solution.trick_points[Team::SouthNorth], 162);
+        assert_eq!(solve(&round_after(5, 0), &rules).capot, None);
+    }
+
+    #[test]
+    fn can_solve_a_full_deal() {
+        let rules = RuleSet::default();
+        let mut round = round_after(0, 11);
+        let solution = solve(&round, &rules);
+        assert_eq!(solution.line.len(), 32);
+        for &(player, card) in &solution.line {
+            round.play(player, card, &rules).unwrap();
+        }
+        assert_eq!(round.trick_points, solution.trick_points);
+        assert_eq!(solution.trick_points.total(), 162);
+    }
+}
@@ -0,0 +1,214 @@
+//! Aggregates over finished games: which contracts are bid and how often they are made,
+//! how many points each seat scores when it takes and how often counters pay off.
+//! `PlayFrequencies` counts which cards each seat plays at each trick, to draw heatmaps.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use bids::Bid;
+use cards::{Card, Rank, Suit};
+use game::Game;
+use players::Player;
+use score_sheet::ScoreSheet;
+
+
+/// How many contracts of some kind were played and made
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct ContractStats {
+    pub played: usize,
+    pub made: usize,
+}
+
+impl ContractStats {
+    fn add(&mut self, made: bool) {
+        self.played += 1;
+        if made {
+            self.made += 1;
+        }
+    }
+
+    /// Share of the contracts that were made, `None` if none were played
+    pub fn success_rate(&self) -> Option<f64> {
+        if self.played == 0 {
+            return None;
+        }
+        Some(self.made as f64 / self.played as f64)
+    }
+}
+
+/// Statistics over all the rounds of the games added
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Statistics {
+    pub games: usize,
+    /// Rounds played, deals where everyone passed are not counted
+    pub rounds: usize,
+    /// Contracts by level and trump. Taken contracts of classic belote have no level and are only counted by trump
+    pub contracts: HashMap<(Bid, Suit), ContractStats>,
+    /// Contracts by trump, whatever the level
+    pub by_suit: HashMap<Suit, ContractStats>,
+    /// Contracts by level, whatever the trump
+    pub by_bid: HashMap<Bid, ContractStats>,
+    /// Contracts that were countered. A counter succeeds when the contract is not made
+    pub countered: ContractStats,
+    /// Contracts that were double countered
+    pub double_countered: ContractStats,
+    /// Contracts taken by each seat, indexed by `Player::index`
+    pub seats: [ContractStats; 4],
+    /// Total score of the team of each seat in the rounds that seat took, indexed by `Player::index`
+    pub seat_points: [usize; 4],
+}
+
+impl Statistics {
+    pub fn new() -> Statistics {
+        Statistics::default()
+    }
+
+    /// Adds all the rounds of a game, finished or not
+    pub fn add_game(&mut self, game: &Game) {
+        self.add_score_sheet(&game.score_sheet());
+    }
+
+    /// Adds all the rounds of a score sheet as one game
+    pub fn add_score_sheet(&mut self, sheet: &ScoreSheet) {
+        self.games += 1;
+        for line in &sheet.lines {
+            let contract = &line.contract;
+            let (bid, suit, made) = (contract.bid(), contract.suit(), line.made);
+            self.rounds += 1;
+            // Taken contracts of classic belote have no bid
+            if let Some(bid) = bid {
+                self.contracts.entry((bid, suit)).or_default().add(made);
+                self.by_bid.entry(bid).or_default().add(made);
+            }
+            self.by_suit.entry(suit).or_default().add(made);
+            if contract.double_countered() {
+                self.double_countered.add(made);
+            } else if contract.countered() {
+                self.countered.add(made);
+            }
+            let taker = contract.player();
+            self.seats[taker.index()].add(made);
+            self.seat_points[taker.index()] += line.scores[taker.team()];
+        }
+    }
+
+    /// Share of the counters where the contract was not made, `None` if there were none
+    pub fn counter_success_rate(&self) -> Option<f64> {
+        self.countered.success_rate().map(|r| 1.0 - r)
+    }
+
+    /// Average score of the team of the seat in the rounds it took, `None` if it never took
+    pub fn average_points_as_taker(&self, player: Player) -> Option<f64> {
+        let taken = self.seats[player.index()].played;
+        if taken == 0 {
+            return None;
+        }
+        Some(self.seat_points[player.index()] as f64 / taken as f64)
+    }
+
+    /// How often each contract was played, most played first
+    pub fn contract_frequencies(&self) -> Vec<((Bid, Suit), usize)> {
+        let mut frequencies: Vec<((Bid, Suit), usize)> = self.contracts.iter().map(|(k, s)| (*k, s.played)).collect();
+        frequencies.sort_by_key(|&((bid, suit), played)| (Reverse(played), bid, suit as usize));
+        frequencies
+    }
+}
+
+/// How often each card is played at each trick by each seat over the rounds of the games added
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PlayFrequencies {
+    pub rounds: usize,
+    /// Times the card was played, by trick number starting at 0, seat and card
+    pub counts: HashMap<(usize, Player, Card), usize>,
+}
+
+impl PlayFrequencies {
+    pub fn new() -> PlayFrequencies {
+        PlayFrequencies::default()
+    }
+
+    /// Adds the tricks of all the rounds of a game, finished or not
+    pub fn add_game(&mut self, game: &Game) {
+        for round in game.rounds() {
+            self.rounds += 1;
+            for (i, (trick, _)) in round.tricks().iter().enumerate() {
+                for &(player, card) in trick.iter() {
+                    *self.counts.entry((i, player, card)).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    /// Times the seat played a card of that rank at that trick, whatever its suit
+    pub fn rank_count(&self, trick: usize, player: Player, rank: Rank) -> usize {
+        Suit::iterator().map(|s| self.counts.get(&(trick, player, Card::new(*s, rank))).map_or(0, |c| *c)).sum()
+    }
+
+    /// Share of the rounds where the seat played that card at that trick, `None` if no rounds were added
+    pub fn frequency(&self, trick: usize, player: Player, card: Card) -> Option<f64> {
+        if self.rounds == 0 {
+            return None;
+        }
+        Some(self.counts.get(&(trick, player, card)).map_or(0, |c| *c) as f64 / self.rounds as f64)
+    }
+
+    /// The cards played, by trick, seat in `Player::iterator` order and card index
+    pub fn sorted_counts(&self) -> Vec<((usize, Player, Card), usize)> {
+        let mut counts: Vec<((usize, Player, Card), usize)> = self.counts.iter().map(|(k, c)| (*k, *c)).collect();
+        counts.sort_by_key(|&((trick, player, card), _)| (trick, player.index(), card.index()));
+        counts
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bots::heuristic_table;
+    use strategy::run_game;
+
+    #[test]
+    fn can_aggregate_games() {
+        let mut stats = Statistics::new();
+        for seed in 0..3 {
+            let mut game = Game::from_seed(Default::default(), seed);
+            run_game(&mut game, &mut heuristic_table()).unwrap();
+            stats.add_game(&game);
+        }
+
+        assert_eq!(stats.games, 3);
+        assert!(stats.rounds >= 3);
+        let total = |map: &HashMap<Suit, ContractStats>| map.values().map(|s| s.played).sum::<usize>();
+        assert_eq!(total(&stats.by_suit), stats.rounds);
+        assert_eq!(stats.by_bid.values().map(|s| s.played).sum::<usize>(), stats.rounds);
+        assert_eq!(stats.seats.iter().map(|s| s.played).sum::<usize>(), stats.rounds);
+        assert_eq!(stats.contract_frequencies().iter().map(|f| f.1).sum::<usize>(), stats.rounds);
+
+        let taker = Player::iterator().find(|p| stats.seats[p.index()].played > 0).unwrap();
+        assert!(stats.average_points_as_taker(*taker).is_some());
+        assert!(stats.seats[taker.index()].success_rate().unwrap() <= 1.0);
+        assert_eq!(Statistics::new().counter_success_rate(), None);
+    }
+
+    #[test]
+    fn can_count_cards_played() {
+        let mut plays = PlayFrequencies::new();
+        assert_eq!(plays.frequency(0, Player::South, Card::new(Suit::Hearts, Rank::Ace)), None);
+        let mut game = Game::from_seed(Default::default(), 1);
+        run_game(&mut game, &mut heuristic_table()).unwrap();
+        plays.add_game(&game);
+
+        assert_eq!(plays.rounds, game.rounds().len());
+        assert_eq!(plays.counts.values().sum::<usize>(), 32 * plays.rounds);
+        // Every seat plays one card in every trick of every round
+        for trick in 0..8 {
+            for player in Player::iterator() {
+                let played: usize = Rank::iterator().map(|r| plays.rank_count(trick, *player, *r)).sum();
+                assert_eq!(played, plays.rounds);
+            }
+        }
+        let ((trick, player, card), count) = plays.sorted_counts()[0];
+        assert_eq!((trick, player), (0, Player::South));
+        assert_eq!(plays.frequency(trick, player, card), Some(count as f64 / plays.rounds as f64));
+    }
+}
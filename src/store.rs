@@ -0,0 +1,147 @@
+//! Where servers keep their games: a snapshot of the game plus the events that happened
+//! since, which is enough to rebuild the game after a restart.
+
+use std::collections::HashMap;
+
+use failure::Error;
+
+use game::Game;
+use session::Event;
+
+
+/// A game as stored: the last snapshot and the events after it
+#[derive(Debug, Clone)]
+pub struct StoredGame {
+    pub snapshot: Game,
+    /// The sequence number of the last event included in the snapshot
+    pub snapshot_seq: usize,
+    /// The events after the snapshot, in order
+    pub events: Vec<Event>,
+}
+
+impl StoredGame {
+    /// The sequence number of the last event stored
+    pub fn last_seq(&self) -> usize {
+        self.events.last().map_or(self.snapshot_seq, |e| e.seq)
+    }
+
+    /// Applies the events to the snapshot to get the current game.
+    /// Errors if an event is not valid for the game
+    pub fn replay(&self) -> Result<Game, Error> {
+        let mut game = self.snapshot.clone();
+        for event in &self.events {
+            event.apply_to(&mut game)?;
+        }
+        Ok(game)
+    }
+}
+
+/// Durable storage of games by id
+pub trait GameStore {
+    /// Saves the game as it is after the event `seq` and forgets the events up to that one.
+    /// Creates the game if it doesn't exist yet
+    fn save_snapshot(&mut self, id: &str, game: &Game, seq: usize) -> Result<(), Error>;
+
+    /// Adds events after the ones already stored.
+    /// Errors if the game doesn't exist or if the sequence numbers don't follow the last one
+    fn append_events(&mut self, id: &str, events: &[Event]) -> Result<(), Error>;
+
+    /// The game with that id, if there is one
+    fn load(&self, id: &str) -> Result<Option<StoredGame>, Error>;
+
+    /// The ids of all the games stored, sorted
+    fn list(&self) -> Result<Vec<String>, Error>;
+}
+
+/// A store keeping everything in memory, for tests and single process servers
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    games: HashMap<String, StoredGame>,
+}
+
+impl MemoryStore {
+    pub fn new() -> MemoryStore {
+        MemoryStore::default()
+    }
+}
+
+impl GameStore for MemoryStore {
+    fn save_snapshot(&mut self, id: &str, game: &Game, seq: usize) -> Result<(), Error> {
+        let events = match self.games.get(id) {
+            Some(stored) => stored.events.iter().filter(|e| e.seq > seq).cloned().collect(),
+            None => vec![],
+        };
+        self.games.insert(id.to_string(), StoredGame { snapshot: game.clone(), snapshot_seq: seq, events });
+        Ok(())
+    }
+
+    fn append_events(&mut self, id: &str, events: &[Event]) -> Result<(), Error> {
+        let stored = match self.games.get_mut(id) {
+            Some(s) => s,
+            None => bail!("No game with the id {}", id),
+        };
+        let mut last_seq = stored.last_seq();
+        for event in events {
+            if event.seq != last_seq + 1 {
+                bail!("Expected the event {} but got {}", last_seq + 1, event.seq);
+            }
+            last_seq = event.seq;
+        }
+        stored.events.extend_from_slice(events);
+        Ok(())
+    }
+
+    fn load(&self, id: &str) -> Result<Option<StoredGame>, Error> {
+        Ok(self.games.get(id).cloned())
+    }
+
+    fn list(&self) -> Result<Vec<String>, Error> {
+        let mut ids: Vec<String> = self.games.keys().cloned().collect();
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use players::Player;
+    use session::Table;
+
+    #[test]
+    fn can_store_and_replay_games() {
+        let mut store = MemoryStore::new();
+        let mut table = Table::from_seed(Game::from_seed(Default::default(), 3), 3);
+        store.save_snapshot("lucky-jack-42", table.game(), 0).unwrap();
+        assert!(store.append_events("nope", &[]).is_err());
+
+        let mut after_four = None;
+        for _ in 0..6 {
+            let player = table.game().current_player().unwrap();
+            let hint = table.game().hint(player).unwrap();
+            let event = table.apply(&table.token(player), hint.action).unwrap();
+            let seq = event.seq;
+            store.append_events("lucky-jack-42", &[event]).unwrap();
+            if seq == 4 {
+                after_four = Some(table.game().clone());
+            }
+        }
+        // Events must follow each other
+        assert!(store.append_events("lucky-jack-42", &table.events_since(2)[..1]).is_err());
+
+        let stored = store.load("lucky-jack-42").unwrap().unwrap();
+        assert_eq!(stored.last_seq(), 6);
+        assert_eq!(stored.replay().unwrap().player_view(Player::South), table.game().player_view(Player::South));
+
+        store.save_snapshot("lucky-jack-42", &after_four.unwrap(), 4).unwrap();
+        let stored = store.load("lucky-jack-42").unwrap().unwrap();
+        assert_eq!(stored.events.len(), 2);
+        assert_eq!(stored.last_seq(), 6);
+        assert_eq!(stored.replay().unwrap().player_view(Player::South), table.game().player_view(Player::South));
+
+        store.save_snapshot("brave-ace-1", table.game(), 0).unwrap();
+        assert_eq!(store.list().unwrap(), vec!["brave-ace-1", "lucky-jack-42"]);
+        assert!(store.load("nope").unwrap().is_none());
+    }
+}
@@ -0,0 +1,216 @@
+use std::time::{Duration, Instant};
+
+use failure::Error;
+
+use actions::Action;
+use bids::Bid;
+use bots::HeuristicBot;
+use cards::{Card, Suit};
+use game::{Game, MatchResult};
+use players::{Player, Team};
+use view::PlayerView;
+
+
+/// How an automated player decides what to do
+pub trait Strategy {
+    /// Called when it's the turn of the player to bid.
+    /// `view.available_bids` contains the bids that can be made
+    fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>);
+
+    /// Called when it's the turn of the player to play.
+    /// `view.playable_cards` contains the cards that can be played
+    fn choose_card(&mut self, view: &PlayerView) -> Card;
+
+    /// Called when it's the turn of the player to take a suit as trump in classic belote,
+    /// `None` to refuse. `view.turned_up` is the card proposed and `view.legal_actions` the
+    /// suits that can be taken. Takes like `HeuristicBot` by default
+    fn choose_take(&mut self, view: &PlayerView) -> Option<Suit> {
+        HeuristicBot::new().advise_take(view).0
+    }
+}
+
+/// What the strategy does with that view: a bid when `bidding`, or taking a suit when a
+/// card is turned up in classic belote, a card otherwise
+pub fn choose_action(strategy: &mut dyn Strategy, view: &PlayerView, bidding: bool) -> Action {
+    if bidding && view.turned_up.is_some() {
+        Action::Take(strategy.choose_take(view))
+    } else if bidding {
+        let (bid, suit) = strategy.choose_bid(view);
+        Action::Bid(bid, suit)
+    } else {
+        Action::Play(strategy.choose_card(view))
+    }
+}
+
+/// Calls `step` with the player whose turn it is until a team wins, dealing first if needed
+pub(crate) fn play_until_won<F>(game: &mut Game, mut step: F) -> Result<Team, Error>
+    where F: FnMut(&mut Game, Player) -> Result<(), Error>
+{
+    if game.current_player().is_none() {
+        game.new_round();
+    }
+
+    loop {
+        if let MatchResult::Won(team) = game.standing().result {
+            return Ok(team);
+        }
+        match game.current_player() {
+            Some(player) => step(game, player)?,
+            None => bail!("The game is stuck: nobody can play"),
+        }
+    }
+}
+
+/// Plays a full game with one strategy per player, indexed by `Player::index`,
+/// and returns the winning team.
+/// Errors if a strategy tries to do something invalid.
+pub fn run_game(game: &mut Game, strategies: &mut [Box<dyn Strategy>; 4]) -> Result<Team, Error> {
+    play_until_won(game, |game, player| {
+        let view = game.player_view(player);
+        let action = choose_action(&mut *strategies[player.index()], &view, game.is_bidding());
+        game.apply(player, action)
+    })
+}
+
+/// A player went over the time limit and `action` was made for them
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Timeout {
+    pub player: Player,
+    pub action: Action,
+    /// How long the strategy took
+    pub elapsed: Duration,
+}
+
+/// Same as `run_game` but every decision has to be made within `limit`.
+/// When a strategy takes longer, its choice is ignored and `Game::time_out` passes or plays
+/// the cheapest card for it instead; `on_timeout` is called every time that happens.
+/// Strategies are not interrupted: the time is checked once they answer
+pub fn run_game_with_time_limit<F>(
+    game: &mut Game,
+    strategies: &mut [Box<dyn Strategy>; 4],
+    limit: Duration,
+    on_timeout: F,
+) -> Result<Team, Error>
+    where F: FnMut(Timeout)
+{
+    run_game_with_clock(game, strategies, limit, Instant::now, on_timeout)
+}
+
+/// `run_game_with_time_limit` reading the time from `now`
+fn run_game_with_clock<C, F>(
+    game: &mut Game,
+    strategies: &mut [Box<dyn Strategy>; 4],
+    limit: Duration,
+    mut now: C,
+    mut on_timeout: F,
+) -> Result<Team, Error>
+    where C: FnMut() -> Instant, F: FnMut(Timeout)
+{
+    play_until_won(game, |game, player| {
+        let view = game.player_view(player);
+        let start = now();
+        let action = choose_action(&mut *strategies[player.index()], &view, game.is_bidding());
+        let elapsed = now() - start;
+        if elapsed > limit {
+            let action = game.time_out(player)?;
+            on_timeout(Timeout { player, action, elapsed });
+            Ok(())
+        } else {
+            game.apply(player, action)
+        }
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use round::TOTAL_TRICK_POINTS;
+
+    /// The first player to bid always bids 80 in the suit of their first card,
+    /// everyone plays the first card possible
+    struct FirstCard;
+
+    impl Strategy for FirstCard {
+        fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>) {
+            if view.bids.is_empty() {
+                (Bid::Eighty, Some(view.hand[0].suit))
+            } else {
+                (Bid::Pass, None)
+            }
+        }
+
+        fn choose_card(&mut self, view: &PlayerView) -> Card {
+            view.playable_cards[0]
+        }
+    }
+
+    /// Takes 20ms of the shared fake clock for its first few decisions
+    struct Slow(usize, Rc<Cell<Duration>>);
+
+    impl Slow {
+        fn think(&mut self) {
+            if self.0 > 0 {
+                self.0 -= 1;
+                self.1.set(self.1.get() + Duration::from_millis(20));
+            }
+        }
+    }
+
+    impl Strategy for Slow {
+        fn choose_bid(&mut self, view: &PlayerView) -> (Bid, Option<Suit>) {
+            self.think();
+            (Bid::Capot, Some(view.hand[0].suit))
+        }
+
+        fn choose_card(&mut self, view: &PlayerView) -> Card {
+            self.think();
+            view.playable_cards[0]
+        }
+    }
+
+    #[test]
+    fn can_run_a_full_game() {
+        let mut game = Game::from_seed(Default::default(), 2);
+        let mut strategies: [Box<dyn Strategy>; 4] = [
+            Box::new(FirstCard), Box::new(FirstCard), Box::new(FirstCard), Box::new(FirstCard),
+        ];
+        let winner = run_game(&mut game, &mut strategies).unwrap();
+        assert_eq!(game.has_winner(), Some(winner));
+
+        let sheet = game.score_sheet();
+        assert!(!sheet.lines.is_empty());
+        // The capot bonus is only added to the scores, the tricks are always worth 162
+        for line in &sheet.lines {
+            assert_eq!(line.trick_points.total(), TOTAL_TRICK_POINTS);
+        }
+        // South bids 80 in the suit of their first card and fails on that deal
+        let first = &sheet.lines[0];
+        assert_eq!(first.contract.player(), Player::South);
+        assert_eq!(first.contract.bid(), Some(Bid::Eighty));
+        assert_eq!((first.trick_points[Team::SouthNorth], first.trick_points[Team::EastWest]), (12, 150));
+        assert_eq!((first.scores[Team::SouthNorth], first.scores[Team::EastWest]), (0, 240));
+        assert_eq!(sheet.lines.len(), 6);
+    }
+
+    #[test]
+    fn slow_players_time_out() {
+        let mut game = Game::from_seed(Default::default(), 1);
+        let time = Rc::new(Cell::new(Duration::from_secs(0)));
+        let mut strategies: [Box<dyn Strategy>; 4] = [
+            Box::new(Slow(3, time.clone())), Box::new(FirstCard), Box::new(FirstCard), Box::new(FirstCard),
+        ];
+        let start = Instant::now();
+        let mut timeouts = vec![];
+        let limit = Duration::from_millis(10);
+        run_game_with_clock(&mut game, &mut strategies, limit, || start + time.get(), |t| timeouts.push(t)).unwrap();
+        assert_eq!(timeouts.len(), 3);
+        // South would have bid capot but passes instead
+        assert_eq!(timeouts[0].player, Player::South);
+        assert_eq!(timeouts[0].action, Action::Bid(Bid::Pass, None));
+        assert_eq!(timeouts[0].elapsed, Duration::from_millis(20));
+        assert!(game.has_winner().is_some());
+    }
+}
@@ -0,0 +1,180 @@
+//! Ids for tables: a name that people can read out loud, like `lucky-jack-42`,
+//! mapped to a UUID for storage and APIs.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use failure::Error;
+use rand;
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+
+const ADJECTIVES: [&str; 32] = [
+    "bold", "brave", "bright", "calm", "clever", "cosy", "crafty", "daring",
+    "eager", "fancy", "fierce", "gentle", "golden", "grand", "happy", "jolly",
+    "keen", "lucky", "merry", "mighty", "noble", "proud", "quick", "quiet",
+    "royal", "shiny", "silent", "sly", "swift", "tidy", "vivid", "witty",
+];
+
+const NOUNS: [&str; 32] = [
+    "ace", "belote", "bid", "card", "capot", "club", "contract", "counter",
+    "deal", "deck", "diamond", "eight", "heart", "jack", "king", "knave",
+    "nine", "queen", "rebelote", "round", "seven", "spade", "suit", "table",
+    "ten", "tierce", "trick", "trump", "quarte", "quinte", "square", "cut",
+];
+
+/// How many times we try to generate a name that hasn't been used before giving up
+const MAX_ATTEMPTS: usize = 1000;
+
+/// A random (version 4) UUID
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Uuid(pub [u8; 16]);
+
+impl Uuid {
+    pub(crate) fn random<R: Rng>(rng: &mut R) -> Uuid {
+        let mut bytes = [0; 16];
+        rng.fill_bytes(&mut bytes);
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Uuid(bytes)
+    }
+}
+
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i == 4 || i == 6 || i == 8 || i == 10 {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// The id of a table: a name for humans and a UUID for machines
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TableId {
+    pub name: String,
+    pub uuid: Uuid,
+}
+
+/// Generates table ids that are unique among the ones it handed out.
+/// It can be shared between threads.
+#[derive(Debug)]
+pub struct TableIdGenerator {
+    rng: Mutex<Isaac64Rng>,
+    tables: Mutex<HashMap<String, Uuid>>,
+}
+
+impl Default for TableIdGenerator {
+    fn default() -> TableIdGenerator {
+        TableIdGenerator::new()
+    }
+}
+
+impl TableIdGenerator {
+    pub fn new() -> TableIdGenerator {
+        TableIdGenerator::from_seed(rand::random())
+    }
+
+    /// A generator giving the same ids in the same order for the same seed
+    pub fn from_seed(seed: u64) -> TableIdGenerator {
+        TableIdGenerator {
+            rng: Mutex::new(Isaac64Rng::from_seed(&[seed])),
+            tables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A new id whose name is not used by any table currently registered.
+    /// Errors if we can't find a free name, which only happens with close to a million tables
+    pub fn generate(&self) -> Result<TableId, Error> {
+        let mut rng = self.rng.lock().unwrap();
+        let mut tables = self.tables.lock().unwrap();
+        for _ in 0..MAX_ATTEMPTS {
+            let name = format!(
+                "{}-{}-{}",
+                rng.choose(&ADJECTIVES).unwrap(),
+                rng.choose(&NOUNS).unwrap(),
+                rng.gen_range(0, 1000),
+            );
+            if tables.contains_key(&name) {
+                continue;
+            }
+            let uuid = Uuid::random(&mut *rng);
+            tables.insert(name.clone(), uuid);
+            return Ok(TableId { name, uuid });
+        }
+        bail!("Could not find a free table name");
+    }
+
+    /// The UUID of the table with that name
+    pub fn uuid(&self, name: &str) -> Option<Uuid> {
+        self.tables.lock().unwrap().get(name).cloned()
+    }
+
+    /// The name of the table with that UUID
+    pub fn name(&self, uuid: Uuid) -> Option<String> {
+        self.tables
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|&(_, u)| *u == uuid)
+            .map(|(name, _)| name.clone())
+    }
+
+    /// Frees the name of a table that is closed so it can be used again
+    pub fn release(&self, name: &str) -> Option<Uuid> {
+        self.tables.lock().unwrap().remove(name)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn can_generate_ids() {
+        let generator = TableIdGenerator::from_seed(1);
+        let id = generator.generate().unwrap();
+        let parts: Vec<&str> = id.name.split('-').collect();
+        assert_eq!(parts.len(), 3);
+        assert!(ADJECTIVES.contains(&parts[0]));
+        assert!(NOUNS.contains(&parts[1]));
+        assert!(parts[2].parse::<usize>().unwrap() < 1000);
+
+        let uuid = id.uuid.to_string();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert_eq!(generator.uuid(&id.name), Some(id.uuid));
+        assert_eq!(generator.name(id.uuid), Some(id.name.clone()));
+        assert_eq!(generator.release(&id.name), Some(id.uuid));
+        assert_eq!(generator.uuid(&id.name), None);
+
+        assert_eq!(TableIdGenerator::from_seed(1).generate().unwrap(), id);
+    }
+
+    #[test]
+    fn ids_are_unique_across_threads() {
+        let generator = Arc::new(TableIdGenerator::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let generator = generator.clone();
+                thread::spawn(move || (0..250).map(|_| generator.generate().unwrap()).collect::<Vec<_>>())
+            })
+            .collect();
+        let mut names = HashSet::new();
+        for handle in handles {
+            for id in handle.join().unwrap() {
+                assert!(names.insert(id.name));
+            }
+        }
+        assert_eq!(names.len(), 1000);
+    }
+}
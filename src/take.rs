@@ -0,0 +1,172 @@
+use failure::Error;
+
+use bids::{BiddingState, Contract};
+use cards::{Card, Suit};
+use errors::{ContreeError, Phase};
+use players::Player;
+
+
+/// The taking phase of classic (non-contrée) belote.
+/// After the first stage of the deal, a card is turned up and each player in turn
+/// can take it, making its suit trump. If everyone refuses, a second table round
+/// happens where players can pick any other suit as trump.
+/// If everyone refuses again, the cards are dealt again.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TakePhase {
+    /// Who is starting the taking phase
+    starting_player: Player,
+    /// The card turned up after the first stage of the deal
+    turned_up: Card,
+    /// All the decisions so far: `None` is a refusal, `Some` the suit taken
+    decisions: Vec<(Player, Option<Suit>)>,
+    pub state: BiddingState,
+}
+
+impl TakePhase {
+    pub fn new(starting_player: Player, turned_up: Card) -> TakePhase {
+        TakePhase {
+            starting_player,
+            turned_up,
+            decisions: vec![],
+            state: BiddingState::Ongoing,
+        }
+    }
+
+    pub fn turned_up(&self) -> Card {
+        self.turned_up
+    }
+
+    /// Which table round we are in: 1 to take the turned-up suit, 2 to choose another one
+    pub fn table_round(&self) -> usize {
+        if self.decisions.len() < 4 { 1 } else { 2 }
+    }
+
+    /// The player who has to decide next
+    pub fn current_player(&self) -> Player {
+        match self.decisions.last() {
+            Some(&(last_player, _)) => last_player.next_player(),
+            None => self.starting_player,
+        }
+    }
+
+    /// The suits the current player can take
+    pub fn available_suits(&self) -> Vec<Suit> {
+        if self.state != BiddingState::Ongoing {
+            return vec![];
+        }
+
+        if self.table_round() == 1 {
+            vec![self.turned_up.suit]
+        } else {
+            Suit::iterator()
+                .filter(|s| **s != self.turned_up.suit)
+                .cloned()
+                .collect()
+        }
+    }
+
+    /// Checks whether the player can take the suit, or refuse with `None`, without doing it
+    pub fn check(&self, player: Player, suit: Option<Suit>) -> Result<(), ContreeError> {
+        if self.state != BiddingState::Ongoing {
+            return Err(ContreeError::PhaseMismatch { expected: Phase::Taking });
+        }
+        if player != self.current_player() {
+            return Err(ContreeError::WrongPlayer { expected: self.current_player(), got: player });
+        }
+        if let Some(suit) = suit {
+            let allowed = self.available_suits();
+            if !allowed.contains(&suit) {
+                return Err(ContreeError::SuitNotAllowed { suit, allowed });
+            }
+        }
+        Ok(())
+    }
+
+    /// The player takes the given suit as trump, which ends the taking phase
+    pub fn take(&mut self, player: Player, suit: Suit) -> Result<(), Error> {
+        self.check(player, Some(suit))?;
+
+        self.decisions.push((player, Some(suit)));
+        self.state = BiddingState::Done;
+        Ok(())
+    }
+
+    /// The player doesn't want to take
+    pub fn refuse(&mut self, player: Player) -> Result<(), Error> {
+        self.check(player, None)?;
+
+        self.decisions.push((player, None));
+        if self.decisions.len() == 8 {
+            self.state = BiddingState::DealAgain;
+        }
+        Ok(())
+    }
+
+    pub fn get_contract(&self) -> Result<Contract, Error> {
+        if self.state != BiddingState::Done {
+            bail!("Invalid taking state: expected the taking phase to be done with a taker");
+        }
+
+        match self.decisions.last() {
+            Some(&(player, Some(suit))) => Ok(Contract::taken(player, suit)),
+            _ => bail!("Couldn't get a contract from the taking phase"),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Rank;
+
+    fn phase() -> TakePhase {
+        TakePhase::new(Player::South, Card::new(Suit::Hearts, Rank::Jack))
+    }
+
+    #[test]
+    fn can_take_on_first_round() {
+        let mut take_phase = phase();
+        assert_eq!(take_phase.available_suits(), vec![Suit::Hearts]);
+        assert!(take_phase.refuse(Player::South).is_ok());
+        assert!(take_phase.take(Player::West, Suit::Spades).is_err());
+        assert!(take_phase.take(Player::West, Suit::Hearts).is_ok());
+        assert_eq!(take_phase.state, BiddingState::Done);
+
+        let contract = take_phase.get_contract().unwrap();
+        assert_eq!(contract, Contract::taken(Player::West, Suit::Hearts));
+        assert!(contract.is_taken());
+    }
+
+    #[test]
+    fn can_choose_another_suit_on_second_round() {
+        let mut take_phase = phase();
+        for player in &[Player::South, Player::West, Player::North, Player::East] {
+            assert!(take_phase.refuse(*player).is_ok());
+        }
+        assert_eq!(take_phase.table_round(), 2);
+        assert_eq!(take_phase.available_suits(), vec![Suit::Clubs, Suit::Diamonds, Suit::Spades]);
+        assert!(take_phase.take(Player::South, Suit::Hearts).is_err());
+        assert!(take_phase.take(Player::South, Suit::Clubs).is_ok());
+        assert_eq!(take_phase.get_contract().unwrap(), Contract::taken(Player::South, Suit::Clubs));
+    }
+
+    #[test]
+    fn everyone_refusing_twice_means_dealing_again() {
+        let mut take_phase = phase();
+        for _ in 0..8 {
+            let player = take_phase.current_player();
+            assert!(take_phase.refuse(player).is_ok());
+        }
+        assert_eq!(take_phase.state, BiddingState::DealAgain);
+        assert!(take_phase.get_contract().is_err());
+        assert!(take_phase.refuse(Player::South).is_err());
+    }
+
+    #[test]
+    fn errors_on_wrong_player() {
+        let mut take_phase = phase();
+        assert!(take_phase.refuse(Player::West).is_err());
+        assert!(take_phase.take(Player::North, Suit::Hearts).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::ops::{Add, AddAssign, Index, IndexMut};
+
+use players::Team;
+
+
+/// Both teams, in the order of `TeamScores::iter`
+const TEAMS: [Team; 2] = [Team::SouthNorth, Team::EastWest];
+
+/// A number of points for each team, eg the trick points or the scores of a round.
+/// Points are `usize` like the ones of `Card::points` and `Bid::value`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct TeamScores {
+    pub south_north: usize,
+    pub east_west: usize,
+}
+
+impl TeamScores {
+    pub fn new(south_north: usize, east_west: usize) -> TeamScores {
+        TeamScores { south_north, east_west }
+    }
+
+    /// The points of both teams added together
+    pub fn total(&self) -> usize {
+        self.south_north + self.east_west
+    }
+
+    /// Each team with its points, South/North first
+    pub fn iter(&self) -> impl Iterator<Item = (Team, usize)> {
+        let scores = *self;
+        TEAMS.iter().map(move |team| (*team, scores[*team]))
+    }
+}
+
+impl Index<Team> for TeamScores {
+    type Output = usize;
+
+    fn index(&self, team: Team) -> &usize {
+        match team {
+            Team::SouthNorth => &self.south_north,
+            Team::EastWest => &self.east_west,
+        }
+    }
+}
+
+impl IndexMut<Team> for TeamScores {
+    fn index_mut(&mut self, team: Team) -> &mut usize {
+        match team {
+            Team::SouthNorth => &mut self.south_north,
+            Team::EastWest => &mut self.east_west,
+        }
+    }
+}
+
+impl Add for TeamScores {
+    type Output = TeamScores;
+
+    fn add(self, other: TeamScores) -> TeamScores {
+        TeamScores::new(self.south_north + other.south_north, self.east_west + other.east_west)
+    }
+}
+
+impl AddAssign for TeamScores {
+    fn add_assign(&mut self, other: TeamScores) {
+        *self = *self + other;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_add_scores() {
+        let mut totals = TeamScores::default();
+        totals += TeamScores::new(100, 62);
+        totals[Team::EastWest] += 20;
+        assert_eq!(totals + TeamScores::new(0, 160), TeamScores::new(100, 242));
+        assert_eq!(totals[Team::SouthNorth], 100);
+        assert_eq!(totals.total(), 182);
+        assert_eq!(totals.iter().collect::<Vec<_>>(), vec![(Team::SouthNorth, 100), (Team::EastWest, 82)]);
+    }
+}
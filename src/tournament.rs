@@ -0,0 +1,258 @@
+//! Tournaments between strategies of a registry: round-robins where every entrant plays every
+//! other one, and knockout brackets where the winners advance until one is left.
+//! Both players of a team use the strategy of the entrant.
+
+use std::collections::HashMap;
+
+use failure::Error;
+
+use game::Game;
+use players::Team;
+use registry::Registry;
+use rules::RuleSet;
+use strategy::run_game;
+
+
+/// One game of a tournament
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Fixture {
+    pub south_north: String,
+    pub east_west: String,
+    pub seed: u64,
+    pub winner: String,
+    /// Final totals of the South/North and East/West teams
+    pub totals: (usize, usize),
+}
+
+/// How an entrant did over the tournament
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TournamentStanding {
+    pub id: String,
+    pub played: usize,
+    pub won: usize,
+    pub points_for: usize,
+    pub points_against: usize,
+}
+
+/// Every round of games played and the standings, best first:
+/// most games won, then best points difference, then id
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TournamentResult {
+    pub rounds: Vec<Vec<Fixture>>,
+    pub standings: Vec<TournamentStanding>,
+}
+
+impl TournamentResult {
+    /// The entrant at the top of the standings
+    pub fn winner(&self) -> Option<&str> {
+        self.standings.first().map(|s| s.id.as_str())
+    }
+}
+
+/// The pairings of a round-robin, by round, as indices of the entrants.
+/// Uses the circle method: with an odd number of entrants one of them sits out each round
+pub fn round_robin_pairings(entrants: usize) -> Vec<Vec<(usize, usize)>> {
+    if entrants < 2 {
+        return vec![];
+    }
+    // A fake entrant for the one sitting out
+    let n = entrants + entrants % 2;
+    let mut circle: Vec<usize> = (0..n).collect();
+    let mut rounds = vec![];
+    for _ in 0..n - 1 {
+        let round = (0..n / 2)
+            .map(|i| (circle[i], circle[n - 1 - i]))
+            .filter(|&(a, b)| a < entrants && b < entrants)
+            .collect();
+        rounds.push(round);
+        // The first one stays in place and everyone else moves
+        let last = circle.pop().unwrap();
+        circle.insert(1, last);
+    }
+    rounds
+}
+
+/// Runs tournaments between strategies of a registry. Game `i` of a tournament and its
+/// strategies use the seed `seed + i` so the results are the same when a tournament is run again
+pub struct Tournament<'a> {
+    registry: &'a Registry,
+    rules: RuleSet,
+    entrants: Vec<String>,
+    seed: u64,
+}
+
+impl<'a> Tournament<'a> {
+    /// Errors if there are fewer than 2 entrants, if one is not in the registry or if
+    /// an entrant is there twice
+    pub fn new(registry: &'a Registry, rules: RuleSet, entrants: &[&str], seed: u64) -> Result<Tournament<'a>, Error> {
+        if entrants.len() < 2 {
+            bail!("A tournament needs at least 2 entrants");
+        }
+        for (i, id) in entrants.iter().enumerate() {
+            registry.strategy(id, &rules, seed)?;
+            if entrants[..i].contains(id) {
+                bail!("{} is entered twice", id);
+            }
+        }
+        Ok(Tournament { registry, rules, entrants: entrants.iter().map(|e| e.to_string()).collect(), seed })
+    }
+
+    fn play(&self, south_north: &str, east_west: &str, game_number: usize) -> Result<Fixture, Error> {
+        let seed = self.seed.wrapping_add(game_number as u64);
+        let mut strategies = self.registry.team_strategies(south_north, east_west, &self.rules, seed)?;
+        let mut game = Game::from_seed(self.rules.clone(), seed);
+        let winner = match run_game(&mut game, &mut strategies)? {
+            Team::SouthNorth => south_north,
+            Team::EastWest => east_west,
+        };
+        let totals = game.standing().totals;
+        Ok(Fixture {
+            south_north: south_north.to_string(),
+            east_west: east_west.to_string(),
+            seed,
+            winner: winner.to_string(),
+            totals: (totals[Team::SouthNorth], totals[Team::EastWest]),
+        })
+    }
+
+    fn standings(&self, rounds: &[Vec<Fixture>]) -> Vec<TournamentStanding> {
+        let mut standings: HashMap<&str, TournamentStanding> = self.entrants
+            .iter()
+            .map(|id| {
+                let standing = TournamentStanding { id: id.clone(), played: 0, won: 0, points_for: 0, points_against: 0 };
+                (id.as_str(), standing)
+            })
+            .collect();
+        for fixture in rounds.iter().flat_map(|r| r.iter()) {
+            let sides = [
+                (&fixture.south_north, fixture.totals.0, fixture.totals.1),
+                (&fixture.east_west, fixture.totals.1, fixture.totals.0),
+            ];
+            for &(id, points_for, points_against) in &sides {
+                let standing = standings.get_mut(id.as_str()).unwrap();
+                standing.played += 1;
+                standing.points_for += points_for;
+                standing.points_against += points_against;
+                if fixture.winner == *id {
+                    standing.won += 1;
+                }
+            }
+        }
+
+        let mut standings: Vec<TournamentStanding> = standings.into_values().collect();
+        let difference = |s: &TournamentStanding| s.points_for as isize - s.points_against as isize;
+        standings.sort_by(|a, b| {
+            b.won.cmp(&a.won).then(difference(b).cmp(&difference(a))).then(a.id.cmp(&b.id))
+        });
+        standings
+    }
+
+    /// Every entrant plays every other one `games` times, swapping seats every game
+    pub fn round_robin(&self, games: usize) -> Result<TournamentResult, Error> {
+        let mut rounds = vec![];
+        let mut game_number = 0;
+        for pairings in round_robin_pairings(self.entrants.len()) {
+            let mut round = vec![];
+            for (a, b) in pairings {
+                for i in 0..games {
+                    let (south_north, east_west) = if i % 2 == 0 { (a, b) } else { (b, a) };
+                    round.push(self.play(&self.entrants[south_north], &self.entrants[east_west], game_number)?);
+                    game_number += 1;
+                }
+            }
+            rounds.push(round);
+        }
+        let standings = self.standings(&rounds);
+        Ok(TournamentResult { rounds, standings })
+    }
+
+    /// Entrants are paired in order, winners advance to the next round until one is left.
+    /// With an odd number of entrants left, the last one goes through without playing.
+    /// The standings are ordered by how far each entrant went
+    pub fn knockout(&self) -> Result<TournamentResult, Error> {
+        let mut remaining = self.entrants.clone();
+        let mut rounds = vec![];
+        let mut eliminated = vec![];
+        let mut game_number = 0;
+        while remaining.len() > 1 {
+            let mut round = vec![];
+            let mut next = vec![];
+            for pair in remaining.chunks(2) {
+                if pair.len() == 1 {
+                    next.push(pair[0].clone());
+                    continue;
+                }
+                let fixture = self.play(&pair[0], &pair[1], game_number)?;
+                game_number += 1;
+                let loser = if fixture.winner == pair[0] { &pair[1] } else { &pair[0] };
+                eliminated.push(loser.clone());
+                next.push(fixture.winner.clone());
+                round.push(fixture);
+            }
+            rounds.push(round);
+            remaining = next;
+        }
+
+        // Winner first, then the ones eliminated the latest
+        eliminated.push(remaining.pop().unwrap());
+        eliminated.reverse();
+        let mut standings = self.standings(&rounds);
+        standings.sort_by_key(|s| eliminated.iter().position(|id| *id == s.id));
+        Ok(TournamentResult { rounds, standings })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strategy::Strategy;
+
+    #[test]
+    fn round_robin_pairs_everyone_once() {
+        for n in 2..8 {
+            let rounds = round_robin_pairings(n);
+            let mut pairs: Vec<(usize, usize)> = rounds.iter().flat_map(|r| r.iter()).map(|&(a, b)| (a.min(b), a.max(b))).collect();
+            pairs.sort();
+            pairs.dedup();
+            assert_eq!(pairs.len(), n * (n - 1) / 2);
+            assert_eq!(rounds.len(), n - 1 + n % 2);
+        }
+        assert!(round_robin_pairings(1).is_empty());
+    }
+
+    #[test]
+    fn can_run_tournaments() {
+        let registry = Registry::default();
+        let rules = RuleSet::default();
+        assert!(Tournament::new(&registry, rules.clone(), &["random"], 0).is_err());
+        assert!(Tournament::new(&registry, rules.clone(), &["random", "nope"], 0).is_err());
+        assert!(Tournament::new(&registry, rules.clone(), &["random", "random"], 0).is_err());
+
+        let tournament = Tournament::new(&registry, rules.clone(), &["random", "heuristic"], 0).unwrap();
+        let result = tournament.round_robin(4).unwrap();
+        assert_eq!(result.rounds.len(), 1);
+        assert_eq!(result.rounds[0].len(), 4);
+        assert_eq!(result.standings[0].played, 4);
+        assert_eq!(result.winner(), Some("heuristic"));
+        assert_eq!(tournament.round_robin(4).unwrap().rounds, result.rounds);
+
+        let tournament = Tournament::new(&registry, rules, &["random", "heuristic", "traditional"], 0);
+        assert!(tournament.is_err());
+    }
+
+    #[test]
+    fn knockout_advances_winners() {
+        let mut registry = Registry::default();
+        registry.register_strategy("heuristic-2", |_, _| Box::new(::bots::HeuristicBot::new()) as Box<dyn Strategy>).unwrap();
+        let tournament = Tournament::new(&registry, RuleSet::default(), &["random", "heuristic", "heuristic-2"], 1).unwrap();
+        let result = tournament.knockout().unwrap();
+        // random vs heuristic, then the winner vs heuristic-2 that had a bye
+        assert_eq!(result.rounds.len(), 2);
+        assert_eq!(result.rounds[0].len(), 1);
+        assert_eq!(result.rounds[1].len(), 1);
+        assert_eq!(result.winner(), Some(result.rounds[1][0].winner.as_str()));
+        assert_eq!(result.standings.len(), 3);
+        assert_eq!(result.standings[2].id, if result.rounds[0][0].winner == "random" { "heuristic" } else { "random" });
+    }
+}
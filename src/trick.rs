@@ -0,0 +1,129 @@
+use std::ops::Deref;
+use std::slice;
+
+use cards::{Card, Suit};
+use players::Player;
+
+
+/// Which player wins the trick: the highest trump if any trump was played,
+/// the highest card of the suit led otherwise
+pub(crate) fn trick_winner(trick: &[(Player, Card)], trump: Suit) -> Player {
+    let mut best = trick[0];
+    for &(player, card) in &trick[1..] {
+        if card.beats(best.1, trump) {
+            best = (player, card);
+        }
+    }
+    best.0
+}
+
+/// The cards played in a trick with who played them, in order.
+/// It derefs to a slice of `(Player, Card)` for everything else
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Trick {
+    cards: Vec<(Player, Card)>,
+}
+
+impl Trick {
+    pub fn new() -> Trick {
+        Trick::default()
+    }
+
+    /// Adds a card to the trick, without checking whether it can be played
+    pub fn push(&mut self, player: Player, card: Card) {
+        self.cards.push((player, card));
+    }
+
+    /// The player who played the first card
+    pub fn leader(&self) -> Option<Player> {
+        self.cards.first().map(|&(player, _)| player)
+    }
+
+    /// The suit of the first card, which the other players have to follow
+    pub fn lead_suit(&self) -> Option<Suit> {
+        self.cards.first().map(|&(_, card)| card.suit)
+    }
+
+    /// Everyone played a card
+    pub fn is_complete(&self) -> bool {
+        self.cards.len() == 4
+    }
+
+    /// The player winning the trick so far, `None` if no card was played yet
+    pub fn winner(&self, trump: Suit) -> Option<Player> {
+        if self.cards.is_empty() {
+            return None;
+        }
+        Some(trick_winner(&self.cards, trump))
+    }
+
+    /// Points of the cards in the trick, without the 10 points of the last trick
+    pub fn points(&self, trump: Suit) -> usize {
+        self.cards.iter().map(|&(_, card)| card.points(trump)).sum()
+    }
+
+    pub fn into_vec(self) -> Vec<(Player, Card)> {
+        self.cards
+    }
+}
+
+impl Deref for Trick {
+    type Target = [(Player, Card)];
+
+    fn deref(&self) -> &[(Player, Card)] {
+        &self.cards
+    }
+}
+
+impl<'a> IntoIterator for &'a Trick {
+    type Item = &'a (Player, Card);
+    type IntoIter = slice::Iter<'a, (Player, Card)>;
+
+    fn into_iter(self) -> slice::Iter<'a, (Player, Card)> {
+        self.cards.iter()
+    }
+}
+
+impl From<Vec<(Player, Card)>> for Trick {
+    fn from(cards: Vec<(Player, Card)>) -> Trick {
+        Trick { cards }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cards::Rank;
+
+    #[test]
+    fn can_find_trick_winner() {
+        let trick = vec![
+            (Player::South, Card::new(Suit::Spades, Rank::Ten)),
+            (Player::West, Card::new(Suit::Spades, Rank::Ace)),
+            (Player::North, Card::new(Suit::Diamonds, Rank::Ace)),
+            (Player::East, Card::new(Suit::Spades, Rank::King)),
+        ];
+        assert_eq!(trick_winner(&trick, Suit::Hearts), Player::West);
+        assert_eq!(trick_winner(&trick, Suit::Diamonds), Player::North);
+    }
+
+    #[test]
+    fn can_build_a_trick() {
+        let mut trick = Trick::new();
+        assert_eq!(trick.winner(Suit::Hearts), None);
+        assert_eq!(trick.lead_suit(), None);
+        trick.push(Player::West, Card::new(Suit::Clubs, Rank::Ten));
+        trick.push(Player::North, Card::new(Suit::Clubs, Rank::Ace));
+        assert_eq!(trick.leader(), Some(Player::West));
+        assert_eq!(trick.lead_suit(), Some(Suit::Clubs));
+        assert_eq!(trick.winner(Suit::Hearts), Some(Player::North));
+        trick.push(Player::East, Card::new(Suit::Hearts, Rank::Seven));
+        trick.push(Player::South, Card::new(Suit::Clubs, Rank::Jack));
+        assert!(trick.is_complete());
+        assert_eq!(trick.winner(Suit::Hearts), Some(Player::East));
+        assert_eq!(trick.winner(Suit::Clubs), Some(Player::South));
+        assert_eq!(trick.points(Suit::Hearts), 23);
+        assert_eq!(trick.points(Suit::Clubs), 41);
+    }
+}
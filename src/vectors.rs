@@ -0,0 +1,273 @@
+//! Test vectors for implementations of the rules in other languages, eg a client validating
+//! moves locally: for many seeded games, the state before each action, the legal actions,
+//! the action taken and the hash of the state after it.
+//!
+//! The state is encoded as text, one line per item, so it can be rebuilt exactly elsewhere:
+//!
+//! ```text
+//! rounds:<number of rounds played>
+//! totals:<N/S total>,<E/W total>
+//! turn:<player to act or ->
+//! hand:<player>:<cards in hand, space separated>      (one line per player, S W N E)
+//! bids:<player><bid>, space separated
+//! contract:<player><bid>[x2|x4] or -
+//! trick:<player><card>, space separated
+//! tricks:<winner of each trick, no separator>
+//! ```
+//!
+//! Players are `S`, `W`, `N` and `E`. A card is its rank (`7 8 9 T J Q K A`) followed by
+//! its suit (`C D H S`), eg `JH`. A bid is `pass`, `counter`, `double-counter` or
+//! its value followed by its suit, eg `80H` or `250S` for a capot. A contract taken in
+//! classic belote is `take` followed by its suit, eg `takeH`.
+//! The hash is the hex SHA-256 of that text.
+
+use rand::{Rng, SeedableRng};
+use rand::isaac::Isaac64Rng;
+
+use actions::Action;
+use bids::{Bid, Contract};
+use cards::{Card, Rank, Suit};
+use game::{Game, MatchResult};
+use players::{Player, Team};
+use rules::RuleSet;
+use sha256;
+
+
+/// One action in a seeded game
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TestVector {
+    /// Seed of the game, given to `Game::from_seed` with the default rules
+    pub seed: u64,
+    /// Index of the action in the game
+    pub step: usize,
+    /// The state before the action
+    pub state: String,
+    pub state_hash: String,
+    pub legal_actions: Vec<Action>,
+    pub action: Action,
+    /// Hash of the state after the action
+    pub next_state_hash: String,
+}
+
+pub(crate) fn player_code(player: Player) -> char {
+    match player {
+        Player::South => 'S',
+        Player::West => 'W',
+        Player::North => 'N',
+        Player::East => 'E',
+    }
+}
+
+pub(crate) fn suit_code(suit: Suit) -> char {
+    match suit {
+        Suit::Clubs => 'C',
+        Suit::Diamonds => 'D',
+        Suit::Hearts => 'H',
+        Suit::Spades => 'S',
+    }
+}
+
+pub(crate) fn card_code(card: Card) -> String {
+    let rank = match card.rank {
+        Rank::Seven => '7',
+        Rank::Eight => '8',
+        Rank::Nine => '9',
+        Rank::Ten => 'T',
+        Rank::Jack => 'J',
+        Rank::Queen => 'Q',
+        Rank::King => 'K',
+        Rank::Ace => 'A',
+    };
+    format!("{}{}", rank, suit_code(card.suit))
+}
+
+pub(crate) fn bid_code(bid: Bid, suit: Option<Suit>) -> String {
+    let bid = match bid {
+        Bid::Pass => "pass".to_string(),
+        Bid::Counter => "counter".to_string(),
+        Bid::DoubleCounter => "double-counter".to_string(),
+        bid => bid.value().to_string(),
+    };
+    match suit {
+        Some(suit) => format!("{}{}", bid, suit_code(suit)),
+        None => bid,
+    }
+}
+
+fn contract_code(contract: &Contract) -> String {
+    let counter = if contract.double_countered() {
+        "x4"
+    } else if contract.countered() {
+        "x2"
+    } else {
+        ""
+    };
+    format!(
+        "{}{}{}",
+        player_code(contract.player()),
+        match contract.bid() {
+            Some(bid) => bid_code(bid, Some(contract.suit())),
+            None => format!("take{}", suit_code(contract.suit())),
+        },
+        counter,
+    )
+}
+
+/// The code of an action as used in the JSON: `bid:80H`, `play:JH`, `take:H` or `take:refuse`
+pub fn action_code(action: Action) -> String {
+    match action {
+        Action::Bid(bid, suit) => format!("bid:{}", bid_code(bid, suit)),
+        Action::Play(card) => format!("play:{}", card_code(card)),
+        Action::Take(Some(suit)) => format!("take:{}", suit_code(suit)),
+        Action::Take(None) => "take:refuse".to_string(),
+    }
+}
+
+/// The text encoding of the state of the game described in the module documentation
+pub fn canonical_state(game: &Game) -> String {
+    let view = game.player_view(Player::South);
+    let mut lines = vec![
+        format!("rounds:{}", game.score_sheet().lines.len()),
+        format!("totals:{},{}", view.totals[Team::SouthNorth], view.totals[Team::EastWest]),
+        format!("turn:{}", game.current_player().map_or('-', player_code)),
+    ];
+    for player in Player::iterator() {
+        let hand: Vec<String> = game.player_view(*player).hand.into_iter().map(card_code).collect();
+        lines.push(format!("hand:{}:{}", player_code(*player), hand.join(" ")));
+    }
+    let bids: Vec<String> = view.bids
+        .iter()
+        .map(|&(player, bid, suit)| format!("{}{}", player_code(player), bid_code(bid, suit)))
+        .collect();
+    lines.push(format!("bids:{}", bids.join(" ")));
+    lines.push(format!("contract:{}", view.contract.as_ref().map_or("-".to_string(), contract_code)));
+    let trick: Vec<String> = view.current_trick
+        .iter()
+        .map(|&(player, card)| format!("{}{}", player_code(player), card_code(card)))
+        .collect();
+    lines.push(format!("trick:{}", trick.join(" ")));
+    let winners: String = view.tricks.iter().map(|&(_, winner)| player_code(winner)).collect();
+    lines.push(format!("tricks:{}", winners));
+    lines.join("\n")
+}
+
+/// The hex SHA-256 of `canonical_state`
+pub fn state_hash(game: &Game) -> String {
+    sha256::digest(canonical_state(game).as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Plays `games` games with seeds starting at `first_seed`, picking random legal actions,
+/// and records a test vector for every action
+pub fn generate_test_vectors(first_seed: u64, games: usize) -> Vec<TestVector> {
+    let mut vectors = vec![];
+    for seed in first_seed..first_seed + games as u64 {
+        let mut game = Game::from_seed(RuleSet::default(), seed);
+        let mut rng = Isaac64Rng::from_seed(&[seed]);
+        game.new_round();
+
+        let mut step = 0;
+        while let MatchResult::Ongoing | MatchResult::ExtraDeal = game.standing().result {
+            let player = match game.current_player() {
+                Some(p) => p,
+                None => break,
+            };
+            let state = canonical_state(&game);
+            let hash = state_hash(&game);
+            let legal_actions = game.legal_actions();
+            let action = *rng.choose(&legal_actions).unwrap();
+            // can't fail, the action is one of the legal ones
+            game.apply(player, action).unwrap();
+            vectors.push(TestVector {
+                seed,
+                step,
+                state,
+                state_hash: hash,
+                legal_actions,
+                action,
+                next_state_hash: state_hash(&game),
+            });
+            step += 1;
+        }
+    }
+    vectors
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The test vectors as a JSON array, one vector per line
+pub fn to_json(vectors: &[TestVector]) -> String {
+    let lines: Vec<String> = vectors
+        .iter()
+        .map(|v| {
+            let legal_actions: Vec<String> = v.legal_actions.iter().map(|a| json_string(&action_code(*a))).collect();
+            format!(
+                "{{\"seed\":{},\"step\":{},\"state\":{},\"state_hash\":{},\"legal_actions\":[{}],\"action\":{},\"next_state_hash\":{}}}",
+                v.seed,
+                v.step,
+                json_string(&v.state),
+                json_string(&v.state_hash),
+                legal_actions.join(","),
+                json_string(&action_code(v.action)),
+                json_string(&v.next_state_hash),
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", lines.join(",\n"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_encode_state() {
+        let mut game = Game::from_seed(RuleSet::default(), 0);
+        game.new_round();
+        game.bid(Player::South, Bid::Eighty, Some(Suit::Hearts)).unwrap();
+        let state = canonical_state(&game);
+        let lines: Vec<&str> = state.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "rounds:0");
+        assert_eq!(lines[1], "totals:0,0");
+        assert_eq!(lines[2], "turn:W");
+        assert!(lines[3].starts_with("hand:S:"));
+        assert_eq!(lines[3].split(' ').count(), 8);
+        assert_eq!(lines[7], "bids:S80H");
+        assert_eq!(lines[8], "contract:-");
+        assert_eq!(state_hash(&game).len(), 64);
+    }
+
+    #[test]
+    fn vectors_are_reproducible() {
+        let vectors = generate_test_vectors(3, 2);
+        assert_eq!(vectors, generate_test_vectors(3, 2));
+        assert_eq!(vectors[0].step, 0);
+        assert_eq!(vectors[0].legal_actions.len(), 41);
+        for pair in vectors.windows(2) {
+            assert!(pair[0].legal_actions.contains(&pair[0].action));
+            if pair[0].seed == pair[1].seed {
+                assert_eq!(pair[0].next_state_hash, pair[1].state_hash);
+            }
+        }
+
+        let json = to_json(&vectors[..2]);
+        assert!(json.starts_with("[\n{\"seed\":3,\"step\":0,\"state\":\"rounds:0\\ntotals:0,0\\nturn:S\\n"));
+        assert_eq!(json.lines().count(), 4);
+    }
+}
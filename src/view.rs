@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use actions::Action;
+use bids::{Bid, BidSet, Contract};
+use card_set::CardSet;
+use cards::{Card, Rank, Suit};
+use hand::Hand;
+use players::Player;
+use team_scores::TeamScores;
+
+
+/// Everything a player is allowed to know about the game at a given time
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PlayerView {
+    pub player: Player,
+    /// The cards in the hand of the player
+    pub hand: Hand,
+    /// All the bids of the current round so far
+    pub bids: Vec<(Player, Bid, Option<Suit>)>,
+    /// The bids the player can make, empty if it's not their turn to bid
+    pub available_bids: BidSet,
+    /// The contract of the round, once the bidding phase is over
+    pub contract: Option<Contract>,
+    /// The card turned up during the taking phase of classic belote
+    pub turned_up: Option<Card>,
+    /// The cards played in the current trick, in order
+    pub current_trick: Vec<(Player, Card)>,
+    /// The tricks already played in the round with who won them
+    pub tricks: Vec<(Vec<(Player, Card)>, Player)>,
+    /// The cards the player can play, empty if it's not their turn to play
+    pub playable_cards: Vec<Card>,
+    /// Total score of each team
+    pub totals: TeamScores,
+    /// Everything the player can do, empty if it's not their turn.
+    /// The position of an action in this list is its number for `Game::apply_index`
+    pub legal_actions: Vec<Action>,
+}
+
+/// What someone watching the game without playing can see: everything public, no hands
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SpectatorView {
+    /// How many cards each player has left
+    pub hand_sizes: HashMap<Player, usize>,
+    pub bids: Vec<(Player, Bid, Option<Suit>)>,
+    pub contract: Option<Contract>,
+    pub turned_up: Option<Card>,
+    pub current_trick: Vec<(Player, Card)>,
+    pub tricks: Vec<(Vec<(Player, Card)>, Player)>,
+    pub totals: TeamScores,
+    /// The cards each player had in the last round played, once it is over,
+    /// if the view was asked to reveal them
+    pub last_round_hands: Option<HashMap<Player, Vec<Card>>>,
+}
+
+/// Length of `PlayerView::encode`
+pub const ENCODING_LEN: usize = 32 + 4 * 32 + 4 * 32 + 4 + 4 * 4 + 4 + 4 + 1 + 1 + 1 + 1 + 2 + 2 + 32;
+
+/// Position of the card in a block of 32 features
+fn card_index(card: Card) -> usize {
+    card.suit as usize * 8 + card.rank as usize
+}
+
+impl PlayerView {
+    /// The suits the player can bid as trump according to the legal actions.
+    /// Views without any legal action, eg built by hand, allow every suit
+    pub fn biddable_suits(&self) -> Vec<Suit> {
+        if self.legal_actions.is_empty() {
+            return Suit::iterator().cloned().collect();
+        }
+        Suit::iterator()
+            .filter(|s| self.legal_actions.iter().any(|a| matches!(*a, Action::Bid(_, Some(suit)) if suit == **s)))
+            .cloned()
+            .collect()
+    }
+
+    /// Position of the player relative to the one of the view:
+    /// 0 for the player, 1 for the next one, 2 for the partner and 3 for the previous one
+    fn relative(&self, player: Player) -> usize {
+        (player.index() + 4 - self.player.index()) % 4
+    }
+
+    /// A fixed-size encoding of the view as numbers between 0 and 1, for machine learning.
+    /// Players are always relative to the one of the view (see below) and cards use
+    /// blocks of 32 features, the card at `suit * 8 + rank` in enum order.
+    /// The layout is stable, new features will only ever be added at the end:
+    ///
+    /// | Offset | Size   | Features                                                    |
+    /// |--------|--------|-------------------------------------------------------------|
+    /// | 0      | 32     | cards in hand                                               |
+    /// | 32     | 4 × 32 | cards played in previous tricks of the round, by player     |
+    /// | 160    | 4 × 32 | cards of the current trick, by player                       |
+    /// | 288    | 4      | highest bid value of each player / 250                      |
+    /// | 292    | 4 × 4  | suit of the last suit bid of each player, by suit           |
+    /// | 308    | 4      | player who has the contract                                 |
+    /// | 312    | 4      | trump suit                                                  |
+    /// | 316    | 1      | contract value / 250                                        |
+    /// | 317    | 1      | contract countered                                          |
+    /// | 318    | 1      | contract double countered                                   |
+    /// | 319    | 1      | bidding phase (no contract yet)                             |
+    /// | 320    | 2      | totals of the team and the opponents / 1000, capped at 1    |
+    /// | 322    | 2      | tricks won by the team and the opponents this round / 8     |
+    /// | 324    | 32     | card turned up during the taking phase of classic belote    |
+    ///
+    /// Players are 0 for the player, 1 for the next one, 2 for the partner, 3 for the previous one.
+    pub fn encode(&self) -> Vec<f32> {
+        let mut features = vec![0.0; ENCODING_LEN];
+
+        for card in &self.hand {
+            features[card_index(*card)] = 1.0;
+        }
+        for (trick, _) in &self.tricks {
+            for &(player, card) in trick {
+                features[32 + 32 * self.relative(player) + card_index(card)] = 1.0;
+            }
+        }
+        for &(player, card) in &self.current_trick {
+            features[160 + 32 * self.relative(player) + card_index(card)] = 1.0;
+        }
+
+        for &(player, bid, suit) in &self.bids {
+            let relative = self.relative(player);
+            let value = bid.value() as f32 / 250.0;
+            if value > features[288 + relative] {
+                features[288 + relative] = value;
+            }
+            if let Some(suit) = suit {
+                for i in 0..4 {
+                    features[292 + 4 * relative + i] = 0.0;
+                }
+                features[292 + 4 * relative + suit as usize] = 1.0;
+            }
+        }
+
+        match self.contract {
+            Some(ref contract) => {
+                features[308 + self.relative(contract.player())] = 1.0;
+                features[312 + contract.suit() as usize] = 1.0;
+                features[316] = contract.value() as f32 / 250.0;
+                features[317] = if contract.countered() { 1.0 } else { 0.0 };
+                features[318] = if contract.double_countered() { 1.0 } else { 0.0 };
+            },
+            None => features[319] = 1.0,
+        }
+
+        let team = self.player.team();
+        for (i, t) in [team, team.opponent()].iter().enumerate() {
+            let total = self.totals[*t] as f32;
+            features[320 + i] = (total / 1000.0).min(1.0);
+            let tricks = self.tricks.iter().filter(|&&(_, winner)| winner.team() == *t).count();
+            features[322 + i] = tricks as f32 / 8.0;
+        }
+
+        if let Some(card) = self.turned_up {
+            features[324 + card_index(card)] = 1.0;
+        }
+
+        features
+    }
+
+    /// All the cards played so far in the round, current trick included
+    pub fn played_cards(&self) -> CardSet {
+        self.tricks
+            .iter()
+            .flat_map(|(trick, _)| trick.iter())
+            .chain(self.current_trick.iter())
+            .map(|&(_, card)| card)
+            .collect()
+    }
+
+    /// The cards still in the hands of the other players
+    pub fn unseen_cards(&self) -> CardSet {
+        self.played_cards().union(CardSet::from_cards(&self.hand)).complement()
+    }
+
+    /// How many cards of the suit the other players still hold between them
+    pub fn remaining_in_suit(&self, suit: Suit) -> usize {
+        self.unseen_cards().intersection(CardSet::suit(suit)).len()
+    }
+
+    /// Whether the card is still in the hand of another player
+    pub fn is_out(&self, card: Card) -> bool {
+        self.unseen_cards().contains(card)
+    }
+
+    /// The Jack and Nine of trump and the other aces that the other players still hold.
+    /// Empty before the contract is known
+    pub fn key_cards_out(&self) -> Vec<Card> {
+        let trump = match self.contract {
+            Some(ref contract) => contract.suit(),
+            None => return vec![],
+        };
+        let unseen = self.unseen_cards();
+        let trumps = [Card::new(trump, Rank::Jack), Card::new(trump, Rank::Nine)];
+        let aces = trump.others().iter().map(|s| Card::new(*s, Rank::Ace)).collect::<Vec<_>>();
+        trumps.iter().chain(aces.iter()).cloned().filter(|c| unseen.contains(*c)).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Bid;
+    use game::Game;
+    use rules::{RuleSet, Variant};
+
+    #[test]
+    fn can_encode_view() {
+        let mut game = Game::default();
+        game.new_round();
+        let encoded = game.player_view(Player::South).encode();
+        assert_eq!(encoded.len(), ENCODING_LEN);
+        assert_eq!(encoded[..32].iter().sum::<f32>(), 8.0);
+        assert_eq!(encoded[319], 1.0);
+
+        game.bid(Player::South, Bid::Eighty, Some(Suit::Hearts)).unwrap();
+        game.bid(Player::West, Bid::Pass, None).unwrap();
+        game.bid(Player::North, Bid::Pass, None).unwrap();
+        game.bid(Player::East, Bid::Pass, None).unwrap();
+        let card = game.player_view(Player::South).playable_cards[0];
+        game.play(Player::South, card).unwrap();
+
+        // North sees the card played by South as the one of its partner
+        let encoded = game.player_view(Player::North).encode();
+        assert_eq!(encoded[160 + 2 * 32 + card_index(card)], 1.0);
+        assert_eq!(encoded[288 + 2], 80.0 / 250.0);
+        assert_eq!(encoded[292 + 2 * 4 + Suit::Hearts as usize], 1.0);
+        assert_eq!(encoded[308 + 2], 1.0);
+        assert_eq!(encoded[312 + Suit::Hearts as usize], 1.0);
+        assert_eq!(encoded[319], 0.0);
+        assert!(encoded.iter().all(|f| *f >= 0.0 && *f <= 1.0));
+        assert!(encoded[324..].iter().all(|f| *f == 0.0));
+
+        let mut game = Game::from_seed(RuleSet { variant: Variant::Classic, ..RuleSet::default() }, 3);
+        game.new_round();
+        let turned_up = game.turned_up().unwrap();
+        let encoded = game.player_view(Player::East).encode();
+        assert_eq!(encoded[324 + card_index(turned_up)], 1.0);
+        assert_eq!(encoded[324..].iter().sum::<f32>(), 1.0);
+    }
+
+    #[test]
+    fn can_count_cards() {
+        let mut game = Game::from_seed(RuleSet::default(), 7);
+        game.new_round();
+        assert!(game.player_view(Player::South).key_cards_out().is_empty());
+        game.bid(Player::South, Bid::Eighty, Some(Suit::Hearts)).unwrap();
+        game.bid(Player::West, Bid::Pass, None).unwrap();
+        game.bid(Player::North, Bid::Pass, None).unwrap();
+        game.bid(Player::East, Bid::Pass, None).unwrap();
+        for _ in 0..6 {
+            let player = game.current_player().unwrap();
+            let card = game.player_view(player).playable_cards[0];
+            game.play(player, card).unwrap();
+        }
+
+        let view = game.player_view(Player::South);
+        let played = view.played_cards();
+        assert_eq!(played, game.current_round().unwrap().played_cards());
+        assert_eq!(played.len(), 6);
+        let unseen = view.unseen_cards();
+        assert_eq!(unseen.len(), 32 - 6 - view.hand.len());
+        let total: usize = Suit::iterator().map(|s| view.remaining_in_suit(*s)).sum();
+        assert_eq!(total, unseen.len());
+        for card in view.key_cards_out() {
+            assert!(view.is_out(card) && !played.contains(card) && !view.hand.contains(&card));
+        }
+        let jack = Card::new(Suit::Hearts, Rank::Jack);
+        assert_eq!(view.key_cards_out().contains(&jack), unseen.contains(jack));
+    }
+}
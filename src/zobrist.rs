@@ -0,0 +1,92 @@
+//! Zobrist hashing of the playing state: every (player, card in hand), (player, card in the
+//! current trick) and player to act has a fixed random 64-bit key, and the hash of a position
+//! is the XOR of the keys that apply. Playing a card only XORs a few keys in and out, so
+//! `Round` keeps its hash up to date as it goes.
+//!
+//! The keys never change, so hashes can be compared between processes, eg a client and a server.
+
+use cards::Card;
+use players::Player;
+use round::Round;
+
+
+const HAND: usize = 0;
+const TRICK: usize = 4 * 32;
+const TURN: usize = 2 * 4 * 32;
+
+const KEYS: [u64; 2 * 4 * 32 + 4] = keys();
+
+/// splitmix64 from a fixed seed, good enough to spread the keys
+const fn keys() -> [u64; 2 * 4 * 32 + 4] {
+    let mut keys = [0; 2 * 4 * 32 + 4];
+    let mut state: u64 = 0x636f_6e74_7265_6521;
+    let mut i = 0;
+    while i < keys.len() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+pub(crate) fn hand_key(player: Player, card: Card) -> u64 {
+    KEYS[HAND + player.index() * 32 + card.index() as usize]
+}
+
+pub(crate) fn trick_key(player: Player, card: Card) -> u64 {
+    KEYS[TRICK + player.index() * 32 + card.index() as usize]
+}
+
+pub(crate) fn turn_key(player: Player) -> u64 {
+    KEYS[TURN + player.index()]
+}
+
+/// The hash of the round computed from scratch, which is what `Round::zobrist_hash`
+/// maintains incrementally
+pub fn hash(round: &Round) -> u64 {
+    let mut hash = turn_key(round.next_player());
+    for player in Player::iterator() {
+        for card in round.hand(*player).iter() {
+            hash ^= hand_key(*player, *card);
+        }
+    }
+    for &(player, card) in round.current_trick() {
+        hash ^= trick_key(player, card);
+    }
+    hash
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bids::Contract;
+    use cards::Suit;
+    use deck::Deck;
+    use rules::RuleSet;
+    use std::collections::HashMap;
+
+    #[test]
+    fn incremental_hash_matches_the_full_one() {
+        let mut deck = Deck::new();
+        deck.shuffle_with_seed(4);
+        let hands: HashMap<Player, Vec<Card>> = Player::iterator().cloned().zip(deck.deal().iter().cloned()).collect();
+        let rules = RuleSet::default();
+        let mut round = Round::with_hands(Contract::taken(Player::South, Suit::Spades), Player::South, hands);
+        let start = round.zobrist_hash();
+        assert_eq!(start, hash(&round));
+
+        let mut seen = vec![start];
+        while !round.is_over() {
+            let player = round.next_player();
+            let card = round.playable_cards(player, &rules)[0];
+            round.play(player, card, &rules).unwrap();
+            assert_eq!(round.zobrist_hash(), hash(&round));
+            assert!(!seen.contains(&round.zobrist_hash()));
+            seen.push(round.zobrist_hash());
+        }
+    }
+}